@@ -0,0 +1,60 @@
+//! Scanner mask demo
+//!
+//! Sweeps a scanner bar across a horizontal strip using the shipping
+//! `apply_scanner_mask` and prints which pixels light at each bar position.
+//!
+//! Run with: `cargo run --example scanner_demo`
+
+use lightspeed::model::PixelStrip;
+use lightspeed::scanner::apply_scanner_mask;
+
+const MASK_X: f32 = 0.5;
+const MASK_Y: f32 = 0.5;
+const MASK_WIDTH: f32 = 0.3;
+const MASK_HEIGHT: f32 = 0.2;
+const BAR_WIDTH: f32 = 0.05;
+
+fn main() {
+    // 100 pixels from x=0.0 to x=0.99 along y=0.5
+    let template = PixelStrip {
+        id: 1,
+        pixel_count: 100,
+        x: 0.0,
+        y: 0.5,
+        spacing: 0.01,
+        data: vec![[0, 0, 0]; 100],
+        ..PixelStrip::default()
+    };
+
+    println!("Mask {}x{} at ({}, {}), bar width {}", MASK_WIDTH, MASK_HEIGHT, MASK_X, MASK_Y, BAR_WIDTH);
+    for step in 0..=8 {
+        let bar_position = -1.0 + step as f32 * 0.25;
+        let mut strips = vec![template.clone()];
+        apply_scanner_mask(
+            MASK_X, MASK_Y,
+            MASK_WIDTH, MASK_HEIGHT,
+            0.0,
+            bar_position,
+            BAR_WIDTH,
+            true,
+            [0, 255, 255],
+            &mut strips,
+        );
+
+        let row: String = strips[0].data.iter().map(|p| if p[1] > 0 { '#' } else { '.' }).collect();
+        println!("{:>5.2} |{}|", bar_position, row);
+
+        // The sweep is scaled so the bar's edge, not its center, meets the mask edge
+        let lit: Vec<usize> = (0..100).filter(|&i| strips[0].data[i][1] > 0).collect();
+        assert!(!lit.is_empty(), "bar at {} lit nothing", bar_position);
+        if bar_position == -1.0 {
+            assert_eq!(lit[0], 35, "bar should start at the mask's left edge");
+            assert!(lit.contains(&44), "full bar width should be inside the mask");
+        }
+        if bar_position == 1.0 {
+            assert_eq!(*lit.last().unwrap(), 65, "bar should end at the mask's right edge");
+            assert!(lit.contains(&56), "full bar width should be inside the mask");
+        }
+        assert!(lit.iter().all(|&i| (35..=65).contains(&i)), "bar leaked outside the mask");
+    }
+}
@@ -1,6 +1,6 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use log::{info, debug, warn};
 
 /// Audio state shared between the audio callback and the engine
@@ -24,13 +24,118 @@ impl Default for AudioState {
     }
 }
 
+/// Lock-free f32 shared with the audio callback: the bits live in an AtomicU32, so
+/// readers never block the callback and there is no lock to poison
+#[derive(Default)]
+pub struct AtomicF32(AtomicU32);
+
+impl AtomicF32 {
+    pub fn new(value: f32) -> Self {
+        Self(AtomicU32::new(value.to_bits()))
+    }
+
+    pub fn load(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    pub fn store(&self, value: f32) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+}
+
+/// Per-callback smoothing of the "level" follower: symmetric, so it rises and falls gently.
+/// Suits meters and anything that should breathe with the music.
+pub const LEVEL_SMOOTHING: f32 = 0.3;
+/// Attack and release of the "transient" follower: it jumps to a hit within a callback or two
+/// and falls away over a few hundred ms, so kicks and snares read as distinct punches.
+pub const TRANSIENT_ATTACK: f32 = 0.9;
+pub const TRANSIENT_RELEASE: f32 = 0.05;
+
+/// One step of an envelope follower towards `input`, using `attack` when rising and
+/// `release` when falling (fractions of the gap closed per step)
+pub fn follow_envelope(prev: f32, input: f32, attack: f32, release: f32) -> f32 {
+    let coeff = if input > prev { attack } else { release };
+    prev + (input - prev) * coeff
+}
+
+/// Frequency bands tracked alongside the overall volume, in `AudioListener::bands` order
+pub const BAND_NAMES: [&str; 3] = ["bass", "mid", "treble"];
+
+/// Crossover frequencies between bass/mid and mid/treble, in Hz
+const BAND_CROSSOVERS: (f32, f32) = (200.0, 2000.0);
+
+/// Splits mono samples into bass/mid/treble with two one-pole low-passes. Cheap enough for
+/// the audio callback; the bands overlap gently rather than having FFT-sharp edges.
+pub struct BandSplitter {
+    low_coeff: f32,
+    high_coeff: f32,
+    low: f32,  // Low-pass state at the bass crossover
+    high: f32, // Low-pass state at the treble crossover
+}
+
+impl BandSplitter {
+    pub fn new(sample_rate: u32) -> Self {
+        let coeff = |hz: f32| 1.0 - (-std::f32::consts::TAU * hz / sample_rate.max(1) as f32).exp();
+        Self {
+            low_coeff: coeff(BAND_CROSSOVERS.0),
+            high_coeff: coeff(BAND_CROSSOVERS.1),
+            low: 0.0,
+            high: 0.0,
+        }
+    }
+
+    /// RMS of each band over `samples`, in `BAND_NAMES` order
+    pub fn process(&mut self, samples: impl Iterator<Item = f32>) -> [f32; 3] {
+        let mut sums = [0.0f32; 3];
+        let mut count = 0;
+        for x in samples {
+            self.low += (x - self.low) * self.low_coeff;
+            self.high += (x - self.high) * self.high_coeff;
+            let bands = [self.low, self.high - self.low, x - self.high];
+            for (sum, b) in sums.iter_mut().zip(bands) {
+                *sum += b * b;
+            }
+            count += 1;
+        }
+        sums.map(|s| if count > 0 { (s / count as f32).sqrt() } else { 0.0 })
+    }
+}
+
+/// Where the energy sits across the bands: 0 = all bass, 0.5 = mid (or evenly spread),
+/// 1 = all treble. A cheap stand-in for the spectral centroid; silence reads 0.5.
+pub fn band_centroid(levels: [f32; 3]) -> f32 {
+    let total: f32 = levels.iter().sum();
+    if total <= f32::EPSILON {
+        return 0.5;
+    }
+    (levels[1] * 0.5 + levels[2]) / total
+}
+
 pub struct AudioListener {
     _stream: cpal::Stream, // Keep stream alive
+    pub device_name: String,
     pub peak_detected: Arc<AtomicBool>,
-    pub current_volume: Arc<Mutex<f32>>,
+    pub current_volume: Arc<AtomicF32>, // "level" follower of the RMS, see LEVEL_SMOOTHING
+    pub transient: Arc<AtomicF32>,      // Fast-attack/slow-release follower, see TRANSIENT_ATTACK
+    pub bands: Arc<[AtomicF32; 3]>, // Smoothed RMS per band, see BAND_NAMES
     pub audio_state: Arc<Mutex<AudioState>>,
 }
 
+impl AudioListener {
+    /// Smoothed level of a band by name; "volume" is the overall level, "transient" the
+    /// overall transient envelope and "centroid" the `band_centroid` (already 0..1).
+    /// Unknown names read 0.
+    pub fn level(&self, band: &str) -> f32 {
+        match band {
+            "volume" => return self.current_volume.load(),
+            "transient" => return self.transient.load(),
+            "centroid" => return band_centroid(std::array::from_fn(|i| self.bands[i].load())),
+            _ => {}
+        }
+        BAND_NAMES.iter().position(|b| *b == band).map_or(0.0, |i| self.bands[i].load())
+    }
+}
+
 impl AudioListener {
     pub fn new() -> Option<Self> {
         debug!("[AUDIO] Initializing audio input...");
@@ -51,6 +156,8 @@ impl AudioListener {
             }
         };
 
+        let device_name = device.name().unwrap_or_else(|_| "Unknown device".to_string());
+
         let config = match device.default_input_config() {
             Ok(c) => {
                 debug!("[AUDIO] Config: {} Hz, {} channels, {:?}",
@@ -64,15 +171,21 @@ impl AudioListener {
         };
 
         let peak_flag = Arc::new(AtomicBool::new(false));
-        let volume_level = Arc::new(Mutex::new(0.0));
+        let volume_level = Arc::new(AtomicF32::new(0.0));
+        let transient_level = Arc::new(AtomicF32::new(0.0));
+        let band_levels: Arc<[AtomicF32; 3]> = Arc::new(Default::default());
         let audio_state = Arc::new(Mutex::new(AudioState::default()));
 
         let peak_clone = peak_flag.clone();
         let vol_clone = volume_level.clone();
+        let transient_clone = transient_level.clone();
+        let bands_clone = band_levels.clone();
         let state_clone = audio_state.clone();
 
         // Get sample rate for cooldown calculation
         let sample_rate = config.sample_rate().0;
+        let channels = config.channels().max(1) as usize;
+        let mut splitter = BandSplitter::new(sample_rate);
 
         let err_fn = |err| eprintln!("Audio stream error: {}", err);
 
@@ -80,7 +193,14 @@ impl AudioListener {
             cpal::SampleFormat::F32 => {
                 match device.build_input_stream(
                     &config.into(),
-                    move |data: &[f32], _: &_| check_audio(data, &peak_clone, &vol_clone, &state_clone, sample_rate),
+                    move |data: &[f32], _: &_| {
+                        check_audio(data, &peak_clone, &vol_clone, &transient_clone, &state_clone, sample_rate);
+                        // Bands are filtered on a mono downmix, smoothed like the volume
+                        let mono = data.chunks(channels).map(|frame| frame.iter().sum::<f32>() / frame.len() as f32);
+                        for (level, rms) in bands_clone.iter().zip(splitter.process(mono)) {
+                            level.store(follow_envelope(level.load(), rms, LEVEL_SMOOTHING, LEVEL_SMOOTHING));
+                        }
+                    },
                     err_fn
                 ) {
                     Ok(s) => s,
@@ -105,8 +225,11 @@ impl AudioListener {
 
         Some(Self {
             _stream: stream,
+            device_name,
             peak_detected: peak_flag,
             current_volume: volume_level,
+            transient: transient_level,
+            bands: band_levels,
             audio_state,
         })
     }
@@ -115,7 +238,8 @@ impl AudioListener {
 fn check_audio(
     data: &[f32],
     peak_flag: &Arc<AtomicBool>,
-    vol_lock: &Arc<Mutex<f32>>,
+    volume: &AtomicF32,
+    transient: &AtomicF32,
     state_lock: &Arc<Mutex<AudioState>>,
     sample_rate: u32,
 ) {
@@ -127,16 +251,14 @@ fn check_audio(
     let sum_squares: f32 = data.iter().map(|&s| s * s).sum();
     let rms = (sum_squares / data.len() as f32).sqrt();
 
-    // Update legacy volume for backward compatibility
-    if let Ok(mut v) = vol_lock.try_lock() {
-        // Less aggressive smoothing for more responsive display
-        *v = (*v * 0.7) + (rms * 0.3);
-    }
+    // Both envelopes follow the same RMS (this callback is the only writer)
+    volume.store(follow_envelope(volume.load(), rms, LEVEL_SMOOTHING, LEVEL_SMOOTHING));
+    transient.store(follow_envelope(transient.load(), rms, TRANSIENT_ATTACK, TRANSIENT_RELEASE));
 
     // Update audio state with onset detection
     if let Ok(mut state) = state_lock.try_lock() {
         // Smooth volume for UI (less aggressive decay)
-        state.current_volume = state.current_volume * 0.7 + rms * 0.3;
+        state.current_volume = follow_envelope(state.current_volume, rms, LEVEL_SMOOTHING, LEVEL_SMOOTHING);
 
         // Decrement cooldown
         if state.onset_cooldown > 0 {
@@ -180,3 +302,61 @@ fn check_audio(
         peak_flag.store(false, Ordering::Relaxed);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atomic_volume_concurrent_reads_and_writes() {
+        let volume = Arc::new(AtomicF32::new(0.0));
+        let writer = {
+            let volume = volume.clone();
+            std::thread::spawn(move || {
+                for i in 0..100_000 {
+                    volume.store(i as f32);
+                }
+            })
+        };
+
+        // Reads never block on the writer and only ever see whole values it stored
+        while !writer.is_finished() {
+            let v = volume.load();
+            assert!((0.0..100_000.0).contains(&v) && v.fract() == 0.0);
+        }
+        writer.join().unwrap();
+        assert_eq!(volume.load(), 99_999.0);
+    }
+
+    #[test]
+    fn test_transient_follower_is_punchier_than_level() {
+        let (mut level, mut transient) = (0.0f32, 0.0f32);
+        let step = |level: &mut f32, transient: &mut f32, rms: f32| {
+            *level = follow_envelope(*level, rms, LEVEL_SMOOTHING, LEVEL_SMOOTHING);
+            *transient = follow_envelope(*transient, rms, TRANSIENT_ATTACK, TRANSIENT_RELEASE);
+        };
+        // A hit: the transient follower is nearly there after one block, the level isn't
+        step(&mut level, &mut transient, 1.0);
+        assert!(transient >= 0.9 && level < 0.5);
+        // Silence after it: the transient decays slowly, the level drops fast
+        for _ in 0..5 {
+            step(&mut level, &mut transient, 0.0);
+        }
+        assert!(transient > 0.6 && level < 0.1, "transient {} level {}", transient, level);
+    }
+
+    #[test]
+    fn test_band_splitter_separates_low_and_high_tones() {
+        let sample_rate = 48_000;
+        let tone = |hz: f32| (0..4800).map(move |i| (std::f32::consts::TAU * hz * i as f32 / sample_rate as f32).sin());
+
+        let bass = BandSplitter::new(sample_rate).process(tone(60.0));
+        assert!(bass[0] > bass[2] * 4.0, "60 Hz should land in bass: {:?}", bass);
+
+        let treble = BandSplitter::new(sample_rate).process(tone(8000.0));
+        assert!(treble[2] > treble[0] * 4.0, "8 kHz should land in treble: {:?}", treble);
+
+        assert!(band_centroid(bass) < 0.2 && band_centroid(treble) > 0.8);
+        assert_eq!(band_centroid([0.0; 3]), 0.5);
+    }
+}
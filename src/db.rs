@@ -1,14 +1,74 @@
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, params};
 use crate::model::*;
 use std::path::Path;
 use anyhow::{Result, Context};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+/// Schema version stored in the metadata table and stamped into JSON exports
+pub const SCHEMA_VERSION: &str = "1";
 
 /// Database connection manager for Lightspeed configuration
 pub struct Database {
     conn: Connection,
 }
 
+/// Rig-only export: the physical layout and its output settings, without scenes or masks
+#[derive(serde::Serialize, serde::Deserialize)]
+pub struct LayoutFile {
+    #[serde(default)]
+    pub schema_version: Option<String>,
+    pub strips: Vec<PixelStrip>,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub bind_address: Option<String>,
+    #[serde(default)]
+    pub audio: AudioConfig,
+}
+
+/// Dry-run summary of what an import would change
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    pub strips_added: usize,
+    pub strips_replaced: usize,
+    pub strips_removed: usize,
+    pub scenes_added: usize,
+    pub scenes_replaced: usize,
+    pub scenes_removed: usize,
+    pub masks_added: usize,
+    pub masks_removed: usize,
+    pub fixtures_added: usize,
+    pub fixtures_replaced: usize,
+    pub fixtures_removed: usize,
+    /// Incoming fixtures that will get a fresh id (included in fixtures_added)
+    pub fixtures_remapped: usize,
+    /// Human-readable descriptions of ids that already exist (merge mode)
+    pub conflicts: Vec<String>,
+    /// Schema version found in the file, if any
+    pub file_schema_version: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Last main window placement, in egui points
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowGeometry {
+    /// Outer (frame) top-left corner; None if the platform didn't report it
+    pub position: Option<[f32; 2]>,
+    pub inner_size: [f32; 2],
+    pub maximized: bool,
+}
+
+/// Saved canvas pan/zoom, recalled with a number key. Slot `LAST_VIEW_SLOT` holds the view at exit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewBookmark {
+    pub slot: u8,
+    pub offset: [f32; 2],
+    pub scale: f32,
+}
+
+/// Bookmark slot the view is saved to on exit and restored from on launch
+pub const LAST_VIEW_SLOT: u8 = 0;
+
 impl Database {
     /// Open or create database at the specified path
     pub fn open(path: &Path) -> Result<Self> {
@@ -42,6 +102,16 @@ impl Database {
             );
             CREATE INDEX IF NOT EXISTS idx_strips_universe ON strips(universe);
 
+            CREATE TABLE IF NOT EXISTS fixtures (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                universe INTEGER NOT NULL,
+                start_channel INTEGER NOT NULL,
+                channels_json TEXT NOT NULL,
+                x REAL NOT NULL,
+                y REAL NOT NULL
+            );
+
             CREATE TABLE IF NOT EXISTS masks (
                 id INTEGER PRIMARY KEY,
                 mask_type TEXT NOT NULL,
@@ -75,6 +145,12 @@ impl Database {
                 FOREIGN KEY (scene_id) REFERENCES scenes(id) ON DELETE CASCADE
             );
 
+            CREATE TABLE IF NOT EXISTS universe_routes (
+                universe INTEGER PRIMARY KEY,
+                use_multicast INTEGER NOT NULL,
+                unicast_ip TEXT NOT NULL
+            );
+
             CREATE TABLE IF NOT EXISTS app_config (
                 id INTEGER PRIMARY KEY CHECK (id = 1),
                 selected_scene_id INTEGER,
@@ -93,23 +169,224 @@ impl Database {
                 FOREIGN KEY (selected_scene_id) REFERENCES scenes(id) ON DELETE SET NULL
             );
 
+            CREATE TABLE IF NOT EXISTS window_state (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                x REAL,
+                y REAL,
+                width REAL NOT NULL,
+                height REAL NOT NULL,
+                maximized INTEGER NOT NULL DEFAULT 0
+            );
+
+            CREATE TABLE IF NOT EXISTS strip_presets (
+                name TEXT PRIMARY KEY,
+                pixel_count INTEGER NOT NULL,
+                spacing REAL NOT NULL,
+                color_order TEXT NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS view_bookmarks (
+                slot INTEGER PRIMARY KEY,
+                offset_x REAL NOT NULL,
+                offset_y REAL NOT NULL,
+                scale REAL NOT NULL
+            );
+
             CREATE TABLE IF NOT EXISTS metadata (
                 key TEXT PRIMARY KEY,
                 value TEXT NOT NULL
             );
 
-            INSERT OR IGNORE INTO metadata (key, value) VALUES ('schema_version', '1');
             INSERT OR IGNORE INTO metadata (key, value) VALUES ('migrated_from_json', '0');
             INSERT OR IGNORE INTO app_config (id) VALUES (1);
             "#
         )?;
+        self.conn.execute(
+            "INSERT OR IGNORE INTO metadata (key, value) VALUES ('schema_version', ?1)",
+            [SCHEMA_VERSION],
+        )?;
         
         // Ensure new columns exist for existing databases
         // Ignore error if column already exists
         let _ = self.conn.execute("ALTER TABLE scenes ADD COLUMN global_effects_json TEXT", []);
         let _ = self.conn.execute("ALTER TABLE scenes ADD COLUMN category TEXT NOT NULL DEFAULT 'Uncategorized'", []);
         let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN midi_enabled INTEGER NOT NULL DEFAULT 1", []);
+        let _ = self.conn.execute("ALTER TABLE masks ADD COLUMN anchor_strip INTEGER", []);
+        let _ = self.conn.execute("ALTER TABLE strips ADD COLUMN lut_json TEXT", []);
+        let _ = self.conn.execute("ALTER TABLE strips ADD COLUMN channel_prefix_json TEXT", []);
+        let _ = self.conn.execute("ALTER TABLE strips ADD COLUMN pixel_offset INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE strips ADD COLUMN dimmer_curve TEXT NOT NULL DEFAULT 'linear'", []);
+        let _ = self.conn.execute("ALTER TABLE strips ADD COLUMN channels_per_pixel INTEGER NOT NULL DEFAULT 3", []);
+        let _ = self.conn.execute("ALTER TABLE strips ADD COLUMN luma_weights_json TEXT", []);
+        let _ = self.conn.execute("ALTER TABLE scenes ADD COLUMN master_intensity REAL NOT NULL DEFAULT 1.0", []);
+        let _ = self.conn.execute("ALTER TABLE scenes ADD COLUMN compositing TEXT NOT NULL DEFAULT 'add'", []);
+        let _ = self.conn.execute("ALTER TABLE scenes ADD COLUMN page INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE scenes ADD COLUMN audio_reactive INTEGER NOT NULL DEFAULT 1", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN network_multicast_ttl INTEGER NOT NULL DEFAULT 1", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN network_multicast_interface TEXT", []);
+        let _ = self.conn.execute("ALTER TABLE scene_masks ADD COLUMN anchor_strip INTEGER", []);
+        let _ = self.conn.execute("ALTER TABLE masks ADD COLUMN target_strips_json TEXT", []);
+        let _ = self.conn.execute("ALTER TABLE scene_masks ADD COLUMN target_strips_json TEXT", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN no_selection_mode TEXT NOT NULL DEFAULT 'black'", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN fallback_scene_id INTEGER", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN network_max_universes INTEGER NOT NULL DEFAULT 64", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN output_smoothing REAL NOT NULL DEFAULT 0.0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN audio_beat_offset REAL NOT NULL DEFAULT 0.0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN fire_midi_btn INTEGER", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN fire_midi_is_cc INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN network_reverse_universe_order INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN sync_loss_behavior TEXT NOT NULL DEFAULT 'continue'", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN panic_midi_btn INTEGER", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN panic_midi_is_cc INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN max_flash_hz REAL NOT NULL DEFAULT 3.0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN max_flash_contrast REAL NOT NULL DEFAULT 0.8", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN zero_based_channels INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN canvas_theme TEXT NOT NULL DEFAULT 'dark'", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN light_ui INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN network_redundant_output INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN network_redundant_interface TEXT", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN audio_flywheel_jump_beats REAL NOT NULL DEFAULT 0.5", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN audio_flywheel_recovery_secs REAL NOT NULL DEFAULT 1.0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN audio_flywheel_nudge REAL NOT NULL DEFAULT 0.1", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN audio_phase_correction REAL NOT NULL DEFAULT 0.4", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN audio_phase_decay REAL NOT NULL DEFAULT 0.02", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN audio_source_priority_json TEXT", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN engine_tick_hz REAL NOT NULL DEFAULT 120.0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN mask_compositing TEXT NOT NULL DEFAULT 'add'", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN launchpad_page INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN heartbeat_midi_btn INTEGER", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN heartbeat_midi_is_cc INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN fit_padding REAL NOT NULL DEFAULT 0.1", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN fit_margin REAL NOT NULL DEFAULT 0.9", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN performance_mode INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN network_muted_universes_json TEXT", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN network_source_name TEXT NOT NULL DEFAULT 'Lightspeed'", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN network_source_cid TEXT NOT NULL DEFAULT ''", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN master_brightness REAL NOT NULL DEFAULT 1.0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN master_speed REAL NOT NULL DEFAULT 1.0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN cc_mappings_json TEXT", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN canvas_unit_meters REAL NOT NULL DEFAULT 0.0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN audio_manual_bpm REAL NOT NULL DEFAULT 120.0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN audio_auto_latch_bpm INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN mask_overlays TEXT NOT NULL DEFAULT 'auto'", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN audio_beats_per_bar INTEGER NOT NULL DEFAULT 4", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN freeze_midi_btn INTEGER", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN freeze_midi_is_cc INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN staged_editing INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN audio_trigger_action TEXT NOT NULL DEFAULT 'off'", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN audio_trigger_threshold REAL NOT NULL DEFAULT 0.6", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN audio_trigger_cooldown_secs REAL NOT NULL DEFAULT 8.0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN network_universe_mode TEXT NOT NULL DEFAULT 'relative'", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN feedback_midi INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN feedback_osc INTEGER NOT NULL DEFAULT 0", []);
+        let _ = self.conn.execute("ALTER TABLE app_config ADD COLUMN feedback_osc_target TEXT NOT NULL DEFAULT '127.0.0.1:9000'", []);
+
+        Ok(())
+    }
+
+    /// Read the schema version recorded in the metadata table
+    pub fn schema_version(&self) -> Result<String> {
+        let version: String = self.conn.query_row(
+            "SELECT value FROM metadata WHERE key = 'schema_version'",
+            [],
+            |row| row.get(0)
+        )?;
+        Ok(version)
+    }
+
+    /// Read the last saved main window placement, if any
+    pub fn load_window_geometry(&self) -> Result<Option<WindowGeometry>> {
+        let geometry = self.conn.query_row(
+            "SELECT x, y, width, height, maximized FROM window_state WHERE id = 1",
+            [],
+            |row| {
+                let x: Option<f32> = row.get(0)?;
+                let y: Option<f32> = row.get(1)?;
+                Ok(WindowGeometry {
+                    position: x.zip(y).map(|(x, y)| [x, y]),
+                    inner_size: [row.get(2)?, row.get(3)?],
+                    maximized: row.get::<_, i32>(4)? != 0,
+                })
+            }
+        ).optional()?;
+        Ok(geometry)
+    }
 
+    /// Store the sACN CID alone, leaving the rest of the config untouched
+    pub fn save_source_cid(&self, cid: &str) -> Result<()> {
+        self.conn.execute("UPDATE app_config SET network_source_cid = ?1 WHERE id = 1", [cid])?;
+        Ok(())
+    }
+
+    /// Remember the main window placement for the next launch
+    pub fn save_window_geometry(&self, geometry: &WindowGeometry) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO window_state (id, x, y, width, height, maximized)
+             VALUES (1, ?1, ?2, ?3, ?4, ?5)",
+            params![
+                geometry.position.map(|p| p[0]),
+                geometry.position.map(|p| p[1]),
+                geometry.inner_size[0],
+                geometry.inner_size[1],
+                geometry.maximized as i32,
+            ]
+        )?;
+        Ok(())
+    }
+
+    /// User-saved strip types, by name
+    pub fn load_strip_presets(&self) -> Result<Vec<StripPreset>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT name, pixel_count, spacing, color_order FROM strip_presets ORDER BY name"
+        )?;
+        let presets = stmt.query_map([], |row| {
+            Ok(StripPreset {
+                name: row.get(0)?,
+                pixel_count: row.get::<_, i64>(1)?.clamp(1, MAX_STRIP_PIXELS as i64) as usize,
+                spacing: row.get(2)?,
+                color_order: row.get(3)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(presets)
+    }
+
+    /// Save a strip type, replacing any preset with the same name
+    pub fn save_strip_preset(&self, preset: &StripPreset) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO strip_presets (name, pixel_count, spacing, color_order)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![preset.name, preset.pixel_count, preset.spacing, preset.color_order],
+        )?;
+        Ok(())
+    }
+
+    pub fn delete_strip_preset(&self, name: &str) -> Result<()> {
+        self.conn.execute("DELETE FROM strip_presets WHERE name = ?1", [name])?;
+        Ok(())
+    }
+
+    /// Canvas view bookmarks, by slot
+    pub fn load_view_bookmarks(&self) -> Result<Vec<ViewBookmark>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT slot, offset_x, offset_y, scale FROM view_bookmarks ORDER BY slot"
+        )?;
+        let bookmarks = stmt.query_map([], |row| {
+            Ok(ViewBookmark {
+                slot: row.get(0)?,
+                offset: [row.get(1)?, row.get(2)?],
+                scale: row.get(3)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(bookmarks)
+    }
+
+    /// Save a view bookmark, replacing whatever was in its slot
+    pub fn save_view_bookmark(&self, bookmark: &ViewBookmark) -> Result<()> {
+        self.conn.execute(
+            "INSERT OR REPLACE INTO view_bookmarks (slot, offset_x, offset_y, scale)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![bookmark.slot, bookmark.offset[0], bookmark.offset[1], bookmark.scale],
+        )?;
         Ok(())
     }
 
@@ -141,12 +418,16 @@ impl Database {
         tx.execute("DELETE FROM scenes", [])?;
         tx.execute("DELETE FROM masks", [])?;
         tx.execute("DELETE FROM strips", [])?;
+        tx.execute("DELETE FROM fixtures", [])?;
+        tx.execute("DELETE FROM universe_routes", [])?;
 
         // Migrate strips
         for strip in &state.strips {
+            let lut_json = strip.lut.as_ref().map(serde_json::to_string).transpose()?;
+            let channel_prefix_json = serde_json::to_string(&strip.channel_prefix)?;
             tx.execute(
-                "INSERT INTO strips (id, universe, start_channel, pixel_count, x, y, spacing, flipped, color_order)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                "INSERT INTO strips (id, universe, start_channel, pixel_count, x, y, spacing, flipped, color_order, lut_json, channel_prefix_json, pixel_offset, dimmer_curve, channels_per_pixel, luma_weights_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
                 params![
                     strip.id as i64,
                     strip.universe,
@@ -157,17 +438,49 @@ impl Database {
                     strip.spacing,
                     if strip.flipped { 1 } else { 0 },
                     strip.color_order,
+                    lut_json,
+                    channel_prefix_json,
+                    strip.pixel_offset as i64,
+                    strip.dimmer_curve,
+                    strip.channels_per_pixel,
+                    serde_json::to_string(&strip.luma_weights)?,
+                ],
+            )?;
+        }
+
+        // Migrate fixtures
+        for fixture in &state.fixtures {
+            tx.execute(
+                "INSERT INTO fixtures (id, name, universe, start_channel, channels_json, x, y)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    fixture.id as i64,
+                    fixture.name,
+                    fixture.universe,
+                    fixture.start_channel,
+                    serde_json::to_string(&fixture.channels)?,
+                    fixture.x,
+                    fixture.y,
                 ],
             )?;
         }
 
+        // Migrate per-universe routing overrides
+        for route in &state.network.universe_routes {
+            tx.execute(
+                "INSERT OR REPLACE INTO universe_routes (universe, use_multicast, unicast_ip) VALUES (?1, ?2, ?3)",
+                params![route.universe, if route.use_multicast { 1 } else { 0 }, route.unicast_ip],
+            )?;
+        }
+
         // Migrate global masks
         for mask in &state.masks {
             let params_json = serde_json::to_string(&mask.params)?;
+            let target_strips_json = serde_json::to_string(&mask.target_strips)?;
             tx.execute(
-                "INSERT INTO masks (id, mask_type, x, y, params_json)
-                 VALUES (?1, ?2, ?3, ?4, ?5)",
-                params![mask.id as i64, mask.mask_type, mask.x, mask.y, params_json],
+                "INSERT INTO masks (id, mask_type, x, y, params_json, anchor_strip, target_strips_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![mask.id as i64, mask.mask_type, mask.x, mask.y, params_json, mask.anchor_strip.map(|v| v as i64), target_strips_json],
             )?;
         }
 
@@ -179,8 +492,8 @@ impl Database {
             let global_effects_json = serde_json::to_string(&scene.global_effects)?;
 
             tx.execute(
-                "INSERT INTO scenes (id, name, kind, category, global_effect_json, global_effects_json, launchpad_btn, launchpad_is_cc, launchpad_color)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                "INSERT INTO scenes (id, name, kind, category, global_effect_json, global_effects_json, launchpad_btn, launchpad_is_cc, launchpad_color, master_intensity, compositing, page, audio_reactive)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
                 params![
                     scene.id as i64,
                     scene.name,
@@ -191,15 +504,20 @@ impl Database {
                     scene.launchpad_btn.map(|v| v as i64),
                     if scene.launchpad_is_cc { 1 } else { 0 },
                     scene.launchpad_color.map(|v| v as i64),
+                    scene.master_intensity,
+                    scene.compositing,
+                    scene.page,
+                    if scene.audio_reactive { 1 } else { 0 },
                 ],
             )?;
 
             // Migrate scene masks
             for (idx, mask) in scene.masks.iter().enumerate() {
                 let params_json = serde_json::to_string(&mask.params)?;
+                let target_strips_json = serde_json::to_string(&mask.target_strips)?;
                 tx.execute(
-                    "INSERT INTO scene_masks (scene_id, mask_id, mask_type, x, y, params_json, display_order)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    "INSERT INTO scene_masks (scene_id, mask_id, mask_type, x, y, params_json, display_order, anchor_strip, target_strips_json)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
                     params![
                         scene.id as i64,
                         mask.id as i64,
@@ -208,6 +526,8 @@ impl Database {
                         mask.y,
                         params_json,
                         idx as i64,
+                        mask.anchor_strip.map(|v| v as i64),
+                        target_strips_json,
                     ],
                 )?;
             }
@@ -228,7 +548,62 @@ impl Database {
                 audio_hybrid_sync = ?10,
                 audio_sensitivity = ?11,
                 layout_locked = ?12,
-                midi_enabled = ?13
+                midi_enabled = ?13,
+                network_multicast_ttl = ?14,
+                network_multicast_interface = ?15,
+                no_selection_mode = ?16,
+                fallback_scene_id = ?17,
+                network_max_universes = ?18,
+                output_smoothing = ?19,
+                audio_beat_offset = ?20,
+                fire_midi_btn = ?21,
+                fire_midi_is_cc = ?22,
+                network_reverse_universe_order = ?23,
+                sync_loss_behavior = ?24,
+                panic_midi_btn = ?25,
+                panic_midi_is_cc = ?26,
+                max_flash_hz = ?27,
+                max_flash_contrast = ?28,
+                zero_based_channels = ?29,
+                canvas_theme = ?30,
+                light_ui = ?31,
+                network_redundant_output = ?32,
+                network_redundant_interface = ?33,
+                audio_flywheel_jump_beats = ?34,
+                audio_flywheel_recovery_secs = ?35,
+                audio_flywheel_nudge = ?36,
+                audio_phase_correction = ?37,
+                audio_phase_decay = ?38,
+                audio_source_priority_json = ?39,
+                engine_tick_hz = ?40,
+                mask_compositing = ?41,
+                launchpad_page = ?42,
+                heartbeat_midi_btn = ?43,
+                heartbeat_midi_is_cc = ?44,
+                fit_padding = ?45,
+                fit_margin = ?46,
+                performance_mode = ?47,
+                network_muted_universes_json = ?48,
+                network_source_name = ?49,
+                network_source_cid = ?50,
+                master_brightness = ?51,
+                master_speed = ?52,
+                cc_mappings_json = ?53,
+                canvas_unit_meters = ?54,
+                audio_manual_bpm = ?55,
+                audio_auto_latch_bpm = ?56,
+                mask_overlays = ?57,
+                audio_beats_per_bar = ?58,
+                freeze_midi_btn = ?59,
+                freeze_midi_is_cc = ?60,
+                staged_editing = ?61,
+                audio_trigger_action = ?62,
+                audio_trigger_threshold = ?63,
+                audio_trigger_cooldown_secs = ?64,
+                network_universe_mode = ?65,
+                feedback_midi = ?66,
+                feedback_osc = ?67,
+                feedback_osc_target = ?68
              WHERE id = 1",
             params![
                 state.selected_scene_id.map(|id| id as i64),
@@ -244,6 +619,61 @@ impl Database {
                 state.audio.sensitivity,
                 if state.layout_locked { 1 } else { 0 },
                 if state.midi_enabled { 1 } else { 0 },
+                state.network.multicast_ttl,
+                state.network.multicast_interface,
+                state.no_selection_mode,
+                state.fallback_scene_id.map(|id| id as i64),
+                state.network.max_universes,
+                state.output_smoothing,
+                state.audio.beat_offset,
+                state.fire_midi_btn,
+                if state.fire_midi_is_cc { 1 } else { 0 },
+                if state.network.reverse_universe_order { 1 } else { 0 },
+                state.sync_loss_behavior,
+                state.panic_midi_btn,
+                if state.panic_midi_is_cc { 1 } else { 0 },
+                state.max_flash_hz,
+                state.max_flash_contrast,
+                if state.zero_based_channels { 1 } else { 0 },
+                state.canvas_theme,
+                if state.light_ui { 1 } else { 0 },
+                if state.network.redundant_output { 1 } else { 0 },
+                state.network.redundant_interface,
+                state.audio.flywheel_jump_beats,
+                state.audio.flywheel_recovery_secs,
+                state.audio.flywheel_nudge,
+                state.audio.phase_correction,
+                state.audio.phase_decay,
+                serde_json::to_string(&state.audio.source_priority)?,
+                state.engine_tick_hz,
+                state.mask_compositing,
+                state.launchpad_page,
+                state.heartbeat_midi_btn,
+                if state.heartbeat_midi_is_cc { 1 } else { 0 },
+                state.fit_padding,
+                state.fit_margin,
+                if state.performance_mode { 1 } else { 0 },
+                serde_json::to_string(&state.network.muted_universes)?,
+                state.network.source_name,
+                state.network.source_cid,
+                state.master_brightness,
+                state.master_speed,
+                serde_json::to_string(&state.cc_mappings)?,
+                state.canvas_unit_meters,
+                state.audio.manual_bpm,
+                if state.audio.auto_latch_bpm { 1 } else { 0 },
+                state.mask_overlays,
+                state.audio.beats_per_bar,
+                state.freeze_midi_btn,
+                if state.freeze_midi_is_cc { 1 } else { 0 },
+                if state.staged_editing { 1 } else { 0 },
+                state.audio.trigger_action,
+                state.audio.trigger_threshold,
+                state.audio.trigger_cooldown_secs,
+                state.network.universe_mode,
+                if state.feedback_midi { 1 } else { 0 },
+                if state.feedback_osc { 1 } else { 0 },
+                state.feedback_osc_target,
             ],
         )?;
 
@@ -255,11 +685,14 @@ impl Database {
     pub fn load_state(&self) -> Result<AppState> {
         // Load strips
         let mut stmt = self.conn.prepare(
-            "SELECT id, universe, start_channel, pixel_count, x, y, spacing, flipped, color_order FROM strips ORDER BY id"
+            "SELECT id, universe, start_channel, pixel_count, x, y, spacing, flipped, color_order, lut_json, channel_prefix_json, pixel_offset, dimmer_curve, channels_per_pixel, luma_weights_json FROM strips ORDER BY id"
         )?;
         let strips = stmt.query_map([], |row| {
-            let pixel_count: usize = row.get(3)?;
-            Ok(PixelStrip {
+            let pixel_count: usize = row.get::<_, i64>(3)?.max(0) as usize;
+            // A corrupt LUT falls back to identity rather than failing the whole load
+            let lut = row.get::<_, Option<String>>(9)?
+                .and_then(|json| serde_json::from_str::<ColorLut>(&json).ok());
+            let mut strip = PixelStrip {
                 id: row.get::<_, i64>(0)? as u64,
                 universe: row.get(1)?,
                 start_channel: row.get(2)?,
@@ -269,31 +702,80 @@ impl Database {
                 spacing: row.get(6)?,
                 flipped: row.get::<_, i64>(7)? != 0,
                 color_order: row.get(8)?,
-                data: vec![[0, 0, 0]; pixel_count], // Initialize with black pixels
+                lut,
+                dimmer_curve: row.get(12)?,
+                channel_prefix: row.get::<_, Option<String>>(10)?
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
+                pixel_offset: row.get::<_, i64>(11)?.max(0) as usize,
+                channels_per_pixel: row.get::<_, i64>(13)?.clamp(0, 255) as u8,
+                luma_weights: row.get::<_, Option<String>>(14)?
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_else(default_luma_weights),
+                data: Vec::new(), // Sized to pixel_count by sanitize
+            };
+            strip.sanitize();
+            Ok(strip)
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        // Load per-universe routing overrides
+        let mut stmt = self.conn.prepare(
+            "SELECT universe, use_multicast, unicast_ip FROM universe_routes ORDER BY universe"
+        )?;
+        let universe_routes = stmt.query_map([], |row| {
+            Ok(UniverseRoute {
+                universe: row.get(0)?,
+                use_multicast: row.get::<_, i64>(1)? != 0,
+                unicast_ip: row.get(2)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+
+        // Load fixtures
+        let mut stmt = self.conn.prepare(
+            "SELECT id, name, universe, start_channel, channels_json, x, y FROM fixtures ORDER BY id"
+        )?;
+        let fixtures = stmt.query_map([], |row| {
+            let channels_json: String = row.get(4)?;
+            Ok(Fixture {
+                id: row.get::<_, i64>(0)? as u64,
+                name: row.get(1)?,
+                universe: row.get(2)?,
+                start_channel: row.get::<_, u16>(3)?.clamp(1, 512),
+                channels: serde_json::from_str(&channels_json).unwrap_or_default(),
+                x: row.get(5)?,
+                y: row.get(6)?,
+                color: [0, 0, 0],
             })
         })?.collect::<Result<Vec<_>, _>>()?;
 
         // Load global masks
         let mut stmt = self.conn.prepare(
-            "SELECT id, mask_type, x, y, params_json FROM masks ORDER BY id"
+            "SELECT id, mask_type, x, y, params_json, anchor_strip, target_strips_json FROM masks ORDER BY id"
         )?;
         let masks = stmt.query_map([], |row| {
             let params_json: String = row.get(4)?;
             let params: HashMap<String, serde_json::Value> = serde_json::from_str(&params_json)
                 .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
 
-            Ok(Mask {
+            let mut mask = Mask {
                 id: row.get::<_, i64>(0)? as u64,
                 mask_type: row.get(1)?,
                 x: row.get(2)?,
                 y: row.get(3)?,
                 params,
-            })
+                anchor_strip: row.get::<_, Option<i64>>(5)?.map(|v| v as u64),
+                target_strips: row.get::<_, Option<String>>(6)?
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
+            };
+            mask.sanitize();
+            mask.prune_targets(&strips);
+            Ok(mask)
         })?.collect::<Result<Vec<_>, _>>()?;
 
         // Load scenes
         let mut stmt = self.conn.prepare(
-            "SELECT id, name, kind, category, global_effect_json, global_effects_json, launchpad_btn, launchpad_is_cc, launchpad_color FROM scenes ORDER BY id"
+            "SELECT id, name, kind, category, global_effect_json, global_effects_json, launchpad_btn, launchpad_is_cc, launchpad_color, master_intensity, compositing, page, audio_reactive FROM scenes ORDER BY id"
         )?;
         let scene_rows: Vec<_> = stmt.query_map([], |row| {
             Ok((
@@ -306,40 +788,62 @@ impl Database {
                 row.get::<_, Option<i64>>(6)?,
                 row.get::<_, i64>(7)?,
                 row.get::<_, Option<i64>>(8)?,
+                row.get::<_, f32>(9)?,
+                row.get::<_, String>(10)?,
+                row.get::<_, i64>(11)?,
+                row.get::<_, i64>(12)?,
             ))
         })?.collect::<Result<Vec<_>, _>>()?;
 
         let mut scenes = Vec::new();
-        for (id, name, kind, category, global_json, global_effects_json, launchpad_btn, launchpad_is_cc, launchpad_color) in scene_rows {
+        for (id, name, kind, category, global_json, global_effects_json, launchpad_btn, launchpad_is_cc, launchpad_color, master_intensity, compositing, page, audio_reactive) in scene_rows {
             // Load scene masks
             let mut stmt = self.conn.prepare(
-                "SELECT mask_id, mask_type, x, y, params_json FROM scene_masks WHERE scene_id = ?1 ORDER BY display_order"
+                "SELECT mask_id, mask_type, x, y, params_json, anchor_strip, target_strips_json FROM scene_masks WHERE scene_id = ?1 ORDER BY display_order"
             )?;
             let scene_masks = stmt.query_map([id as i64], |row| {
                 let params_json: String = row.get(4)?;
                 let params: HashMap<String, serde_json::Value> = serde_json::from_str(&params_json)
                     .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
 
-                Ok(Mask {
+                let mut mask = Mask {
                     id: row.get::<_, i64>(0)? as u64,
                     mask_type: row.get(1)?,
                     x: row.get(2)?,
                     y: row.get(3)?,
                     params,
-                })
+                    anchor_strip: row.get::<_, Option<i64>>(5)?.map(|v| v as u64),
+                    target_strips: row.get::<_, Option<String>>(6)?
+                        .and_then(|json| serde_json::from_str(&json).ok())
+                        .unwrap_or_default(),
+                };
+                mask.sanitize();
+                mask.prune_targets(&strips);
+                Ok(mask)
             })?.collect::<Result<Vec<_>, _>>()?;
 
-            let global = global_json
+            let mut global: Option<GlobalEffect> = global_json
                 .map(|json| serde_json::from_str(&json))
                 .transpose()
                 .context("Failed to parse global effect JSON")?;
 
-            let global_effects = if let Some(json) = global_effects_json {
+            let mut global_effects: Vec<GlobalEffectConfig> = if let Some(json) = global_effects_json {
                 serde_json::from_str(&json).unwrap_or_default()
             } else {
                 Vec::new() // Default for old DBs
             };
 
+            // MIGRATION: the single deprecated `global` effect becomes the scene's base layer
+            if global_effects.is_empty() {
+                if let Some(old_global) = global.take() {
+                    global_effects.push(GlobalEffectConfig {
+                        effect: old_global,
+                        targets: None, // Apply to all
+                    });
+                    log::info!("Migrated scene '{}' global effect", name);
+                }
+            }
+
             scenes.push(Scene {
                 id,
                 name,
@@ -351,6 +855,10 @@ impl Database {
                 launchpad_btn: launchpad_btn.map(|v| v as u8),
                 launchpad_is_cc: launchpad_is_cc != 0,
                 launchpad_color: launchpad_color.map(|v| v as u8),
+                master_intensity,
+                compositing,
+                page: page.clamp(0, SCENE_PAGES as i64 - 1) as u8,
+                audio_reactive: audio_reactive != 0,
             });
         }
 
@@ -369,10 +877,66 @@ impl Database {
             audio_sensitivity,
             layout_locked,
             midi_enabled,
+            network_multicast_ttl,
+            network_multicast_interface,
+            no_selection_mode,
+            fallback_scene_id,
+            network_max_universes,
+            output_smoothing,
+            audio_beat_offset,
+            fire_midi_btn,
+            fire_midi_is_cc,
+            network_reverse_universe_order,
+            sync_loss_behavior,
+            panic_midi_btn,
+            panic_midi_is_cc,
+            max_flash_hz,
+            max_flash_contrast,
+            zero_based_channels,
+            canvas_theme,
+            light_ui,
+            network_redundant_output,
+            network_redundant_interface,
+            audio_flywheel_jump_beats,
+            audio_flywheel_recovery_secs,
+            audio_flywheel_nudge,
+            audio_phase_correction,
+            audio_phase_decay,
+            audio_source_priority_json,
+            engine_tick_hz,
+            mask_compositing,
+            launchpad_page,
+            heartbeat_midi_btn,
+            heartbeat_midi_is_cc,
+            fit_padding,
+            fit_margin,
+            performance_mode,
+            network_muted_universes_json,
+            network_source_name,
+            network_source_cid,
+            master_brightness,
+            master_speed,
+            cc_mappings_json,
+            canvas_unit_meters,
+            audio_manual_bpm,
+            audio_auto_latch_bpm,
+            mask_overlays,
+            audio_beats_per_bar,
+            freeze_midi_btn,
+            freeze_midi_is_cc,
+            staged_editing,
+            audio_trigger_action,
+            audio_trigger_threshold,
+            audio_trigger_cooldown_secs,
+            network_universe_mode,
+            feedback_midi,
+            feedback_osc,
+            feedback_osc_target,
         ) = self.conn.query_row(
             "SELECT selected_scene_id, network_use_multicast, network_unicast_ip, network_universe,
                     bind_address, mode, effect, audio_latency_ms, audio_use_flywheel,
-                    audio_hybrid_sync, audio_sensitivity, layout_locked, midi_enabled
+                    audio_hybrid_sync, audio_sensitivity, layout_locked, midi_enabled,
+                    network_multicast_ttl, network_multicast_interface, no_selection_mode, fallback_scene_id, network_max_universes, output_smoothing, audio_beat_offset, fire_midi_btn, fire_midi_is_cc, network_reverse_universe_order, sync_loss_behavior, panic_midi_btn, panic_midi_is_cc, max_flash_hz, max_flash_contrast, zero_based_channels, canvas_theme, light_ui, network_redundant_output, network_redundant_interface, audio_flywheel_jump_beats, audio_flywheel_recovery_secs, audio_flywheel_nudge, audio_phase_correction, audio_phase_decay, audio_source_priority_json, engine_tick_hz, mask_compositing, launchpad_page, heartbeat_midi_btn, heartbeat_midi_is_cc, fit_padding, fit_margin, performance_mode, network_muted_universes_json, network_source_name, network_source_cid, master_brightness, master_speed, cc_mappings_json, canvas_unit_meters, audio_manual_bpm, audio_auto_latch_bpm, mask_overlays, audio_beats_per_bar, freeze_midi_btn, freeze_midi_is_cc, staged_editing, audio_trigger_action, audio_trigger_threshold, audio_trigger_cooldown_secs, network_universe_mode, feedback_midi, feedback_osc, feedback_osc_target
              FROM app_config WHERE id = 1",
             [],
             |row| {
@@ -390,6 +954,61 @@ impl Database {
                     row.get::<_, f32>(10)?,
                     row.get::<_, i64>(11)?,
                     row.get::<_, i64>(12)?,
+                    row.get::<_, u32>(13)?,
+                    row.get::<_, Option<String>>(14)?,
+                    row.get::<_, String>(15)?,
+                    row.get::<_, Option<i64>>(16)?,
+                    row.get::<_, u16>(17)?,
+                    row.get::<_, f32>(18)?,
+                    row.get::<_, f32>(19)?,
+                    row.get::<_, Option<u8>>(20)?,
+                    row.get::<_, i64>(21)?,
+                    row.get::<_, i32>(22)?,
+                    row.get::<_, String>(23)?,
+                    row.get::<_, Option<u8>>(24)?,
+                    row.get::<_, i64>(25)?,
+                    row.get::<_, f32>(26)?,
+                    row.get::<_, f32>(27)?,
+                    row.get::<_, i64>(28)?,
+                    row.get::<_, String>(29)?,
+                    row.get::<_, i64>(30)?,
+                    row.get::<_, i64>(31)?,
+                    row.get::<_, Option<String>>(32)?,
+                    row.get::<_, f32>(33)?,
+                    row.get::<_, f32>(34)?,
+                    row.get::<_, f32>(35)?,
+                    row.get::<_, f32>(36)?,
+                    row.get::<_, f32>(37)?,
+                    row.get::<_, Option<String>>(38)?,
+                    row.get::<_, f32>(39)?,
+                    row.get::<_, String>(40)?,
+                    row.get::<_, i64>(41)?,
+                    row.get::<_, Option<u8>>(42)?,
+                    row.get::<_, i64>(43)?,
+                    row.get::<_, f32>(44)?,
+                    row.get::<_, f32>(45)?,
+                    row.get::<_, i32>(46)?,
+                    row.get::<_, Option<String>>(47)?,
+                    row.get::<_, String>(48)?,
+                    row.get::<_, String>(49)?,
+                    row.get::<_, f32>(50)?,
+                    row.get::<_, f32>(51)?,
+                    row.get::<_, Option<String>>(52)?,
+                    row.get::<_, f32>(53)?,
+                    row.get::<_, f32>(54)?,
+                    row.get::<_, i32>(55)?,
+                    row.get::<_, String>(56)?,
+                    row.get::<_, u8>(57)?,
+                    row.get::<_, Option<u8>>(58)?,
+                    row.get::<_, i32>(59)?,
+                    row.get::<_, i32>(60)?,
+                    row.get::<_, String>(61)?,
+                    row.get::<_, f32>(62)?,
+                    row.get::<_, f32>(63)?,
+                    row.get::<_, String>(64)?,
+                    row.get::<_, i64>(65)?,
+                    row.get::<_, i64>(66)?,
+                    row.get::<_, String>(67)?,
                 ))
             }
         )?;
@@ -398,23 +1017,84 @@ impl Database {
             strips,
             masks,
             scenes,
+            fixtures,
             selected_scene_id,
             network: NetworkConfig {
                 use_multicast: network_use_multicast != 0,
                 unicast_ip: network_unicast_ip,
                 universe: network_universe,
+                multicast_ttl: network_multicast_ttl,
+                multicast_interface: network_multicast_interface,
+                max_universes: network_max_universes,
+                universe_routes,
+                reverse_universe_order: network_reverse_universe_order != 0,
+                redundant_output: network_redundant_output != 0,
+                redundant_interface: network_redundant_interface,
+                muted_universes: network_muted_universes_json
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default(),
+                source_name: network_source_name,
+                source_cid: network_source_cid,
+                universe_mode: network_universe_mode,
             },
             audio: AudioConfig {
                 latency_ms: audio_latency_ms,
                 use_flywheel: audio_use_flywheel != 0,
                 hybrid_sync: audio_hybrid_sync != 0,
                 sensitivity: audio_sensitivity,
+                beat_offset: audio_beat_offset,
+                flywheel_jump_beats: audio_flywheel_jump_beats,
+                flywheel_recovery_secs: audio_flywheel_recovery_secs,
+                flywheel_nudge: audio_flywheel_nudge,
+                phase_correction: audio_phase_correction,
+                phase_decay: audio_phase_decay,
+                source_priority: audio_source_priority_json
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_else(|| AudioConfig::default().source_priority),
+                manual_bpm: audio_manual_bpm.clamp(*MANUAL_BPM_RANGE.start(), *MANUAL_BPM_RANGE.end()),
+                auto_latch_bpm: audio_auto_latch_bpm != 0,
+                beats_per_bar: audio_beats_per_bar.clamp(*BEATS_PER_BAR_RANGE.start(), *BEATS_PER_BAR_RANGE.end()),
+                trigger_action: audio_trigger_action,
+                trigger_threshold: audio_trigger_threshold,
+                trigger_cooldown_secs: audio_trigger_cooldown_secs,
             },
             bind_address,
             mode,
             effect,
             layout_locked: layout_locked != 0,
             midi_enabled: midi_enabled != 0,
+            no_selection_mode,
+            fallback_scene_id: fallback_scene_id.map(|id| id as u64),
+            output_smoothing,
+            fire_midi_btn,
+            fire_midi_is_cc: fire_midi_is_cc != 0,
+            sync_loss_behavior,
+            panic_midi_btn,
+            panic_midi_is_cc: panic_midi_is_cc != 0,
+            max_flash_hz,
+            max_flash_contrast,
+            zero_based_channels: zero_based_channels != 0,
+            canvas_theme,
+            light_ui: light_ui != 0,
+            engine_tick_hz,
+            mask_compositing,
+            launchpad_page: launchpad_page.clamp(0, SCENE_PAGES as i64 - 1) as u8,
+            heartbeat_midi_btn,
+            heartbeat_midi_is_cc: heartbeat_midi_is_cc != 0,
+            fit_padding: fit_padding.clamp(*FIT_PADDING_RANGE.start(), *FIT_PADDING_RANGE.end()),
+            fit_margin: fit_margin.clamp(*FIT_MARGIN_RANGE.start(), *FIT_MARGIN_RANGE.end()),
+            performance_mode: performance_mode != 0,
+            master_brightness: master_brightness.clamp(0.0, 1.0),
+            master_speed: master_speed.clamp(*MASTER_SPEED_RANGE.start(), *MASTER_SPEED_RANGE.end()),
+            cc_mappings: cc_mappings_json.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default(),
+            canvas_unit_meters: canvas_unit_meters.max(0.0),
+            mask_overlays,
+            freeze_midi_btn,
+            freeze_midi_is_cc: freeze_midi_is_cc != 0,
+            staged_editing: staged_editing != 0,
+            feedback_midi: feedback_midi != 0,
+            feedback_osc: feedback_osc != 0,
+            feedback_osc_target,
         })
     }
 
@@ -430,12 +1110,16 @@ impl Database {
         tx.execute("DELETE FROM scenes", [])?;
         tx.execute("DELETE FROM masks", [])?;
         tx.execute("DELETE FROM strips", [])?;
+        tx.execute("DELETE FROM fixtures", [])?;
+        tx.execute("DELETE FROM universe_routes", [])?;
 
         // Save strips
         for strip in &state.strips {
+            let lut_json = strip.lut.as_ref().map(serde_json::to_string).transpose()?;
+            let channel_prefix_json = serde_json::to_string(&strip.channel_prefix)?;
             tx.execute(
-                "INSERT INTO strips (id, universe, start_channel, pixel_count, x, y, spacing, flipped, color_order)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                "INSERT INTO strips (id, universe, start_channel, pixel_count, x, y, spacing, flipped, color_order, lut_json, channel_prefix_json, pixel_offset, dimmer_curve, channels_per_pixel, luma_weights_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
                 params![
                     strip.id as i64,
                     strip.universe,
@@ -446,17 +1130,49 @@ impl Database {
                     strip.spacing,
                     if strip.flipped { 1 } else { 0 },
                     strip.color_order,
+                    lut_json,
+                    channel_prefix_json,
+                    strip.pixel_offset as i64,
+                    strip.dimmer_curve,
+                    strip.channels_per_pixel,
+                    serde_json::to_string(&strip.luma_weights)?,
                 ],
             )?;
         }
 
+        // Save fixtures
+        for fixture in &state.fixtures {
+            tx.execute(
+                "INSERT INTO fixtures (id, name, universe, start_channel, channels_json, x, y)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    fixture.id as i64,
+                    fixture.name,
+                    fixture.universe,
+                    fixture.start_channel,
+                    serde_json::to_string(&fixture.channels)?,
+                    fixture.x,
+                    fixture.y,
+                ],
+            )?;
+        }
+
+        // Save per-universe routing overrides
+        for route in &state.network.universe_routes {
+            tx.execute(
+                "INSERT OR REPLACE INTO universe_routes (universe, use_multicast, unicast_ip) VALUES (?1, ?2, ?3)",
+                params![route.universe, if route.use_multicast { 1 } else { 0 }, route.unicast_ip],
+            )?;
+        }
+
         // Save global masks
         for mask in &state.masks {
             let params_json = serde_json::to_string(&mask.params)?;
+            let target_strips_json = serde_json::to_string(&mask.target_strips)?;
             tx.execute(
-                "INSERT INTO masks (id, mask_type, x, y, params_json)
-                 VALUES (?1, ?2, ?3, ?4, ?5)",
-                params![mask.id as i64, mask.mask_type, mask.x, mask.y, params_json],
+                "INSERT INTO masks (id, mask_type, x, y, params_json, anchor_strip, target_strips_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![mask.id as i64, mask.mask_type, mask.x, mask.y, params_json, mask.anchor_strip.map(|v| v as i64), target_strips_json],
             )?;
         }
 
@@ -468,8 +1184,8 @@ impl Database {
             let global_effects_json = serde_json::to_string(&scene.global_effects)?;
 
             tx.execute(
-                "INSERT INTO scenes (id, name, kind, category, global_effect_json, global_effects_json, launchpad_btn, launchpad_is_cc, launchpad_color)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                "INSERT INTO scenes (id, name, kind, category, global_effect_json, global_effects_json, launchpad_btn, launchpad_is_cc, launchpad_color, master_intensity, compositing, page, audio_reactive)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
                 params![
                     scene.id as i64,
                     scene.name,
@@ -480,15 +1196,20 @@ impl Database {
                     scene.launchpad_btn.map(|v| v as i64),
                     if scene.launchpad_is_cc { 1 } else { 0 },
                     scene.launchpad_color.map(|v| v as i64),
+                    scene.master_intensity,
+                    scene.compositing,
+                    scene.page,
+                    if scene.audio_reactive { 1 } else { 0 },
                 ],
             )?;
 
             // Save scene masks
             for (idx, mask) in scene.masks.iter().enumerate() {
                 let params_json = serde_json::to_string(&mask.params)?;
+                let target_strips_json = serde_json::to_string(&mask.target_strips)?;
                 tx.execute(
-                    "INSERT INTO scene_masks (scene_id, mask_id, mask_type, x, y, params_json, display_order)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    "INSERT INTO scene_masks (scene_id, mask_id, mask_type, x, y, params_json, display_order, anchor_strip, target_strips_json)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
                     params![
                         scene.id as i64,
                         mask.id as i64,
@@ -497,6 +1218,8 @@ impl Database {
                         mask.y,
                         params_json,
                         idx as i64,
+                        mask.anchor_strip.map(|v| v as i64),
+                        target_strips_json,
                     ],
                 )?;
             }
@@ -517,7 +1240,62 @@ impl Database {
                 audio_hybrid_sync = ?10,
                 audio_sensitivity = ?11,
                 layout_locked = ?12,
-                midi_enabled = ?13
+                midi_enabled = ?13,
+                network_multicast_ttl = ?14,
+                network_multicast_interface = ?15,
+                no_selection_mode = ?16,
+                fallback_scene_id = ?17,
+                network_max_universes = ?18,
+                output_smoothing = ?19,
+                audio_beat_offset = ?20,
+                fire_midi_btn = ?21,
+                fire_midi_is_cc = ?22,
+                network_reverse_universe_order = ?23,
+                sync_loss_behavior = ?24,
+                panic_midi_btn = ?25,
+                panic_midi_is_cc = ?26,
+                max_flash_hz = ?27,
+                max_flash_contrast = ?28,
+                zero_based_channels = ?29,
+                canvas_theme = ?30,
+                light_ui = ?31,
+                network_redundant_output = ?32,
+                network_redundant_interface = ?33,
+                audio_flywheel_jump_beats = ?34,
+                audio_flywheel_recovery_secs = ?35,
+                audio_flywheel_nudge = ?36,
+                audio_phase_correction = ?37,
+                audio_phase_decay = ?38,
+                audio_source_priority_json = ?39,
+                engine_tick_hz = ?40,
+                mask_compositing = ?41,
+                launchpad_page = ?42,
+                heartbeat_midi_btn = ?43,
+                heartbeat_midi_is_cc = ?44,
+                fit_padding = ?45,
+                fit_margin = ?46,
+                performance_mode = ?47,
+                network_muted_universes_json = ?48,
+                network_source_name = ?49,
+                network_source_cid = ?50,
+                master_brightness = ?51,
+                master_speed = ?52,
+                cc_mappings_json = ?53,
+                canvas_unit_meters = ?54,
+                audio_manual_bpm = ?55,
+                audio_auto_latch_bpm = ?56,
+                mask_overlays = ?57,
+                audio_beats_per_bar = ?58,
+                freeze_midi_btn = ?59,
+                freeze_midi_is_cc = ?60,
+                staged_editing = ?61,
+                audio_trigger_action = ?62,
+                audio_trigger_threshold = ?63,
+                audio_trigger_cooldown_secs = ?64,
+                network_universe_mode = ?65,
+                feedback_midi = ?66,
+                feedback_osc = ?67,
+                feedback_osc_target = ?68
              WHERE id = 1",
             params![
                 state.selected_scene_id.map(|id| id as i64),
@@ -533,6 +1311,61 @@ impl Database {
                 state.audio.sensitivity,
                 if state.layout_locked { 1 } else { 0 },
                 if state.midi_enabled { 1 } else { 0 },
+                state.network.multicast_ttl,
+                state.network.multicast_interface,
+                state.no_selection_mode,
+                state.fallback_scene_id.map(|id| id as i64),
+                state.network.max_universes,
+                state.output_smoothing,
+                state.audio.beat_offset,
+                state.fire_midi_btn,
+                if state.fire_midi_is_cc { 1 } else { 0 },
+                if state.network.reverse_universe_order { 1 } else { 0 },
+                state.sync_loss_behavior,
+                state.panic_midi_btn,
+                if state.panic_midi_is_cc { 1 } else { 0 },
+                state.max_flash_hz,
+                state.max_flash_contrast,
+                if state.zero_based_channels { 1 } else { 0 },
+                state.canvas_theme,
+                if state.light_ui { 1 } else { 0 },
+                if state.network.redundant_output { 1 } else { 0 },
+                state.network.redundant_interface,
+                state.audio.flywheel_jump_beats,
+                state.audio.flywheel_recovery_secs,
+                state.audio.flywheel_nudge,
+                state.audio.phase_correction,
+                state.audio.phase_decay,
+                serde_json::to_string(&state.audio.source_priority)?,
+                state.engine_tick_hz,
+                state.mask_compositing,
+                state.launchpad_page,
+                state.heartbeat_midi_btn,
+                if state.heartbeat_midi_is_cc { 1 } else { 0 },
+                state.fit_padding,
+                state.fit_margin,
+                if state.performance_mode { 1 } else { 0 },
+                serde_json::to_string(&state.network.muted_universes)?,
+                state.network.source_name,
+                state.network.source_cid,
+                state.master_brightness,
+                state.master_speed,
+                serde_json::to_string(&state.cc_mappings)?,
+                state.canvas_unit_meters,
+                state.audio.manual_bpm,
+                if state.audio.auto_latch_bpm { 1 } else { 0 },
+                state.mask_overlays,
+                state.audio.beats_per_bar,
+                state.freeze_midi_btn,
+                if state.freeze_midi_is_cc { 1 } else { 0 },
+                if state.staged_editing { 1 } else { 0 },
+                state.audio.trigger_action,
+                state.audio.trigger_threshold,
+                state.audio.trigger_cooldown_secs,
+                state.network.universe_mode,
+                if state.feedback_midi { 1 } else { 0 },
+                if state.feedback_osc { 1 } else { 0 },
+                state.feedback_osc_target,
             ],
         )?;
 
@@ -546,14 +1379,202 @@ impl Database {
     /// Export entire state to JSON string
     pub fn export_to_json(&self) -> Result<String> {
         let state = self.load_state()?;
-        let json = serde_json::to_string_pretty(&state)?;
+        let mut value = serde_json::to_value(&state)?;
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("schema_version".into(), serde_json::json!(self.schema_version()?));
+        }
+        let json = serde_json::to_string_pretty(&value)?;
         Ok(json)
     }
 
+    /// Export the strips, network, bind address and audio settings as a layout JSON string
+    pub fn export_layout(&self) -> Result<String> {
+        let state = self.load_state()?;
+        let layout = LayoutFile {
+            schema_version: Some(self.schema_version()?),
+            strips: state.strips,
+            network: state.network,
+            bind_address: state.bind_address,
+            audio: state.audio,
+        };
+        Ok(serde_json::to_string_pretty(&layout)?)
+    }
+
+    /// Replace the strips, network, bind address and audio settings with a layout file's,
+    /// keeping scenes, masks and fixtures. Imported strips keep their ids, so scenes built on
+    /// this layout stay targeted; only ids repeated within the file get new ones.
+    /// The sACN CID stays this machine's. Returns how many strip ids were remapped.
+    pub fn import_layout(&mut self, json: &str) -> Result<usize> {
+        let layout: LayoutFile = serde_json::from_str(json)
+            .context("Not a Lightspeed layout file")?;
+        let mut state = self.load_state()?;
+
+        // The current strips are all replaced, so only the file's own ids can collide
+        let mut used = HashSet::new();
+        let mut remapped = 0;
+        let mut strips = layout.strips;
+        for strip in &mut strips {
+            strip.sanitize();
+            if used.contains(&strip.id) {
+                strip.id = fresh_id(&used);
+                remapped += 1;
+            }
+            used.insert(strip.id);
+        }
+
+        let source_cid = std::mem::take(&mut state.network.source_cid);
+        state.strips = strips;
+        state.network = layout.network;
+        state.network.source_cid = source_cid;
+        state.bind_address = layout.bind_address;
+        state.audio = layout.audio;
+        self.save_state(&state)?;
+        Ok(remapped)
+    }
+
+    /// Parse an import file and report what `import_from_json` would change, without writing
+    pub fn preview_import(&self, json: &str, merge: bool) -> Result<ImportReport> {
+        let raw: serde_json::Value = serde_json::from_str(json)
+            .context("Invalid JSON format")?;
+        let mut import_state: AppState = serde_json::from_value(raw.clone())
+            .context("JSON does not match the Lightspeed config format")?;
+        let migrated_masks = import_state.masks.len();
+        import_state.migrate_global_masks();
+
+        let mut report = ImportReport::default();
+
+        // Schema version check
+        let current_version = self.schema_version()?;
+        report.file_schema_version = raw.get("schema_version").and_then(|v| match v {
+            serde_json::Value::String(s) => Some(s.clone()),
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            _ => None,
+        });
+        match &report.file_schema_version {
+            None => report.warnings.push(
+                "File has no schema version (exported by an older build); fields may be missing".into()
+            ),
+            Some(v) if *v != current_version => report.warnings.push(format!(
+                "File schema version {} differs from current version {}", v, current_version
+            )),
+            _ => {}
+        }
+
+        let existing_strips: Vec<u64> = self.conn
+            .prepare("SELECT id FROM strips")?
+            .query_map([], |row| Ok(row.get::<_, i64>(0)? as u64))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let existing_scenes: Vec<u64> = self.conn
+            .prepare("SELECT id FROM scenes")?
+            .query_map([], |row| Ok(row.get::<_, i64>(0)? as u64))?
+            .collect::<Result<Vec<_>, _>>()?;
+        let existing_fixtures: HashSet<u64> = self.conn
+            .prepare("SELECT id FROM fixtures")?
+            .query_map([], |row| Ok(row.get::<_, i64>(0)? as u64))?
+            .collect::<Result<_, _>>()?;
+        let existing_routes: i64 = self.conn
+            .query_row("SELECT COUNT(*) FROM universe_routes", [], |row| row.get(0))?;
+        let existing_scene_masks: i64 = self.conn
+            .query_row("SELECT COUNT(*) FROM scene_masks", [], |row| row.get(0))?;
+        let existing_global_masks: i64 = self.conn
+            .query_row("SELECT COUNT(*) FROM masks", [], |row| row.get(0))?;
+
+        for strip in &import_state.strips {
+            let exists = existing_strips.contains(&strip.id);
+            if merge && exists {
+                report.conflicts.push(format!("Strip id {} already exists", strip.id));
+                report.strips_added += 1;
+            } else if exists {
+                report.strips_replaced += 1;
+            } else {
+                report.strips_added += 1;
+            }
+        }
+
+        for scene in &import_state.scenes {
+            let exists = existing_scenes.contains(&scene.id);
+            if merge && exists {
+                report.conflicts.push(format!("Scene '{}' (id {}) already exists", scene.name, scene.id));
+                report.scenes_added += 1;
+            } else if exists {
+                report.scenes_replaced += 1;
+            } else {
+                report.scenes_added += 1;
+            }
+            report.masks_added += scene.masks.len();
+        }
+
+        // Fixtures collide with existing ids only in merge mode, but repeats within the file always get fresh ids
+        let mut taken_fixture_ids: HashSet<u64> = if merge { existing_fixtures.clone() } else { HashSet::new() };
+        for fixture in &import_state.fixtures {
+            if taken_fixture_ids.contains(&fixture.id) {
+                report.fixtures_remapped += 1;
+                report.fixtures_added += 1;
+                continue;
+            }
+            taken_fixture_ids.insert(fixture.id);
+            if existing_fixtures.contains(&fixture.id) {
+                report.fixtures_replaced += 1;
+            } else {
+                report.fixtures_added += 1;
+            }
+        }
+
+        let incoming_strips: HashSet<u64> = import_state.strips.iter().map(|s| s.id).collect();
+        let incoming_scenes: HashSet<u64> = import_state.scenes.iter().map(|s| s.id).collect();
+        let incoming_fixtures: HashSet<u64> = import_state.fixtures.iter().map(|f| f.id).collect();
+        if !merge {
+            report.strips_removed = existing_strips.iter().filter(|id| !incoming_strips.contains(id)).count();
+            report.scenes_removed = existing_scenes.iter().filter(|id| !incoming_scenes.contains(id)).count();
+            report.fixtures_removed = existing_fixtures.iter().filter(|id| !incoming_fixtures.contains(id)).count();
+            report.masks_removed = (existing_scene_masks + existing_global_masks) as usize;
+            if existing_routes > 0 {
+                report.warnings.push(format!(
+                    "{} universe route override(s) will be replaced by the {} in the file",
+                    existing_routes, import_state.network.universe_routes.len()
+                ));
+            }
+        }
+
+        // Merge mode gives repeats fresh ids; replace mode inserts them as-is and hits the primary key
+        let outcome = if merge { "they will get new ids" } else { "the import will fail" };
+        let strip_dupes = import_state.strips.len() - incoming_strips.len();
+        let scene_dupes = import_state.scenes.len() - incoming_scenes.len();
+        if strip_dupes > 0 {
+            report.warnings.push(format!("{} strip(s) in the file repeat an id; {}", strip_dupes, outcome));
+        }
+        if scene_dupes > 0 {
+            report.warnings.push(format!("{} scene(s) in the file repeat an id; {}", scene_dupes, outcome));
+        }
+
+        if !report.conflicts.is_empty() {
+            report.warnings.push(format!(
+                "{} item(s) with existing ids will be imported under new ids", report.conflicts.len()
+            ));
+        }
+        if migrated_masks > 0 {
+            report.warnings.push(format!(
+                "{} global mask(s) in the file will be imported as scene 'Migrated Masks'", migrated_masks
+            ));
+        }
+
+        Ok(report)
+    }
+
     /// Import from JSON string
     pub fn import_from_json(&mut self, json: &str, merge: bool) -> Result<()> {
-        let import_state: AppState = serde_json::from_str(json)
+        let mut import_state: AppState = serde_json::from_str(json)
             .context("Invalid JSON format")?;
+        import_state.migrate_global_masks();
+        for strip in &mut import_state.strips {
+            strip.sanitize();
+        }
+        for fixture in &mut import_state.fixtures {
+            fixture.start_channel = fixture.start_channel.clamp(1, 512);
+        }
+        for mask in import_state.scenes.iter_mut().flat_map(|s| s.masks.iter_mut()) {
+            mask.sanitize();
+        }
 
         let tx = self.conn.transaction()?;
 
@@ -563,29 +1584,41 @@ impl Database {
             tx.execute("DELETE FROM scenes", [])?;
             tx.execute("DELETE FROM masks", [])?;
             tx.execute("DELETE FROM strips", [])?;
+            tx.execute("DELETE FROM fixtures", [])?;
+            tx.execute("DELETE FROM universe_routes", [])?;
         }
 
-        // Import strips (handle ID conflicts in merge mode)
-        for strip in &import_state.strips {
-            if merge {
-                // In merge mode, find max ID and offset if needed
-                let exists: bool = tx.query_row(
-                    "SELECT COUNT(*) > 0 FROM strips WHERE id = ?1",
-                    [strip.id],
-                    |row| row.get(0)
-                )?;
+        // In merge mode, incoming ids that collide with existing rows get fresh ids
+        let mut taken_strip_ids: HashSet<u64> = HashSet::new();
+        let mut taken_scene_ids: HashSet<u64> = HashSet::new();
+        if merge {
+            taken_strip_ids = tx.prepare("SELECT id FROM strips")?
+                .query_map([], |row| Ok(row.get::<_, i64>(0)? as u64))?
+                .collect::<Result<_, _>>()?;
+            taken_scene_ids = tx.prepare("SELECT id FROM scenes")?
+                .query_map([], |row| Ok(row.get::<_, i64>(0)? as u64))?
+                .collect::<Result<_, _>>()?;
+        }
 
-                if exists {
-                    // Skip or generate new ID
-                    continue;
-                }
-            }
+        // Import strips, remembering any remapped ids so scene references can follow
+        let mut strip_id_map: HashMap<u64, u64> = HashMap::new();
+        for strip in &import_state.strips {
+            let id = if merge && taken_strip_ids.contains(&strip.id) {
+                let new_id = fresh_id(&taken_strip_ids);
+                strip_id_map.insert(strip.id, new_id);
+                new_id
+            } else {
+                strip.id
+            };
+            taken_strip_ids.insert(id);
 
+            let lut_json = strip.lut.as_ref().map(serde_json::to_string).transpose()?;
+            let channel_prefix_json = serde_json::to_string(&strip.channel_prefix)?;
             tx.execute(
-                "INSERT INTO strips (id, universe, start_channel, pixel_count, x, y, spacing, flipped, color_order)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                "INSERT INTO strips (id, universe, start_channel, pixel_count, x, y, spacing, flipped, color_order, lut_json, channel_prefix_json, pixel_offset, dimmer_curve, channels_per_pixel, luma_weights_json)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
                 params![
-                    strip.id as i64,
+                    id as i64,
                     strip.universe,
                     strip.start_channel,
                     strip.pixel_count,
@@ -594,33 +1627,85 @@ impl Database {
                     strip.spacing,
                     if strip.flipped { 1 } else { 0 },
                     strip.color_order,
+                    lut_json,
+                    channel_prefix_json,
+                    strip.pixel_offset as i64,
+                    strip.dimmer_curve,
+                    strip.channels_per_pixel,
+                    serde_json::to_string(&strip.luma_weights)?,
                 ],
             )?;
         }
 
-        // Import scenes and masks similarly
+        // Import per-universe routing overrides (file wins on conflict)
+        for route in &import_state.network.universe_routes {
+            tx.execute(
+                "INSERT OR REPLACE INTO universe_routes (universe, use_multicast, unicast_ip) VALUES (?1, ?2, ?3)",
+                params![route.universe, if route.use_multicast { 1 } else { 0 }, route.unicast_ip],
+            )?;
+        }
+
+        // Import fixtures, with fresh ids for collisions in merge mode
+        let mut taken_fixture_ids: HashSet<u64> = if merge {
+            tx.prepare("SELECT id FROM fixtures")?
+                .query_map([], |row| Ok(row.get::<_, i64>(0)? as u64))?
+                .collect::<Result<_, _>>()?
+        } else {
+            HashSet::new()
+        };
+        for fixture in &import_state.fixtures {
+            let id = if taken_fixture_ids.contains(&fixture.id) {
+                fresh_id(&taken_fixture_ids)
+            } else {
+                fixture.id
+            };
+            taken_fixture_ids.insert(id);
+            tx.execute(
+                "INSERT INTO fixtures (id, name, universe, start_channel, channels_json, x, y)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    id as i64,
+                    fixture.name,
+                    fixture.universe,
+                    fixture.start_channel,
+                    serde_json::to_string(&fixture.channels)?,
+                    fixture.x,
+                    fixture.y,
+                ],
+            )?;
+        }
+
+        // Import scenes and their masks
         for scene in &import_state.scenes {
-            if merge {
-                let exists: bool = tx.query_row(
-                    "SELECT COUNT(*) > 0 FROM scenes WHERE id = ?1",
-                    [scene.id],
-                    |row| row.get(0)
-                )?;
-                if exists {
-                    continue;
+            let scene_id = if merge && taken_scene_ids.contains(&scene.id) {
+                fresh_id(&taken_scene_ids)
+            } else {
+                scene.id
+            };
+            taken_scene_ids.insert(scene_id);
+
+            // Effect targets point at strip ids, so rewrite them to the remapped ids
+            let mut global_effects = scene.global_effects.clone();
+            for config in &mut global_effects {
+                if let Some(targets) = &mut config.targets {
+                    for target in targets.iter_mut() {
+                        if let Some(&new_id) = strip_id_map.get(target) {
+                            *target = new_id;
+                        }
+                    }
                 }
             }
 
             let global_effect_json = scene.global.as_ref()
                 .map(|g| serde_json::to_string(g))
                 .transpose()?;
-            let global_effects_json = serde_json::to_string(&scene.global_effects)?;
+            let global_effects_json = serde_json::to_string(&global_effects)?;
 
             tx.execute(
-                "INSERT INTO scenes (id, name, kind, category, global_effect_json, global_effects_json, launchpad_btn, launchpad_is_cc, launchpad_color)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                "INSERT INTO scenes (id, name, kind, category, global_effect_json, global_effects_json, launchpad_btn, launchpad_is_cc, launchpad_color, master_intensity, compositing, page, audio_reactive)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
                 params![
-                    scene.id as i64,
+                    scene_id as i64,
                     scene.name,
                     scene.kind,
                     scene.category,
@@ -629,22 +1714,33 @@ impl Database {
                     scene.launchpad_btn.map(|v| v as i64),
                     if scene.launchpad_is_cc { 1 } else { 0 },
                     scene.launchpad_color.map(|v| v as i64),
+                    scene.master_intensity,
+                    scene.compositing,
+                    scene.page,
+                    if scene.audio_reactive { 1 } else { 0 },
                 ],
             )?;
 
             for (idx, mask) in scene.masks.iter().enumerate() {
                 let params_json = serde_json::to_string(&mask.params)?;
+                let anchor_strip = mask.anchor_strip.map(|id| strip_id_map.get(&id).copied().unwrap_or(id));
+                let target_strips: Vec<u64> = mask.target_strips.iter()
+                    .map(|id| strip_id_map.get(id).copied().unwrap_or(*id))
+                    .collect();
+                let target_strips_json = serde_json::to_string(&target_strips)?;
                 tx.execute(
-                    "INSERT INTO scene_masks (scene_id, mask_id, mask_type, x, y, params_json, display_order)
-                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    "INSERT INTO scene_masks (scene_id, mask_id, mask_type, x, y, params_json, display_order, anchor_strip, target_strips_json)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
                     params![
-                        scene.id as i64,
+                        scene_id as i64,
                         mask.id as i64,
                         mask.mask_type,
                         mask.x,
                         mask.y,
                         params_json,
                         idx as i64,
+                        anchor_strip.map(|v| v as i64),
+                        target_strips_json,
                     ],
                 )?;
             }
@@ -663,7 +1759,61 @@ impl Database {
                     audio_hybrid_sync = ?7,
                     audio_sensitivity = ?8,
                     layout_locked = ?9,
-                    midi_enabled = ?10
+                    midi_enabled = ?10,
+                    network_multicast_ttl = ?11,
+                    network_multicast_interface = ?12,
+                    no_selection_mode = ?13,
+                    fallback_scene_id = ?14,
+                    network_max_universes = ?15,
+                    output_smoothing = ?16,
+                    audio_beat_offset = ?17,
+                    fire_midi_btn = ?18,
+                    fire_midi_is_cc = ?19,
+                    network_reverse_universe_order = ?20,
+                    sync_loss_behavior = ?21,
+                    panic_midi_btn = ?22,
+                    panic_midi_is_cc = ?23,
+                    max_flash_hz = ?24,
+                    max_flash_contrast = ?25,
+                    zero_based_channels = ?26,
+                    canvas_theme = ?27,
+                    light_ui = ?28,
+                    network_redundant_output = ?29,
+                    network_redundant_interface = ?30,
+                    audio_flywheel_jump_beats = ?31,
+                    audio_flywheel_recovery_secs = ?32,
+                    audio_flywheel_nudge = ?33,
+                    audio_phase_correction = ?34,
+                    audio_phase_decay = ?35,
+                    audio_source_priority_json = ?36,
+                    engine_tick_hz = ?37,
+                    mask_compositing = ?38,
+                    launchpad_page = ?39,
+                    heartbeat_midi_btn = ?40,
+                    heartbeat_midi_is_cc = ?41,
+                    fit_padding = ?42,
+                    fit_margin = ?43,
+                    performance_mode = ?44,
+                    network_muted_universes_json = ?45,
+                    network_source_name = ?46,
+                    master_brightness = ?47,
+                    master_speed = ?48,
+                    cc_mappings_json = ?49,
+                    canvas_unit_meters = ?50,
+                    audio_manual_bpm = ?51,
+                    audio_auto_latch_bpm = ?52,
+                    mask_overlays = ?53,
+                    audio_beats_per_bar = ?54,
+                    freeze_midi_btn = ?55,
+                    freeze_midi_is_cc = ?56,
+                    staged_editing = ?57,
+                    audio_trigger_action = ?58,
+                    audio_trigger_threshold = ?59,
+                    audio_trigger_cooldown_secs = ?60,
+                    network_universe_mode = ?61,
+                    feedback_midi = ?62,
+                    feedback_osc = ?63,
+                    feedback_osc_target = ?64
                  WHERE id = 1",
                 params![
                     import_state.selected_scene_id.map(|id| id as i64),
@@ -676,6 +1826,60 @@ impl Database {
                     import_state.audio.sensitivity,
                     if import_state.layout_locked { 1 } else { 0 },
                     if import_state.midi_enabled { 1 } else { 0 },
+                    import_state.network.multicast_ttl,
+                    import_state.network.multicast_interface,
+                    import_state.no_selection_mode,
+                    import_state.fallback_scene_id.map(|id| id as i64),
+                    import_state.network.max_universes,
+                    import_state.output_smoothing,
+                    import_state.audio.beat_offset,
+                    import_state.fire_midi_btn,
+                    if import_state.fire_midi_is_cc { 1 } else { 0 },
+                    if import_state.network.reverse_universe_order { 1 } else { 0 },
+                    import_state.sync_loss_behavior,
+                    import_state.panic_midi_btn,
+                    if import_state.panic_midi_is_cc { 1 } else { 0 },
+                    import_state.max_flash_hz,
+                    import_state.max_flash_contrast,
+                    if import_state.zero_based_channels { 1 } else { 0 },
+                    import_state.canvas_theme,
+                    if import_state.light_ui { 1 } else { 0 },
+                    if import_state.network.redundant_output { 1 } else { 0 },
+                    import_state.network.redundant_interface,
+                    import_state.audio.flywheel_jump_beats,
+                    import_state.audio.flywheel_recovery_secs,
+                    import_state.audio.flywheel_nudge,
+                    import_state.audio.phase_correction,
+                    import_state.audio.phase_decay,
+                    serde_json::to_string(&import_state.audio.source_priority)?,
+                    import_state.engine_tick_hz,
+                    import_state.mask_compositing,
+                    import_state.launchpad_page,
+                    import_state.heartbeat_midi_btn,
+                    if import_state.heartbeat_midi_is_cc { 1 } else { 0 },
+                    import_state.fit_padding,
+                    import_state.fit_margin,
+                    if import_state.performance_mode { 1 } else { 0 },
+                    serde_json::to_string(&import_state.network.muted_universes)?,
+                    import_state.network.source_name,
+                    import_state.master_brightness,
+                    import_state.master_speed,
+                    serde_json::to_string(&import_state.cc_mappings)?,
+                    import_state.canvas_unit_meters,
+                    import_state.audio.manual_bpm,
+                    if import_state.audio.auto_latch_bpm { 1 } else { 0 },
+                    import_state.mask_overlays,
+                    import_state.audio.beats_per_bar,
+                    import_state.freeze_midi_btn,
+                    if import_state.freeze_midi_is_cc { 1 } else { 0 },
+                    if import_state.staged_editing { 1 } else { 0 },
+                    import_state.audio.trigger_action,
+                    import_state.audio.trigger_threshold,
+                    import_state.audio.trigger_cooldown_secs,
+                    import_state.network.universe_mode,
+                    if import_state.feedback_midi { 1 } else { 0 },
+                    if import_state.feedback_osc { 1 } else { 0 },
+                    import_state.feedback_osc_target,
                 ],
             )?;
         }
@@ -684,3 +1888,486 @@ impl Database {
         Ok(())
     }
 }
+
+/// Pick a random id not already in `taken`
+fn fresh_id(taken: &HashSet<u64>) -> u64 {
+    loop {
+        // Keep ids within i64 range so they round-trip through SQLite unchanged
+        let id = rand::random::<u64>() >> 1;
+        if id != 0 && !taken.contains(&id) {
+            return id;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Helper to open a fresh in-memory database
+    fn open_test_db() -> Database {
+        Database::open(Path::new(":memory:")).expect("in-memory database")
+    }
+
+    /// Helper to create a scene with no Launchpad mapping
+    fn create_test_scene(id: u64, name: &str, kind: &str) -> Scene {
+        Scene {
+            id,
+            name: name.into(),
+            kind: kind.into(),
+            category: "Uncategorized".into(),
+            masks: Vec::new(),
+            global: None,
+            global_effects: Vec::new(),
+            launchpad_btn: None,
+            launchpad_is_cc: false,
+            launchpad_color: None,
+            master_intensity: 1.0,
+            compositing: "add".into(),
+            page: 0,
+            audio_reactive: true,
+        }
+    }
+
+    /// Helper to build a small config with one strip, one Masks scene and one Global scene
+    fn create_test_state() -> AppState {
+        let strip = PixelStrip { id: 10, ..PixelStrip::default() };
+        let mask = Mask {
+            id: 100,
+            mask_type: "scanner".into(),
+            x: 0.5,
+            y: 0.5,
+            params: HashMap::new(),
+            anchor_strip: None,
+            target_strips: Vec::new(),
+        };
+        let mut masks_scene = create_test_scene(1, "Sweep", "Masks");
+        masks_scene.masks.push(mask);
+        let mut global_scene = create_test_scene(2, "Wash", "Global");
+        global_scene.global_effects.push(GlobalEffectConfig {
+            effect: GlobalEffect { kind: "Solid".into(), params: HashMap::new() },
+            targets: Some(vec![10]),
+        });
+        AppState {
+            strips: vec![strip],
+            scenes: vec![masks_scene, global_scene],
+            ..AppState::default()
+        }
+    }
+
+    #[test]
+    fn test_merge_import_remaps_colliding_ids() {
+        let mut db = open_test_db();
+        let state = create_test_state();
+        db.save_state(&state).unwrap();
+
+        // Import the exact same config: every id collides
+        let json = serde_json::to_string(&state).unwrap();
+        db.import_from_json(&json, true).unwrap();
+
+        let merged = db.load_state().unwrap();
+        assert_eq!(merged.strips.len(), 2, "colliding strip should be imported, not skipped");
+        assert_eq!(merged.scenes.len(), 4, "colliding scenes should be imported, not skipped");
+
+        let new_strip = merged.strips.iter().find(|s| s.id != 10).expect("remapped strip");
+        let new_scenes: Vec<&Scene> = merged.scenes.iter().filter(|s| s.id != 1 && s.id != 2).collect();
+        assert_eq!(new_scenes.len(), 2, "both scenes should land under new ids");
+
+        // Scene masks follow their remapped scene
+        let new_sweep = new_scenes.iter().find(|s| s.name == "Sweep").unwrap();
+        assert_eq!(new_sweep.masks.len(), 1);
+        assert_eq!(new_sweep.masks[0].id, 100);
+
+        // Effect targets follow the remapped strip
+        let new_wash = new_scenes.iter().find(|s| s.name == "Wash").unwrap();
+        assert_eq!(new_wash.global_effects[0].targets, Some(vec![new_strip.id]));
+
+        // Originals are untouched
+        let old_wash = merged.scenes.iter().find(|s| s.id == 2).unwrap();
+        assert_eq!(old_wash.global_effects[0].targets, Some(vec![10]));
+    }
+
+    #[test]
+    fn test_preview_counts_repeated_ids() {
+        let mut db = open_test_db();
+        let mut state = create_test_state();
+        state.fixtures.push(Fixture { id: 20, ..Fixture::default() });
+        db.save_state(&state).unwrap();
+
+        // The file repeats existing strip 10 and fixture 20, and drops scene 2
+        let mut file = state.clone();
+        file.strips.push(file.strips[0].clone());
+        file.fixtures.push(file.fixtures[0].clone());
+        file.scenes.truncate(1);
+        let json = serde_json::to_string(&file).unwrap();
+
+        let report = db.preview_import(&json, false).unwrap();
+        assert_eq!(report.strips_replaced, 2);
+        assert_eq!(report.strips_removed, 0, "a repeated id must not count as removing another strip");
+        assert_eq!(report.scenes_removed, 1);
+        assert_eq!((report.fixtures_replaced, report.fixtures_remapped, report.fixtures_removed), (1, 1, 0));
+        assert!(report.warnings.iter().any(|w| w.contains("repeat an id")));
+
+        let report = db.preview_import(&json, true).unwrap();
+        assert_eq!(report.fixtures_remapped, 2, "merge mode remaps both copies of fixture 20");
+    }
+
+    #[test]
+    fn test_import_moves_global_masks_into_scene() {
+        let mut db = open_test_db();
+        let mut state = create_test_state();
+        let legacy = state.scenes[0].masks.remove(0);
+        state.masks.push(legacy);
+
+        let json = serde_json::to_string(&state).unwrap();
+        db.import_from_json(&json, false).unwrap();
+
+        let loaded = db.load_state().unwrap();
+        assert!(loaded.masks.is_empty(), "global masks should not survive import");
+        let migrated = loaded.scenes.iter().find(|s| s.name == "Migrated Masks").expect("migration scene");
+        assert_eq!(migrated.kind, "Masks");
+        assert_eq!(migrated.masks.len(), 1);
+        assert_eq!(migrated.masks[0].id, 100);
+    }
+
+    #[test]
+    fn test_import_flags_duplicate_launchpad_buttons() {
+        let mut db = open_test_db();
+        let mut state = create_test_state();
+        for scene in state.scenes.iter_mut() {
+            scene.launchpad_btn = Some(11); // Same note on both scenes
+        }
+        let mut cc_scene = create_test_scene(3, "CC", "Masks");
+        cc_scene.launchpad_btn = Some(11);
+        cc_scene.launchpad_is_cc = true; // CC 11 is a different button than note 11
+        state.scenes.push(cc_scene);
+        db.import_from_json(&serde_json::to_string(&state).unwrap(), false).unwrap();
+
+        let mut loaded = db.load_state().unwrap();
+        assert_eq!(loaded.duplicate_launchpad_scenes(), vec![2]);
+        assert_eq!(loaded.clear_duplicate_launchpad_mappings(), 1);
+        assert!(loaded.duplicate_launchpad_scenes().is_empty());
+        let btn = |id: u64| loaded.scenes.iter().find(|s| s.id == id).unwrap().launchpad_btn;
+        assert_eq!((btn(1), btn(2), btn(3)), (Some(11), None, Some(11)));
+    }
+
+    #[test]
+    fn test_scene_pages_round_trip() {
+        let mut db = open_test_db();
+        let mut state = create_test_state();
+        for scene in state.scenes.iter_mut() {
+            scene.launchpad_btn = Some(11);
+        }
+        state.scenes[1].page = 2; // Same pad on another page is not a duplicate
+        state.launchpad_page = 2;
+        db.save_state(&state).unwrap();
+
+        let mut loaded = db.load_state().unwrap();
+        assert_eq!(loaded.launchpad_page, 2);
+        assert_eq!(loaded.scenes.iter().find(|s| s.id == 2).unwrap().page, 2);
+        assert!(loaded.duplicate_launchpad_scenes().is_empty());
+        assert_eq!(loaded.launchpad_scene(11, false).map(|s| s.id), Some(2));
+        loaded.launchpad_page = 0;
+        assert_eq!(loaded.launchpad_scene(11, false).map(|s| s.id), Some(1));
+    }
+
+    #[test]
+    fn test_scene_audio_reactive_round_trip() {
+        let mut db = open_test_db();
+        let mut state = create_test_state();
+        state.scenes[1].audio_reactive = false;
+        db.save_state(&state).unwrap();
+
+        let loaded = db.load_state().unwrap();
+        let reactive: Vec<(u64, bool)> = loaded.scenes.iter().map(|s| (s.id, s.audio_reactive)).collect();
+        assert_eq!(reactive, vec![(1, true), (2, false)]);
+    }
+
+    #[test]
+    fn test_layout_import_keeps_scenes_and_strip_ids() {
+        let mut db = open_test_db();
+        let mut rig = create_test_state();
+        rig.strips.push(PixelStrip { id: 11, universe: 4, ..PixelStrip::default() });
+        rig.network.unicast_ip = "10.0.0.9".into();
+        db.save_state(&rig).unwrap();
+        let layout = db.export_layout().unwrap();
+        assert!(!layout.contains("\"scenes\""));
+
+        // A different show on another machine
+        let mut show = create_test_state();
+        show.scenes.truncate(1);
+        show.network.source_cid = "local-cid".into();
+        db.save_state(&show).unwrap();
+        assert_eq!(db.import_layout(&layout).unwrap(), 0);
+
+        let loaded = db.load_state().unwrap();
+        assert_eq!(loaded.scenes.len(), 1);
+        assert_eq!(loaded.scenes[0].masks.len(), 1);
+        assert_eq!(loaded.strips.len(), 2);
+        assert!(loaded.strips.iter().any(|s| s.id == 10));
+        assert!(loaded.strips.iter().any(|s| s.id == 11 && s.universe == 4));
+        assert_eq!(loaded.network.unicast_ip, "10.0.0.9");
+        assert_eq!(loaded.network.source_cid, "local-cid");
+
+        // Ids repeated within the file are the only ones remapped
+        let doubled = layout.replace("\"id\": 11", "\"id\": 10");
+        assert_eq!(db.import_layout(&doubled).unwrap(), 1);
+        let ids: HashSet<u64> = db.load_state().unwrap().strips.iter().map(|s| s.id).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&10));
+    }
+
+    #[test]
+    fn test_reimported_layout_keeps_masks_targeted() {
+        let mut db = open_test_db();
+        let mut show = create_test_state();
+        show.strips.push(PixelStrip { id: 11, ..PixelStrip::default() });
+        show.scenes[0].masks[0].target_strips = vec![10];
+        show.scenes[0].masks[0].anchor_strip = Some(10);
+        db.save_state(&show).unwrap();
+
+        let layout = db.export_layout().unwrap();
+        assert_eq!(db.import_layout(&layout).unwrap(), 0);
+
+        let mask = &db.load_state().unwrap().scenes[0].masks[0];
+        assert_eq!(mask.target_strips, vec![10]);
+        assert_eq!(mask.anchor_strip, Some(10));
+    }
+
+    #[test]
+    fn test_fader_cc_mappings_round_trip_and_apply() {
+        let mut db = open_test_db();
+        let mut state = create_test_state();
+        state.selected_scene_id = Some(1);
+        state.set_cc("brightness", Some(7));
+        state.set_cc("speed", Some(8));
+        state.set_cc("submaster", Some(9));
+        state.set_cc("scene:2", Some(9)); // One fader can drive two controls
+        db.save_state(&state).unwrap();
+
+        let mut loaded = db.load_state().unwrap();
+        assert_eq!(loaded.cc_mappings, state.cc_mappings);
+        assert!(!loaded.apply_cc(20, 127));
+        assert!(loaded.apply_cc(7, 0));
+        assert_eq!(loaded.master_brightness, 0.0);
+        loaded.apply_cc(8, 96);
+        assert!((loaded.master_speed - 2.0).abs() < 1e-6);
+        loaded.apply_cc(9, 127);
+        assert!(loaded.scenes.iter().all(|s| s.master_intensity == 1.0));
+        loaded.apply_cc(9, 0);
+        assert!(loaded.scenes.iter().all(|s| s.master_intensity == 0.0));
+
+        loaded.set_cc("speed", None);
+        assert_eq!(loaded.cc_for("speed"), None);
+        db.save_state(&loaded).unwrap();
+        let reloaded = db.load_state().unwrap();
+        assert_eq!((reloaded.master_brightness, reloaded.cc_mappings.len()), (0.0, 3));
+    }
+
+    #[test]
+    fn test_fixtures_round_trip() {
+        let mut db = open_test_db();
+        let mut state = create_test_state();
+        state.fixtures.push(Fixture {
+            id: 7,
+            name: "Stage Left PAR".into(),
+            universe: 3,
+            start_channel: 101,
+            channels: vec!["dimmer".into(), "red".into(), "green".into(), "blue".into()],
+            x: 0.2,
+            y: 0.8,
+            color: [0, 0, 0],
+        });
+        db.save_state(&state).unwrap();
+
+        let loaded = db.load_state().unwrap();
+        assert_eq!(loaded.fixtures.len(), 1);
+        let f = &loaded.fixtures[0];
+        assert_eq!(f.name, "Stage Left PAR");
+        assert_eq!((f.universe, f.start_channel), (3, 101));
+        assert_eq!(f.channels, state.fixtures[0].channels);
+    }
+
+    #[test]
+    fn test_load_sanitizes_pathological_mask_params() {
+        let mut db = open_test_db();
+        let mut state = create_test_state();
+        let mask = &mut state.scenes[0].masks[0];
+        mask.params.insert("radius".into(), serde_json::json!(-3.0));
+        mask.params.insert("speed".into(), serde_json::json!(1e9));
+        mask.params.insert("width".into(), serde_json::json!("wide"));
+        mask.params.insert("bar_width".into(), serde_json::Value::Null);
+        mask.params.insert("width_lfo_depth".into(), serde_json::json!(7.0));
+        mask.params.insert("color".into(), serde_json::json!([1, 2]));
+        mask.params.insert("sync".into(), serde_json::json!(true));
+        mask.params.insert("radius_lfo_hz".into(), serde_json::json!(1.0 / 60.0));
+        mask.params.insert("radius_lfo_rate_unit".into(), serde_json::json!("period"));
+        mask.params.insert("not_a_param".into(), serde_json::json!(1));
+        db.save_state(&state).unwrap();
+
+        let loaded = db.load_state().unwrap();
+        let mask = &loaded.scenes.iter().find(|s| s.id == 1).unwrap().masks[0];
+        assert_eq!(mask.params["radius"].as_f64(), Some(0.0));
+        assert_eq!(mask.params["speed"].as_f64(), Some(5.0));
+        assert_eq!(mask.params["width_lfo_depth"].as_f64(), Some(1.0));
+        assert_eq!(mask.params["sync"].as_bool(), Some(true));
+        // Slow periods entered in seconds survive as their canonical Hz
+        assert_eq!(mask.params["radius_lfo_hz"].as_f64(), Some(1.0 / 60.0));
+        assert_eq!(mask.params["radius_lfo_rate_unit"].as_str(), Some("period"));
+        // Wrong types and unknown keys are dropped so the engine falls back to defaults
+        for key in ["width", "bar_width", "color", "not_a_param"] {
+            assert!(!mask.params.contains_key(key), "{} should be dropped", key);
+        }
+    }
+
+    #[test]
+    fn test_load_clamps_absurd_pixel_counts() {
+        let mut db = open_test_db();
+        let mut state = create_test_state();
+        state.strips[0].pixel_count = 50_000_000;
+        state.strips.push(PixelStrip { id: 11, pixel_count: 0, ..PixelStrip::default() });
+        db.save_state(&state).unwrap();
+
+        let loaded = db.load_state().unwrap();
+        let huge = loaded.strips.iter().find(|s| s.id == 10).unwrap();
+        assert_eq!(huge.pixel_count, MAX_STRIP_PIXELS);
+        assert_eq!(huge.data.len(), MAX_STRIP_PIXELS);
+        let empty = loaded.strips.iter().find(|s| s.id == 11).unwrap();
+        assert_eq!(empty.pixel_count, 1);
+        assert_eq!(empty.data.len(), 1);
+    }
+
+    #[test]
+    fn test_strip_pixel_offset_round_trip() {
+        let mut db = open_test_db();
+        let mut state = create_test_state();
+        state.strips[0].pixel_offset = 170;
+        db.save_state(&state).unwrap();
+
+        let loaded = db.load_state().unwrap();
+        assert_eq!(loaded.strips[0].pixel_offset, 170);
+    }
+
+    #[test]
+    fn test_strip_mono_output_round_trip() {
+        let mut db = open_test_db();
+        let mut state = create_test_state();
+        state.strips[0].channels_per_pixel = 1;
+        state.strips[0].luma_weights = [1.0, 0.0, 0.5];
+        db.save_state(&state).unwrap();
+
+        let loaded = db.load_state().unwrap();
+        assert_eq!(loaded.strips[0].channels_per_pixel, 1);
+        assert_eq!(loaded.strips[0].luma_weights, [1.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn test_import_clamps_start_channels() {
+        let mut db = open_test_db();
+        let mut state = create_test_state();
+        state.strips[0].start_channel = 0;
+        state.fixtures.push(Fixture { id: 20, start_channel: 900, ..Fixture::default() });
+        let json = serde_json::to_string(&state).unwrap();
+        db.import_from_json(&json, false).unwrap();
+
+        let loaded = db.load_state().unwrap();
+        assert_eq!(loaded.strips[0].start_channel, 1);
+        assert_eq!(loaded.fixtures[0].start_channel, 512);
+    }
+
+    #[test]
+    fn test_load_prunes_deleted_mask_targets() {
+        let mut db = open_test_db();
+        let mut state = create_test_state();
+        state.scenes[0].masks[0].target_strips = vec![10, 999];
+        db.save_state(&state).unwrap();
+
+        let loaded = db.load_state().unwrap();
+        let mask = &loaded.scenes.iter().find(|s| s.id == 1).unwrap().masks[0];
+        assert_eq!(mask.target_strips, vec![10]);
+
+        // Deleting the only target leaves the mask on no strips, not on all of them
+        let mut state = loaded;
+        state.remove_strip(0);
+        let mask = &state.scenes.iter().find(|s| s.id == 1).unwrap().masks[0];
+        assert_eq!(mask.target_strips, vec![10]);
+        db.save_state(&state).unwrap();
+        let loaded = db.load_state().unwrap();
+        let mask = &loaded.scenes.iter().find(|s| s.id == 1).unwrap().masks[0];
+        assert_eq!(mask.target_strips, vec![10]);
+        assert!(!mask.targets_any(&loaded.strips));
+    }
+
+    #[test]
+    fn test_strip_presets_replace_by_name() {
+        let db = open_test_db();
+        let mut preset = StripPreset {
+            name: "Truss run".into(),
+            pixel_count: 120,
+            spacing: 0.004,
+            color_order: "GRB".into(),
+        };
+        db.save_strip_preset(&preset).unwrap();
+        preset.pixel_count = 240;
+        db.save_strip_preset(&preset).unwrap();
+        assert_eq!(db.load_strip_presets().unwrap(), vec![preset]);
+
+        db.delete_strip_preset("Truss run").unwrap();
+        assert!(db.load_strip_presets().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_view_bookmarks_replace_by_slot() {
+        let db = open_test_db();
+        let mut bookmark = ViewBookmark { slot: 3, offset: [12.0, -40.0], scale: 2.5 };
+        db.save_view_bookmark(&bookmark).unwrap();
+        bookmark.scale = 4.0;
+        db.save_view_bookmark(&bookmark).unwrap();
+        let other = ViewBookmark { slot: 1, offset: [0.0, 0.0], scale: 1.0 };
+        db.save_view_bookmark(&other).unwrap();
+        assert_eq!(db.load_view_bookmarks().unwrap(), vec![other, bookmark]);
+    }
+
+    #[test]
+    fn test_sacn_source_name_and_cid() {
+        let mut network = NetworkConfig { source_name: "  ".into(), ..NetworkConfig::default() };
+        assert_eq!(network.sacn_source_name(), "Lightspeed");
+        network.source_name = "é".repeat(40);
+        let name = network.sacn_source_name();
+        assert!(name.len() <= SACN_SOURCE_NAME_MAX && name.chars().all(|c| c == 'é'));
+
+        // A CID is generated once and then kept
+        assert!(network.ensure_source_cid());
+        let cid = network.source_cid.clone();
+        assert!(uuid::Uuid::parse_str(&cid).is_ok());
+        assert!(!network.ensure_source_cid());
+        assert_eq!(network.source_cid, cid);
+
+        // Saving the CID on its own leaves the rest of the config as it was
+        let mut db = open_test_db();
+        db.save_state(&create_test_state()).unwrap();
+        db.save_source_cid(&cid).unwrap();
+        let loaded = db.load_state().unwrap();
+        assert_eq!(loaded.network.source_cid, cid);
+        assert_eq!(loaded.strips.len(), 1);
+        assert_eq!(loaded.scenes.len(), 2);
+    }
+
+    #[test]
+    fn test_window_geometry_round_trip() {
+        let db = open_test_db();
+        assert_eq!(db.load_window_geometry().unwrap(), None);
+
+        let geometry = WindowGeometry {
+            position: Some([-1600.0, 40.0]),
+            inner_size: [1400.0, 900.0],
+            maximized: true,
+        };
+        db.save_window_geometry(&geometry).unwrap();
+        assert_eq!(db.load_window_geometry().unwrap(), Some(geometry));
+
+        let unplaced = WindowGeometry { position: None, ..geometry };
+        db.save_window_geometry(&unplaced).unwrap();
+        assert_eq!(db.load_window_geometry().unwrap(), Some(unplaced));
+    }
+}
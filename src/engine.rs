@@ -1,11 +1,32 @@
-use crate::model::{AppState, Mask, PixelStrip, NetworkConfig, GlobalEffect};
+use crate::model::{AppState, Mask, Scene, PixelStrip, NetworkConfig, GlobalEffect, Fixture, AudioConfig};
+use crate::model::{color_order_indices, dimmer_curve_lut, AUDIO_MOD_TARGETS, MASTER_SPEED_RANGE, ENGINE_TICK_HZ_RANGE, FLYWHEEL_JUMP_BEATS_RANGE, FLYWHEEL_RECOVERY_SECS_RANGE, FLYWHEEL_NUDGE_RANGE, PHASE_CORRECTION_RANGE, PHASE_DECAY_RANGE, MANUAL_BPM_RANGE, BEATS_PER_BAR_RANGE, TRIGGER_THRESHOLD_RANGE, TRIGGER_COOLDOWN_SECS_RANGE};
 use crate::audio::AudioListener;
+use crate::recording::{Player, Recorder};
 use sacn::source::SacnSource;
 use std::time::Instant;
 use log::{info, debug, warn, error};
 
 use rusty_link::{AblLink, SessionState};
 
+/// Fixed-timestep animation clock: real frame time is accumulated and consumed in whole
+/// ticks, so effect timing doesn't jitter with UI frame pacing
+#[derive(Default)]
+struct TickClock {
+    time: f64,        // Animation time consumed so far, always a whole number of ticks
+    accumulator: f64, // Real time not yet consumed (under one tick)
+}
+
+impl TickClock {
+    /// Add `real_dt` seconds of wall time; returns the animation time advanced this frame
+    fn advance(&mut self, real_dt: f64, tick: f64) -> f64 {
+        self.accumulator += real_dt.max(0.0);
+        let step_dt = (self.accumulator / tick).floor() * tick;
+        self.accumulator -= step_dt;
+        self.time += step_dt;
+        step_dt
+    }
+}
+
 struct SparklePixel {
     strip_id: u64,
     pixel_index: usize,
@@ -19,6 +40,69 @@ struct PulseState {
     last_update: f32,   // Time of last update
 }
 
+/// Detected BPM must stay within this many BPM of where it settled to count as steady
+const BPM_LATCH_TOLERANCE: f64 = 1.0;
+/// Seconds a steady detected BPM must hold before it is auto-latched
+const BPM_LATCH_SECS: f64 = 8.0;
+
+/// Watches the detected audio BPM and reports it once it has held steady long enough
+#[derive(Default)]
+struct BpmLatch {
+    candidate: f64,   // BPM the detector has settled around, 0 = none
+    steady_secs: f64, // How long it has stayed within tolerance
+    latched: f64,     // Last BPM reported, so a steady tempo is only latched once
+}
+
+impl BpmLatch {
+    /// Feed the current detected BPM (0 while there is no stable estimate) and the wall time
+    /// since the last call; returns the BPM to latch, rounded to 0.1
+    fn update(&mut self, bpm: f64, dt: f64) -> Option<f64> {
+        if bpm <= 30.0 {
+            self.candidate = 0.0;
+            self.steady_secs = 0.0;
+            return None;
+        }
+        if (bpm - self.candidate).abs() > BPM_LATCH_TOLERANCE {
+            self.candidate = bpm;
+            self.steady_secs = 0.0;
+            return None;
+        }
+        self.steady_secs += dt;
+        if self.steady_secs < BPM_LATCH_SECS || (self.candidate - self.latched).abs() <= BPM_LATCH_TOLERANCE {
+            return None;
+        }
+        self.latched = self.candidate;
+        Some((self.candidate * 10.0).round() / 10.0)
+    }
+}
+
+/// Fires the audio trigger on a strong onset, at most once per cooldown
+#[derive(Default)]
+struct AudioTrigger {
+    since_fire: Option<f64>, // Wall seconds since it last fired
+}
+
+impl AudioTrigger {
+    /// Feed this frame's onset strength (0 without an onset) and the wall time since the last
+    /// call; true when the trigger fires
+    fn update(&mut self, strength: f32, dt: f64, threshold: f32, cooldown_secs: f32) -> bool {
+        if let Some(since) = &mut self.since_fire {
+            *since += dt;
+        }
+        if strength < threshold || self.since_fire.is_some_and(|since| since < cooldown_secs as f64) {
+            return false;
+        }
+        self.since_fire = Some(0.0);
+        true
+    }
+}
+
+struct CometState {
+    progress: f32,       // 0..1 along the sweep, head enters at 0 and the tail leaves at 1
+    last_update: f32,    // Time of last update
+    last_beat: f64,      // Beat of last update, for beat-synced sweeps
+}
+
 struct GlitchPixel {
     strip_id: u64,
     pixel_index: usize,
@@ -26,30 +110,95 @@ struct GlitchPixel {
     color: [u8; 3],
 }
 
+/// Output health figures, averaged over the last second
+#[derive(Clone, Debug, Default)]
+pub struct OutputStats {
+    pub fps: f32,
+    pub packets_per_sec: f32,
+    pub bytes_per_sec: f32, // Estimated from E1.31 packet size, excluding UDP/IP headers
+    pub universes: usize,
+    pub send_errors: u64, // Total since startup
+    pub redundant_active: bool, // Backup sender bound and sending
+    pub redundant_send_errors: u64, // Backup path failures (registration or send), total since startup
+}
+
+/// Beat clock internals for the sync diagnostics panel, as of the last update
+#[derive(Clone, Debug, Default)]
+pub struct SyncDiagnostics {
+    pub link_beat: f64,      // Raw Link beat (latency compensated), before the flywheel
+    pub flywheel_beat: f64,  // Flywheel beat, before audio phase correction and the user offset
+    pub link_peers: u64,
+    pub in_sync: bool,       // False while the flywheel disagrees with Link by more than half a beat
+    pub sync_error_secs: f32, // How long the flywheel has been out of sync
+    pub phase_offset: f64,   // Accumulated audio phase correction, in beats
+    pub last_phase_error: f64, // Phase error measured at the last on-beat audio onset, in beats
+}
+
+/// E1.31 data packet overhead before the DMX payload (root + framing + DMP layers)
+const E131_HEADER_BYTES: usize = 126;
+
+/// Seconds without a tempo source (or out of sync) before sync counts as lost
+const SYNC_LOSS_TIMEOUT_SECS: f32 = 4.0;
+/// Seconds for the "fade" sync loss behavior to ramp output fully down (or back up)
+const SYNC_LOSS_FADE_SECS: f32 = 3.0;
+
 pub struct LightingEngine {
     sender: SacnSource,
+    redundant_sender: Option<SacnSource>, // Second source on the backup network, sends the same universes
+    redundant_registered: std::collections::HashSet<u16>,
     link: AblLink,
     registered_universes: std::collections::HashSet<u16>,
+    over_limit_universes: std::collections::BTreeSet<u16>, // Universes with output dropped by max_universes
+    muted_blacked: std::collections::HashSet<u16>, // Muted universes that have had their final black frame
     bind_ip: Option<String>,
     pub speed: f32,
     pub latency_ms: f32,
     pub use_flywheel: bool,
     pub hybrid_sync: bool, 
     pub audio_sensitivity: f32,
+    pub beat_offset: f64, // User phase trim in beats, applied to every animation beat
+    sync_tuning: AudioConfig, // Flywheel/phase constants from AppState, clamped to their ranges
+    source_priority: Vec<String>, // Tempo sources to follow, most preferred first
+    pub force_white: bool, // Panic/test: every pixel full white, bypassing scenes, masters and smoothing
+    pub test_pattern: Option<String>, // Commissioning override, see TEST_PATTERNS; bypasses scenes like force_white
+    pub test_pattern_strip: Option<u64>, // Limit the test pattern to this strip; the rest go dark
+    pub frozen: bool, // Hold the live image: skip rendering but keep sending the held frame
+    pub mask_phase_preview: Option<(u64, f64)>, // (mask id, cycle phase 0..1) replacing that mask's clock
+    frozen_frame: Option<Vec<Vec<[u8; 3]>>>, // Strip (then fixture) pixels captured when freeze went on
+    pub max_flash_hz: f32, // Safety limiter, synced from AppState
+    pub max_flash_contrast: f32,
+    flash_limited: bool, // An effect asked for more than the safety limits this frame
+    tempo: f64, // Effective BPM used by the flywheel this frame
     audio_listener: Option<AudioListener>,
     was_peaking: bool, // For edge detection
-    pub current_beat: u8, // 1, 2, 3, 4
-    start_time: Instant,
+    pub current_beat: u8, // Beat within the Link bar, 1..=beats_per_bar
+    clock: TickClock,
+    pub tick_hz: f32, // Engine tick rate, synced from AppState
+    mask_compositing: String, // Global mask overlap policy, synced from AppState
     last_network: NetworkConfig,
     flywheel_beat: f64,
+    link_leads: bool, // Link was the selected tempo source on the last update
+    last_send_error: Option<Instant>, // Most recent primary sACN send failure
     last_update: std::time::Instant,
     sync_error_timer: f32, // How long we've been out of sync
+    link_beat: f64, // Raw Link beat from the last update, for diagnostics
+    link_peers: u64,
+    last_phase_error: f64, // Last audio phase error, for diagnostics
     sync_mode: bool, // true if locked, false if drifting/error
+    unsynced_since: Option<Instant>, // When sync_mode last went false
+    last_link_peer_time: Option<Instant>, // Last frame with at least one Link peer
+    sync_lost: bool,
+    held_clock: Option<(f32, f64)>, // (time, beat) frozen by the "hold" sync loss behavior
+    sync_loss_fade: f32, // Output multiplier ramped by the "fade" sync loss behavior
 
     // Audio BPM
     last_tap_time: Option<Instant>,
     tap_intervals: Vec<f64>,
     pub audio_bpm: f64,
+    bpm_latch: BpmLatch,
+    latched_bpm: Option<f64>, // Auto-latched BPM waiting for the UI to store it
+    audio_trigger: AudioTrigger,
+    audio_triggered: bool, // The audio trigger fired; the UI dispatches its action
 
     // Audio Snap Phase Tracking (PLL-style)
     last_audio_beat_time: Option<Instant>,
@@ -60,31 +209,37 @@ pub struct LightingEngine {
     sparkle_states: Vec<SparklePixel>,
     // Pulse Wave effect state tracking
     pulse_states: Vec<PulseState>,
+    comet_states: std::collections::HashMap<(u64, usize), CometState>, // Per (scene id, effect index)
+    comet_heads: Vec<(f32, f32)>, // World-space heads of the Comets rendered this frame
     // Glitch Sparkle effect state tracking
     glitch_states: Vec<GlitchPixel>,
     glitch_sparkle_accumulator: f32,
     // Burst effect radius smoothing per-mask
     burst_radius_states: std::collections::HashMap<u64, f32>,
+    // Temporary masks removed since the UI last asked
+    expired_masks: Vec<u64>,
+    // Most recently selected scene, for the "last" no-selection fallback
+    last_scene_id: Option<u64>,
+    // Output statistics: counters for the current window, last published figures
+    stats_window_start: Instant,
+    stats_frames: u32,
+    stats_packets: u32,
+    stats_bytes: usize,
+    stats: OutputStats,
+    // Beat at which one-shot effects were last fired; None = not armed
+    fire_beat: Option<f64>,
+    // Previous smoothed output per strip (float so slow fades don't stall on rounding)
+    smoothed_frames: std::collections::HashMap<u64, Vec<[f32; 3]>>,
+    // Output recording and playback (see the recording module)
+    recorder: Option<Recorder>,
+    player: Option<(Player, Instant)>, // Player and when playback started
+    recording_message: Option<String>, // Why recording or playback stopped on its own
 }
 
 impl LightingEngine {
     pub fn new() -> Self {
-        info!("[LIGHTS] Initializing sACN (E1.31) network stack...");
-
-        let local_addr = std::net::SocketAddr::from(([0, 0, 0, 0], 0));
-        debug!("[LIGHTS] Binding to address: {}", local_addr);
-
-        let sender = SacnSource::with_ip("Lightspeed", local_addr)
-            .unwrap_or_else(|e| {
-                error!("[LIGHTS] Failed to create sACN sender: {:?}", e);
-                warn!("[LIGHTS] Attempting fallback configuration...");
-                // Try with explicit IPv4 any address as fallback
-                SacnSource::with_ip("Lightspeed", "0.0.0.0:0".parse().unwrap())
-                    .expect("Critical: Cannot initialize network stack")
-            });
-
-        info!("[LIGHTS] sACN sender initialized successfully");
-        debug!("[LIGHTS] Source name: 'Lightspeed', ready for multicast/unicast");
+        let network = NetworkConfig::default();
+        let sender = Self::create_sender(&network);
 
         let link = AblLink::new(120.0);
         link.enable(true);
@@ -92,38 +247,168 @@ impl LightingEngine {
         
         Self {
             sender,
+            redundant_sender: None,
+            redundant_registered: std::collections::HashSet::new(),
             link,
             registered_universes: std::collections::HashSet::new(),
+            over_limit_universes: std::collections::BTreeSet::new(),
+            muted_blacked: std::collections::HashSet::new(),
             bind_ip: None,
             speed: 1.0,
             latency_ms: 0.0,
             use_flywheel: true,
             hybrid_sync: false,
             audio_sensitivity: 0.5,
+            beat_offset: 0.0,
+            sync_tuning: AudioConfig::default(),
+            source_priority: AudioConfig::default().beat_source_order(),
+            force_white: false,
+            test_pattern: None,
+            test_pattern_strip: None,
+            frozen: false,
+            mask_phase_preview: None,
+            frozen_frame: None,
+            max_flash_hz: 3.0,
+            max_flash_contrast: 0.8,
+            flash_limited: false,
+            tempo: 120.0,
             audio_listener: AudioListener::new(), // Try to init
             was_peaking: false,
             current_beat: 1,
-            start_time: Instant::now(),
-            last_network: NetworkConfig::default(),
+            clock: TickClock::default(),
+            tick_hz: 120.0,
+            mask_compositing: "add".to_string(),
+            last_network: network,
             flywheel_beat: 0.0,
+            link_leads: false,
+            last_send_error: None,
             last_update: Instant::now(),
             sync_error_timer: 0.0,
+            link_beat: 0.0,
+            link_peers: 0,
+            last_phase_error: 0.0,
             sync_mode: true,
+            unsynced_since: None,
+            last_link_peer_time: None,
+            sync_lost: false,
+            held_clock: None,
+            sync_loss_fade: 1.0,
             last_tap_time: None,
             tap_intervals: Vec::new(),
             audio_bpm: 0.0,
+            bpm_latch: BpmLatch::default(),
+            latched_bpm: None,
+            audio_trigger: AudioTrigger::default(),
+            audio_triggered: false,
             last_audio_beat_time: None,
             phase_offset: 0.0,
             last_onset_time: None,
             sparkle_states: Vec::new(),
             pulse_states: Vec::new(),
+            comet_states: std::collections::HashMap::new(),
+            comet_heads: Vec::new(),
             glitch_states: Vec::new(),
             glitch_sparkle_accumulator: 0.0,
             burst_radius_states: std::collections::HashMap::new(),
+            expired_masks: Vec::new(),
+            last_scene_id: None,
+            stats_window_start: Instant::now(),
+            stats_frames: 0,
+            stats_packets: 0,
+            stats_bytes: 0,
+            stats: OutputStats::default(),
+            fire_beat: None,
+            smoothed_frames: std::collections::HashMap::new(),
+            recorder: None,
+            player: None,
+            recording_message: None,
+        }
+    }
+
+    /// Build an sACN source bound to the configured interface with the configured multicast TTL
+    fn create_sender(network: &NetworkConfig) -> SacnSource {
+        info!("[LIGHTS] Initializing sACN (E1.31) network stack...");
+
+        // Binding to a specific local IPv4 selects the interface multicast leaves from
+        let bind_ip: std::net::Ipv4Addr = match network.multicast_interface.as_deref().map(str::trim) {
+            Some(iface) if !iface.is_empty() => iface.parse().unwrap_or_else(|_| {
+                warn!("[LIGHTS] Invalid interface address '{}', using default route", iface);
+                std::net::Ipv4Addr::UNSPECIFIED
+            }),
+            _ => std::net::Ipv4Addr::UNSPECIFIED,
+        };
+
+        let sender = Self::bind_sender(bind_ip, network)
+            .unwrap_or_else(|| {
+                warn!("[LIGHTS] Attempting fallback configuration...");
+                // Try with explicit IPv4 any address as fallback
+                SacnSource::with_ip("Lightspeed", "0.0.0.0:0".parse().unwrap())
+                    .expect("Critical: Cannot initialize network stack")
+            });
+
+        info!("[LIGHTS] sACN sender initialized successfully");
+        debug!("[LIGHTS] Source name: '{}', ready for multicast/unicast, TTL {}", network.sacn_source_name(), network.multicast_ttl);
+        sender
+    }
+
+    /// Backup sACN source for redundant output. Unlike the main sender it never falls back to
+    /// the default route: a backup on the same network as the primary isn't redundant.
+    fn create_redundant_sender(network: &NetworkConfig) -> Option<SacnSource> {
+        if !network.redundant_output {
+            return None;
+        }
+        let iface = network.redundant_interface.as_deref().map(str::trim).unwrap_or("");
+        let Ok(bind_ip) = iface.parse::<std::net::Ipv4Addr>() else {
+            warn!("[LIGHTS] Redundant output needs a valid interface address, got '{}'", iface);
+            return None;
+        };
+        let sender = Self::bind_sender(bind_ip, network)?;
+        info!("[LIGHTS] Redundant sACN sender bound to {}", bind_ip);
+        Some(sender)
+    }
+
+    fn bind_sender(bind_ip: std::net::Ipv4Addr, network: &NetworkConfig) -> Option<SacnSource> {
+        let local_addr = std::net::SocketAddr::from((bind_ip, 0));
+        debug!("[LIGHTS] Binding to address: {}", local_addr);
+
+        // Without a saved CID (first run, before the state is loaded) the library picks a random one
+        let name = network.sacn_source_name();
+        let sender = match uuid::Uuid::parse_str(&network.source_cid) {
+            Ok(cid) => SacnSource::with_cid_ip(&name, cid, local_addr),
+            Err(_) => SacnSource::with_ip(&name, local_addr),
+        };
+        let mut sender = sender
+            .map_err(|e| error!("[LIGHTS] Failed to create sACN sender on {}: {:?}", local_addr, e))
+            .ok()?;
+        if let Err(e) = sender.set_multicast_ttl(network.multicast_ttl) {
+            warn!("[LIGHTS] Failed to set multicast TTL {}: {:?}", network.multicast_ttl, e);
         }
+        Some(sender)
     }
 
     pub fn update(&mut self, state: &mut AppState) {
+        // Rebuild the sender when the interface, multicast TTL or source identity changes, or
+        // the universe limit drops below what is already registered
+        let identity_changed = state.network.sacn_source_name() != self.last_network.sacn_source_name()
+            || state.network.source_cid != self.last_network.source_cid;
+        if state.network.multicast_ttl != self.last_network.multicast_ttl
+            || state.network.multicast_interface != self.last_network.multicast_interface
+            || identity_changed
+            || self.registered_universes.len() > state.network.max_universes as usize
+        {
+            self.sender = Self::create_sender(&state.network);
+            self.registered_universes.clear();
+        }
+        if state.network.redundant_output != self.last_network.redundant_output
+            || state.network.redundant_interface != self.last_network.redundant_interface
+            || state.network.multicast_ttl != self.last_network.multicast_ttl
+            || identity_changed
+            || self.redundant_registered.len() > state.network.max_universes as usize
+        {
+            self.redundant_sender = Self::create_redundant_sender(&state.network);
+            self.redundant_registered.clear();
+        }
+        self.last_network = state.network.clone();
 
 
         // Sync Audio Params from State
@@ -131,30 +416,41 @@ impl LightingEngine {
         self.use_flywheel = state.audio.use_flywheel;
         self.hybrid_sync = state.audio.hybrid_sync;
         self.audio_sensitivity = state.audio.sensitivity;
+        self.beat_offset = state.audio.beat_offset as f64;
+        self.sync_tuning = clamped_sync_tuning(&state.audio);
+        self.source_priority = state.audio.beat_source_order();
+        self.max_flash_hz = state.max_flash_hz;
+        self.tick_hz = state.engine_tick_hz;
+        if self.mask_compositing != state.mask_compositing {
+            self.mask_compositing = state.mask_compositing.clone();
+        }
+        self.max_flash_contrast = state.max_flash_contrast;
+        self.speed = state.master_speed.clamp(*MASTER_SPEED_RANGE.start(), *MASTER_SPEED_RANGE.end());
+        self.flash_limited = false;
+        self.comet_heads.clear();
 
         let now = Instant::now();
-        let dt = now.duration_since(self.last_update).as_secs_f64();
+        let tick = 1.0 / self.tick_hz.clamp(*ENGINE_TICK_HZ_RANGE.start(), *ENGINE_TICK_HZ_RANGE.end()) as f64;
+        let wall_dt = now.duration_since(self.last_update).as_secs_f64();
+        let dt = self.clock.advance(wall_dt, tick);
         self.last_update = now;
-        let t = self.start_time.elapsed().as_secs_f32();
+        let t = self.clock.time as f32;
         
         // Capture Link Beat
         let mut session_state = SessionState::new();
         self.link.capture_app_session_state(&mut session_state);
-        let link_micros = self.link.clock_micros();
-        // Adjust for latency: Visuals are ahead, so we need to query 'earlier' time? 
-        // No, if visuals are ahead (Beat 2.0 displayed when Audio is 1.9), 
-        // we want to display Beat 1.9.
-        // Beat increases with time. 
-        // If we subtract delay from 'now', we get a smaller time -> smaller beat. 
-        // Correct.
-        let adjusted_micros = (link_micros as i64 - (self.latency_ms * 1000.0) as i64).max(0) as u64;
-        
-        let link_beat = session_state.beat_at_time(adjusted_micros as i64, 1.0);
-        let phase = session_state.phase_at_time(adjusted_micros as i64, 4.0); // Quantum 4 for bars
-        self.current_beat = (phase.floor() as u8 % 4) + 1;
+        let adjusted_micros = self.adjusted_link_micros();
+
+        let link_beat = session_state.beat_at_time(adjusted_micros, 1.0);
+        // Link quantum is the bar length, so phase runs 0..beats_per_bar
+        let beats_per_bar = self.sync_tuning.beats_per_bar;
+        let phase = session_state.phase_at_time(adjusted_micros, beats_per_bar as f64);
+        self.current_beat = beat_in_bar(phase, beats_per_bar);
         
         let tempo = session_state.tempo();
         let link_peers = self.link.num_peers();
+        self.link_beat = link_beat;
+        self.link_peers = link_peers;
 
         // Hybrid Sync / Audio logic
         let mut force_snap = false;
@@ -164,14 +460,21 @@ impl LightingEngine {
                 (state.is_onset, state.onset_strength, state.current_volume)
             } else {
                 // Fallback to legacy volume-based detection
-                let vol = audio.current_volume.lock()
-                    .map(|v| *v)
-                    .unwrap_or(0.0);
+                let vol = audio.current_volume.load();
                 let threshold = 0.5 - (self.audio_sensitivity * 0.45);
                 let is_peak = vol > threshold && !self.was_peaking;
                 (is_peak, if is_peak { 1.0 } else { 0.0 }, vol)
             };
 
+            // Audio trigger: a strong onset (a drop, a hit after a break) fires the configured action
+            let strength = if is_onset { onset_strength } else { 0.0 };
+            let tuning = &self.sync_tuning;
+            if tuning.trigger_action != "off"
+                && self.audio_trigger.update(strength, wall_dt, tuning.trigger_threshold, tuning.trigger_cooldown_secs)
+            {
+                self.audio_triggered = true;
+            }
+
             // Apply sensitivity threshold to onset strength
             let sensitivity_threshold = 0.5 - (self.audio_sensitivity * 0.45);
             let beat_detected = is_onset && onset_strength > sensitivity_threshold;
@@ -236,13 +539,7 @@ impl LightingEngine {
                     // 2. Phase correction for hybrid sync
                     if self.hybrid_sync {
                         // Get current effective BPM
-                        let current_bpm = if self.link.num_peers() > 0 {
-                            tempo
-                        } else if self.audio_bpm > 30.0 {
-                            self.audio_bpm
-                        } else {
-                            120.0 * self.speed as f64
-                        };
+                        let current_bpm = self.source_tempo(select_beat_source(&self.source_priority, link_peers, self.audio_bpm), tempo);
 
                         // Only do phase correction if we have a stable tempo estimate
                         // (at least 4 consistent beat intervals)
@@ -272,8 +569,10 @@ impl LightingEngine {
                                     1.0 - current_phase  // We're before the beat, push forward
                                 };
 
-                                // Gentle correction - only correct 40% per beat
-                                let correction = phase_error * 0.4;
+                                self.last_phase_error = phase_error;
+
+                                // Gentle correction - only correct part of the error per beat
+                                let correction = phase_error * self.sync_tuning.phase_correction as f64;
                                 self.phase_offset += correction;
                             }
 
@@ -289,20 +588,26 @@ impl LightingEngine {
             self.was_peaking = vol > (0.5 - self.audio_sensitivity * 0.45);
         }
 
-        // Determine effective tempo
-        let effective_tempo = if link_peers > 0 {
-             tempo // Link Tempo
-        } else if self.audio_bpm > 30.0 {
-             self.audio_bpm // Audio Tempo
-        } else {
-             120.0 * self.speed as f64 // Manual Speed (Multiplier on 120 default?) 
-             // Wait, self.speed was "Master Speed" in UI (0.1..5.0).
-             // If we treat manual speed as multiplier on 120, that works.
-             // Or we can add a base tempo field? For now, 120 * speed.
-        };
+        // Auto-latch: a detected tempo that has held steady becomes the manual fallback
+        let steady_bpm = if self.tap_intervals.len() >= 4 { self.audio_bpm } else { 0.0 };
+        if let Some(bpm) = self.bpm_latch.update(steady_bpm, wall_dt) {
+            if self.sync_tuning.auto_latch_bpm {
+                self.latched_bpm = Some(bpm);
+            }
+        }
+
+        // Determine effective tempo from the first available source in priority order
+        let source = select_beat_source(&self.source_priority, link_peers, self.audio_bpm);
+        let effective_tempo = self.source_tempo(source, tempo);
+        let link_leads = source == "link";
+        self.link_leads = link_leads;
 
-        // Flywheel Logic (only run if we didn't just hard-snap)
-        if !self.use_flywheel && !force_snap {
+        self.tempo = effective_tempo;
+
+        // Flywheel Logic (only run if we didn't just hard-snap). Turning the flywheel off only
+        // affects Link: when Link isn't leading, the flywheel is what advances the audio/manual beat.
+        // With it off, flywheel_beat just mirrors Link so switching back on doesn't jump.
+        if !self.use_flywheel && link_leads && !force_snap {
             self.flywheel_beat = link_beat;
             self.sync_mode = true;
         } else if !force_snap {
@@ -313,10 +618,10 @@ impl LightingEngine {
             let diff = (link_beat - predicted_beat).abs();
 
             // Configurable Thresholds
-            let error_threshold = 0.5; // If off by more than half a beat, consider it an error (jump)
-            let recovery_time = 1.0; // Seconds to wait before snapping (approx 2 beats at 120bpm)
+            let error_threshold = self.sync_tuning.flywheel_jump_beats as f64; // Off by more than this counts as a jump
+            let recovery_time = self.sync_tuning.flywheel_recovery_secs; // Seconds to wait before snapping
 
-            if diff > error_threshold && link_peers > 0 {
+            if diff > error_threshold && link_leads {
                 // Significant deviation from Link
                 self.sync_error_timer += dt as f32;
                 self.sync_mode = false;
@@ -336,9 +641,9 @@ impl LightingEngine {
                 self.sync_error_timer = 0.0;
                 self.sync_mode = true;
 
-                // If Link is available, gently nudge towards it
-                if link_peers > 0 {
-                    let lerp_factor = 0.1; // Smooth correction
+                // If Link is leading, gently nudge towards it
+                if link_leads {
+                    let lerp_factor = self.sync_tuning.flywheel_nudge as f64; // Smooth correction
                     self.flywheel_beat = predicted_beat + (link_beat - predicted_beat) * lerp_factor;
                 } else {
                     // No Link - just use predicted beat (audio-driven or manual)
@@ -350,13 +655,13 @@ impl LightingEngine {
         // Gradually decay phase offset when not receiving audio beats
         // This prevents permanent drift if audio stops
         if self.hybrid_sync {
-            let decay_rate = 0.02; // Decay 2% per frame
+            let decay_rate = self.sync_tuning.phase_decay as f64; // Fraction released per frame
             self.phase_offset *= 1.0 - decay_rate;
         }
 
         // Use flywheel_beat + phase_offset for animations
         // Safety check: ensure beat is valid (not NaN or infinite)
-        let raw_beat = self.flywheel_beat + self.phase_offset;
+        let raw_beat = self.flywheel_beat + self.phase_offset + self.beat_offset;
         let beat = if raw_beat.is_finite() {
             raw_beat
         } else {
@@ -366,109 +671,220 @@ impl LightingEngine {
             0.0
         };
 
-        // 1. Clear all strips
-        for strip in &mut state.strips {
-            strip.data = vec![[0, 0, 0]; strip.pixel_count];
+        // Sync loss: every tempo source we had has gone quiet, or we've been drifting too long
+        if link_peers > 0 {
+            self.last_link_peer_time = Some(now);
+        }
+        if self.sync_mode {
+            self.unsynced_since = None;
+        } else if self.unsynced_since.is_none() {
+            self.unsynced_since = Some(now);
+        }
+        let timed_out = |since: Option<Instant>| since.is_some_and(|i| i.elapsed().as_secs_f32() >= SYNC_LOSS_TIMEOUT_SECS);
+        let last_tempo_source = self.last_link_peer_time.max(self.last_onset_time);
+        self.sync_lost = timed_out(last_tempo_source) || timed_out(self.unsynced_since);
+
+        let (t, beat) = if self.sync_lost && state.sync_loss_behavior == "hold" {
+            *self.held_clock.get_or_insert((t, beat))
+        } else {
+            self.held_clock = None;
+            (t, beat)
+        };
+        let fade_step = dt as f32 / SYNC_LOSS_FADE_SECS;
+        self.sync_loss_fade = if self.sync_lost && state.sync_loss_behavior == "fade" {
+            (self.sync_loss_fade - fade_step).max(0.0)
+        } else {
+            (self.sync_loss_fade + fade_step).min(1.0)
+        };
+
+        // Temporary masks leave the show once their lifetime runs out, taking their state with them
+        let expired = expire_masks(&mut state.scenes, t as f64, beat);
+        if !expired.is_empty() {
+            let scenes = &state.scenes;
+            self.burst_radius_states.retain(|id, _| scenes.iter().any(|s| s.masks.iter().any(|m| m.id == *id)));
+            self.expired_masks.extend(expired);
+        }
+
+        // Disarm one-shots once even the slowest rate (4 bars) has finished its cycle
+        if self.fire_beat.is_some_and(|fb| beat - fb >= 16.0) {
+            self.fire_beat = None;
         }
 
-        // 2. Apply Scene or fallback to raw masks
-        if let Some(sel_id) = state.selected_scene_id {
-            if let Some(scene) = state.scenes.iter().find(|s| s.id == sel_id).cloned() {
-                match scene.kind.as_str() {
-                    "Masks" => {
+        // Fixtures render as one-pixel strips appended after the real ones
+        let strip_count = state.strips.len();
+        let fixture_proxies: Vec<PixelStrip> = state.fixtures.iter().map(Fixture::proxy_strip).collect();
+        state.strips.extend(fixture_proxies);
+
+        if self.frozen {
+            // Freeze: capture what's on the rig once, then hold it instead of rendering
+            let held = self.frozen_frame
+                .get_or_insert_with(|| capture_frame(&state.strips[..strip_count], &state.fixtures));
+            restore_frame(&mut state.strips, held);
+        } else {
+            self.frozen_frame = None;
+
+            // 1. Clear all strips
+            clear_strips(&mut state.strips);
+
+            // 2. Apply Scene (or the configured no-selection fallback)
+            let scene_id = match state.selected_scene_id {
+                Some(sel_id) => {
+                    self.last_scene_id = Some(sel_id);
+                    Some(sel_id)
+                }
+                None => match state.no_selection_mode.as_str() {
+                    "last" => self.last_scene_id,
+                    "scene" => state.fallback_scene_id,
+                    _ => None, // "black"
+                },
+            };
+            if let Some(scene) = scene_id.and_then(|id| state.scenes.iter().find(|s| s.id == id)).cloned() {
+                if scene.kind != "Blackout" {
+                    // Global effects form the base layer, masks composite on top
+                    for (idx, config) in scene.global_effects.iter().enumerate() {
+                         self.apply_global_effect(&config.effect, &mut state.strips, t, beat, config.targets.as_ref(), (scene.id, idx));
+                    }
+                    if scene.compositing == "add" || scene.global_effects.is_empty() || scene.masks.is_empty() {
                         for mask in &scene.masks {
-                            self.apply_mask_to_strips(mask, &mut state.strips, t, beat);
+                            self.apply_mask_to_strips(mask, &mut state.strips, t, beat, scene.audio_reactive);
                         }
-                    }
-                    "Global" => {
-                        for config in &scene.global_effects {
-                             self.apply_global_effect(&config.effect, &mut state.strips, t, beat, config.targets.as_ref());
+                    } else {
+                        // Masks render into their own layer so they can be layered against the base
+                        let base: Vec<Vec<[u8; 3]>> = state.strips.iter_mut()
+                            .map(|s| { let len = s.data.len(); std::mem::replace(&mut s.data, vec![[0, 0, 0]; len]) })
+                            .collect();
+                        for mask in &scene.masks {
+                            self.apply_mask_to_strips(mask, &mut state.strips, t, beat, scene.audio_reactive);
                         }
-                    }
-                    _ => {
-                        for mask in &state.masks {
-                            self.apply_mask_to_strips(mask, &mut state.strips, t, beat);
+                        let base_over = scene.compositing == "base_over";
+                        for (strip, base) in state.strips.iter_mut().zip(base) {
+                            for (px, b) in strip.data.iter_mut().zip(base) {
+                                *px = composite_pixel(b, *px, base_over);
+                            }
                         }
                     }
                 }
-            } else {
-                // Selected scene not found, fallback
-                for mask in &state.masks {
-                    self.apply_mask_to_strips(mask, &mut state.strips, t, beat);
+                // Blackout: strips were already cleared above, output stays at zero
+
+                // Scene submaster scales everything the scene rendered
+                scale_strips(&mut state.strips, scene.master_intensity);
+            }
+            // No scene resolved: strips stay black
+
+            scale_strips(&mut state.strips, state.master_brightness);
+
+            if self.sync_loss_fade < 1.0 {
+                scale_strips(&mut state.strips, self.sync_loss_fade);
+            }
+
+            // Temporal smoothing after compositing, before the wire
+            self.smooth_strips(&mut state.strips, state.output_smoothing, dt as f32);
+        }
+
+        if let Some(pattern) = &self.test_pattern {
+            render_test_pattern(pattern, &mut state.strips, self.clock.time as f32);
+            if let Some(only) = self.test_pattern_strip {
+                for strip in state.strips.iter_mut().filter(|s| s.id != only) {
+                    strip.data.fill([0, 0, 0]);
                 }
             }
-        } else {
-            // No scene selected: use masks directly
-            for mask in &state.masks {
-                self.apply_mask_to_strips(mask, &mut state.strips, t, beat);
+        }
+
+        if self.force_white {
+            for strip in &mut state.strips {
+                strip.data.fill([255, 255, 255]);
             }
         }
 
-        // 3. Send to sACN
-        // Coalesce data by universe
-        let mut universe_data: std::collections::HashMap<u16, Vec<u8>> = std::collections::HashMap::new();
-        
-        let global_universe_offset = state.network.universe.saturating_sub(1);
-
-        for strip in &state.strips {
-             // specific strip universe + global offset (clamped to valid sACN range 1-63999)
-             let u = strip.universe.saturating_add(global_universe_offset).min(63999).max(1);
-
-             // sACN allows multiple strips in one universe if channels don't overlap
-             let start = (strip.start_channel as usize).saturating_sub(1);
-             
-             // Ensure we have a buffer (512 bytes for DMX)
-             let entry = universe_data.entry(u).or_insert_with(|| vec![0; 512]);
-             
-             for (i, pixel) in strip.data.iter().enumerate() {
-                 let idx = start + i * 3;
-                 // Bounds check: ensure idx, idx+1, idx+2 are all valid
-                 if let Some(max_idx) = idx.checked_add(2) {
-                     if max_idx < entry.len() {
-                         match strip.color_order.as_str() {
-                             "GRB" => {
-                                 entry[idx] = pixel[1];   // G
-                                 entry[idx+1] = pixel[0]; // R
-                                 entry[idx+2] = pixel[2]; // B
-                             },
-                             "BGR" => {
-                                 entry[idx] = pixel[2];   // B
-                                 entry[idx+1] = pixel[1]; // G
-                                 entry[idx+2] = pixel[0]; // R
-                             },
-                             _ => { // RGB
-                                 entry[idx] = pixel[0];   // R
-                                 entry[idx+1] = pixel[1]; // G
-                                 entry[idx+2] = pixel[2]; // B
-                             }
-                         }
-                     }
-                 }
-             }
+        // Pull fixture colors back out of their proxy strips
+        let fixture_proxies = state.strips.split_off(strip_count);
+        for (fixture, proxy) in state.fixtures.iter_mut().zip(fixture_proxies) {
+            fixture.color = proxy.data.first().copied().unwrap_or([0, 0, 0]);
         }
-    
-        
+
+        // 3. Send to sACN. A recording being played back replaces the render; panic white and
+        // test patterns still win.
+        let playback = if self.force_white || self.test_pattern.is_some() { None } else { self.playback_frame() };
+        let mut universe_data = playback.unwrap_or_else(|| pack_universes(state));
+        mute_universes(&mut universe_data, &state.network.muted_universes, &mut self.muted_blacked);
+        if let Some(recorder) = &mut self.recorder {
+            if let Err(e) = recorder.record(&universe_data) {
+                error!("[LIGHTS] Recording failed: {:?}", e);
+                self.recorder = None;
+                self.recording_message = Some(format!("Recording stopped: {}", e));
+            }
+        }
+
         // Debug: Log color data before sending
         static mut LAST_COLOR_LOG: f32 = 0.0;
 
-        for (u, data) in universe_data {
-            if !self.registered_universes.contains(&u) {
-                match self.sender.register_universe(u) {
-                    Ok(_) => {
-                        self.registered_universes.insert(u);
-                        info!("[LIGHTS] Registered sACN Universe {}", u);
-                    },
-                    Err(e) => {
-                        error!("[LIGHTS] Failed to register sACN Universe {}: {:?}", u, e);
+        // Universes with no output any more (re-patched or renumbered) are terminated so they
+        // stop counting toward the limit. Muted ones stay registered to resume on unmute.
+        for u in stale_universes(&self.registered_universes, &universe_data, &state.network.muted_universes) {
+            if let Err(e) = self.sender.terminate_stream(u, 0) {
+                warn!("[LIGHTS] Failed to terminate sACN Universe {}: {:?}", u, e);
+            }
+            self.registered_universes.remove(&u);
+            info!("[LIGHTS] Released sACN Universe {}", u);
+        }
+        if let Some(redundant) = self.redundant_sender.as_mut() {
+            for u in stale_universes(&self.redundant_registered, &universe_data, &state.network.muted_universes) {
+                if let Err(e) = redundant.terminate_stream(u, 0) {
+                    warn!("[LIGHTS] Failed to terminate Universe {} on redundant sender: {:?}", u, e);
+                }
+                self.redundant_registered.remove(&u);
+            }
+        }
+
+        // Register new universes in one batch, up to the configured limit
+        let mut pending: Vec<u16> = universe_data.keys()
+            .filter(|u| !self.registered_universes.contains(u))
+            .copied()
+            .collect();
+        pending.sort_unstable();
+        let room = (state.network.max_universes as usize).saturating_sub(self.registered_universes.len());
+        let over_limit: std::collections::BTreeSet<u16> = pending.split_off(room.min(pending.len())).into_iter().collect();
+        if !pending.is_empty() {
+            match self.sender.register_universes(&pending) {
+                Ok(_) => {
+                    info!("[LIGHTS] Registered sACN Universes {:?}", pending);
+                    self.registered_universes.extend(pending);
+                },
+                Err(e) => {
+                    // Fall back to one at a time so a single bad universe doesn't block the rest
+                    warn!("[LIGHTS] Batch universe registration failed ({:?}), retrying individually", e);
+                    for u in pending {
+                        match self.sender.register_universe(u) {
+                            Ok(_) => {
+                                self.registered_universes.insert(u);
+                                info!("[LIGHTS] Registered sACN Universe {}", u);
+                            },
+                            Err(e) => {
+                                error!("[LIGHTS] Failed to register sACN Universe {}: {:?}", u, e);
+                            }
+                        }
                     }
                 }
             }
+        }
+        if over_limit != self.over_limit_universes {
+            if !over_limit.is_empty() {
+                warn!("[LIGHTS] Universe limit {} reached, not sending universes {:?}", state.network.max_universes, over_limit);
+            }
+            self.over_limit_universes = over_limit;
+        }
+
+        for (u, data) in universe_data {
+            if !self.registered_universes.contains(&u) {
+                continue; // Over the limit or failed to register (already logged)
+            }
 
             let priority = 100; // Default priority
-            let dst_ip: Option<std::net::SocketAddr> = if state.network.use_multicast {
+            let (use_multicast, unicast_ip) = state.network.route_for(u);
+            let dst_ip: Option<std::net::SocketAddr> = if use_multicast {
                 None
             } else {
-                if let Ok(ip) = state.network.unicast_ip.parse::<std::net::IpAddr>() {
+                if let Ok(ip) = unicast_ip.parse::<std::net::IpAddr>() {
                     Some(std::net::SocketAddr::new(ip, 5568))
                 } else {
                     None // Fallback
@@ -479,47 +895,138 @@ impl LightingEngine {
             // User code implies we should try to send.
             // If !multicast and invalid IP -> dst_ip is None -> Sends Multicast?
             // Let's explicitly check:
-            if !state.network.use_multicast && dst_ip.is_none() {
+            if !use_multicast && dst_ip.is_none() {
                 // Invalid Unicast IP, skip or log
                 continue;
             }
             // let _ = self.sender.send(&[u], &data, Some(priority), dst_ip, None);
-            let mut fixed_data = vec![0u8]; // Start Code
-            fixed_data.extend_from_slice(&data);
+            let fixed_data = frame_for_protocol("sacn", &data);
 
             match self.sender.send(&[u], &fixed_data, Some(200), dst_ip, None) {
                 Ok(_) => {
                     // Success - use trace level to avoid flooding logs
+                    self.stats_packets += 1;
+                    self.stats_bytes += E131_HEADER_BYTES + fixed_data.len();
                 }
                 Err(e) => {
+                    self.stats.send_errors += 1;
+                    self.last_send_error = Some(Instant::now());
                     warn!("[LIGHTS] sACN send error on Universe {} (Dest: {:?}): {:?}", u, dst_ip, e);
                 }
             }
+
+            // Same frame down the backup path; its failures never affect the primary
+            if let Some(redundant) = self.redundant_sender.as_mut() {
+                if !self.redundant_registered.contains(&u) {
+                    match redundant.register_universe(u) {
+                        Ok(_) => {
+                            self.redundant_registered.insert(u);
+                        }
+                        Err(e) => {
+                            self.stats.redundant_send_errors += 1;
+                            error!("[LIGHTS] Failed to register Universe {} on redundant sender: {:?}", u, e);
+                            continue;
+                        }
+                    }
+                }
+                if let Err(e) = redundant.send(&[u], &fixed_data, Some(200), dst_ip, None) {
+                    self.stats.redundant_send_errors += 1;
+                    warn!("[LIGHTS] Redundant sACN send error on Universe {}: {:?}", u, e);
+                }
+            }
+        }
+
+        // Roll the stats window once a second
+        self.stats_frames += 1;
+        let elapsed = self.stats_window_start.elapsed().as_secs_f32();
+        if elapsed >= 1.0 {
+            self.stats.fps = self.stats_frames as f32 / elapsed;
+            self.stats.packets_per_sec = self.stats_packets as f32 / elapsed;
+            self.stats.bytes_per_sec = self.stats_bytes as f32 / elapsed;
+            self.stats_window_start = Instant::now();
+            self.stats_frames = 0;
+            self.stats_packets = 0;
+            self.stats_bytes = 0;
+        }
+        self.stats.universes = self.registered_universes.len();
+        self.stats.redundant_active = self.redundant_sender.is_some();
+    }
+
+    /// `audio` is the scene's audio-reactive flag: when false the mask renders as if there were
+    /// no audio input
+    fn apply_mask_to_strips(&mut self, mask: &Mask, strips: &mut [PixelStrip], t: f32, beat: f64, audio: bool) {
+        // Temporary masks fade out over their life: blend from what was underneath
+        match mask.life_remaining(t as f64, beat).filter(|life| *life < 1.0) {
+            Some(life) => {
+                let before: Vec<Vec<[u8; 3]>> = strips.iter().map(|s| s.data.clone()).collect();
+                self.apply_targeted_mask(mask, strips, t, beat, audio);
+                fade_from(strips, &before, life);
+            }
+            None => self.apply_targeted_mask(mask, strips, t, beat, audio),
+        }
+    }
+
+    fn apply_targeted_mask(&mut self, mask: &Mask, strips: &mut [PixelStrip], t: f32, beat: f64, audio: bool) {
+        let modulated = self.audio_listener.as_ref()
+            .filter(|_| audio)
+            .and_then(|listener| audio_modulated_mask(mask, |band| listener.level(band)));
+        let mask = modulated.as_ref().unwrap_or(mask);
+        if mask.target_strips.is_empty() {
+            self.render_mask(mask, strips, t, beat, audio);
+            return;
+        }
+        // Render across the whole rig (anchors and positions stay valid), then put back
+        // whatever was under the mask on strips it doesn't target
+        let untouched: Vec<(usize, Vec<[u8; 3]>)> = strips.iter().enumerate()
+            .filter(|(_, s)| !mask.target_strips.contains(&s.id))
+            .map(|(i, s)| (i, s.data.clone()))
+            .collect();
+        self.render_mask(mask, strips, t, beat, audio);
+        for (i, data) in untouched {
+            strips[i].data = data;
         }
     }
 
-    fn apply_mask_to_strips(&mut self, mask: &Mask, strips: &mut [PixelStrip], t: f32, beat: f64) {
-        let mx = mask.x;
-        let my = mask.y;
+    fn render_mask(&mut self, mask: &Mask, strips: &mut [PixelStrip], t: f32, beat: f64, audio: bool) {
+        let (mx, my) = mask.resolved_position(strips);
+        if !mx.is_finite() || !my.is_finite() {
+            return; // Corrupt position, nothing sensible to draw
+        }
         
+        let compositing = self.mask_compositing.clone();
         let mode = mask.params.get("color_mode").and_then(|v| v.as_str()).unwrap_or("static");
+        // Editor "freeze phase": one point of the cycle, in cycles, instead of the clock
+        let preview_phase = self.mask_phase_preview.filter(|(id, _)| *id == mask.id).map(|(_, phase)| phase);
         let speed = mask.params.get("speed").and_then(|v| v.as_f64()).unwrap_or(1.0) as f32;
 
-        // Helper to get color based on mode
-        let get_color = |base_color: [u8; 3]| -> [u8; 3] {
-            if mode == "rainbow" {
-                let hue = (t * speed * 0.5) % 1.0; // 0.0 to 1.0
+        let gradient_colors: Vec<[u8; 3]> = if mode == "gradient" {
+            mask.params.get("gradient_colors").and_then(|v| {
+                serde_json::from_value(v.clone()).ok()
+            }).unwrap_or_else(|| {
+                // Fallback
+                let c1 = mask.params.get("color").and_then(|v| serde_json::from_value(v.clone()).ok()).unwrap_or([0, 255, 255]);
+                let c2 = mask.params.get("color2").and_then(|v| serde_json::from_value(v.clone()).ok()).unwrap_or([255, 0, 255]);
+                vec![c1, c2]
+            })
+        } else {
+            Vec::new()
+        };
+
+        // Audio color is the same for every pixel; without audio input the base color stays
+        let audio_rgb = match self.audio_listener.as_ref().filter(|_| audio) {
+            Some(listener) if mode == "audio" => Some(audio_color(mask, |source| listener.level(source))),
+            _ => None,
+        };
+
+        // Helper to get color based on mode. `phase_shift` (in cycles) offsets the color cycle, for per-pixel jitter
+        let get_color = |base_color: [u8; 3], phase_shift: f64| -> [u8; 3] {
+            if let Some(rgb) = audio_rgb {
+                rgb
+            } else if mode == "rainbow" {
+                let hue = (t * speed * 0.5 + phase_shift as f32).rem_euclid(1.0); // 0.0 to 1.0
                 hsv_to_rgb(hue, 1.0, 1.0)
             } else if mode == "gradient" {
-                let colors: Vec<[u8; 3]> = mask.params.get("gradient_colors").and_then(|v| {
-                    serde_json::from_value(v.clone()).ok()
-                }).unwrap_or_else(|| {
-                    // Fallback
-                    let c1 = mask.params.get("color").and_then(|v| serde_json::from_value(v.clone()).ok()).unwrap_or([0, 255, 255]);
-                    let c2 = mask.params.get("color2").and_then(|v| serde_json::from_value(v.clone()).ok()).unwrap_or([255, 0, 255]);
-                    vec![c1, c2]
-                });
-                
+                let colors = &gradient_colors;
                 if colors.is_empty() { return base_color; }
                 if colors.len() == 1 { return colors[0]; }
 
@@ -538,8 +1045,13 @@ impl LightingEngine {
                 // For now, let's duplicate the Sync check phase logic here for color cycle.
                 
                 let is_sync = mask.params.get("sync").and_then(|v| v.as_bool()).unwrap_or(false);
-                let progress = if is_sync {
-                     let rate_str = mask.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1/4");
+                let progress = if let Some(phase) = preview_phase {
+                     phase
+                } else if is_sync {
+                     // Color cycle has its own rate; masks saved before it existed follow the motion rate
+                     let rate_str = mask.params.get("color_rate")
+                         .or_else(|| mask.params.get("rate"))
+                         .and_then(|v| v.as_str()).unwrap_or("1/4");
                      let divisor = match rate_str {
                          "4 Bar" => 16.0, "2 Bar" => 8.0, "1 Bar" => 4.0, "1/2" => 2.0, "1/4" => 1.0, "1/8" => 0.5, _ => 1.0,
                      };
@@ -550,6 +1062,7 @@ impl LightingEngine {
                      // If speed=1, cycle 1hz.
                      (t * speed).fract() as f64
                 };
+                let progress = (progress + phase_shift).rem_euclid(1.0);
 
                 // Cycle logic: c1->c2->c3->c1
                 let n = colors.len();
@@ -559,12 +1072,9 @@ impl LightingEngine {
                 
                 let c_start = colors[idx % n];
                 let c_end = colors[(idx + 1) % n];
-                
-                [
-                    (c_start[0] as f32 * (1.0 - sub_t) + c_end[0] as f32 * sub_t) as u8,
-                    (c_start[1] as f32 * (1.0 - sub_t) + c_end[1] as f32 * sub_t) as u8,
-                    (c_start[2] as f32 * (1.0 - sub_t) + c_end[2] as f32 * sub_t) as u8,
-                ]
+                let space = mask.params.get("gradient_space").and_then(|v| v.as_str()).unwrap_or("rgb");
+
+                interpolate_color(c_start, c_end, sub_t, space)
             } else {
                 base_color
             }
@@ -575,8 +1085,8 @@ impl LightingEngine {
             // Get mask dimensions in local (unrotated) space
             let base_width = mask.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.3) as f32;
             let base_height = mask.params.get("height").and_then(|v| v.as_f64()).unwrap_or(0.3) as f32;
-            let width = apply_lfo_modulation(base_width, &mask.params, "width", t, beat);
-            let height = apply_lfo_modulation(base_height, &mask.params, "height", t, beat);
+            let width = apply_lfo_modulation(base_width, &mask.params, "width", 0.01..=2.0, t, beat);
+            let height = apply_lfo_modulation(base_height, &mask.params, "height", 0.01..=2.0, t, beat);
             // Debug: when true, fill all pixels inside mask with white
             let debug_fill = mask.params.get("debug_fill").and_then(|v| v.as_bool()).unwrap_or(false);
 
@@ -590,12 +1100,15 @@ impl LightingEngine {
 
             // Get bar parameters
             let base_bar_width = mask.params.get("bar_width").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
-            let bar_width = apply_lfo_modulation(base_bar_width, &mask.params, "bar_width", t, beat);
+            // Lower bound keeps the falloff division finite at full LFO depth
+            let bar_width = apply_lfo_modulation(base_bar_width, &mask.params, "bar_width", 0.001..=4.0, t, beat);
             let hard_edge = mask.params.get("hard_edge").and_then(|v| v.as_bool()).unwrap_or(false);
 
             // Calculate bar position (scanning animation)
             let is_sync = mask.params.get("sync").and_then(|v| v.as_bool()).unwrap_or(false);
-            let phase = if is_sync {
+            let phase = if let Some(phase) = preview_phase {
+                phase * std::f64::consts::TAU
+            } else if is_sync {
                 let rate_str = mask.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1/4");
                 let divisor = match rate_str {
                     "4 Bar" => 16.0, "2 Bar" => 8.0, "1 Bar" => 4.0,
@@ -614,17 +1127,15 @@ impl LightingEngine {
             let unidirectional = mask.params.get("unidirectional").and_then(|v| v.as_bool()).unwrap_or(false);
             let motion = mask.params.get("motion").and_then(|v| v.as_str()).unwrap_or("Smooth");
             
-            let osc_val = if unidirectional {
+            let osc = |phase: f64| if unidirectional {
                  // Sawtooth wave: -1.0 to 1.0
                  let norm_phase = (phase / (std::f64::consts::PI * 2.0)).fract();
                  // fract() returns [0, 1) (if positive). phase is usually positive (t*speed or beat).
                  // We want strictly 0..1 then map to -1..1
                  let p = if norm_phase < 0.0 { norm_phase + 1.0 } else { norm_phase };
                  p * 2.0 - 1.0
-            } else if motion == "Linear" {
-                (2.0 / std::f64::consts::PI) * (phase.sin().asin())
             } else {
-                phase.sin()
+                motion_curve(phase, motion)
             };
 
             // Bar position in local space
@@ -633,7 +1144,11 @@ impl LightingEngine {
             // on osc_val hitting perfect ±1.0. This prevents a dark sliver at
             // the mask boundaries, especially noticeable when rotated.
             let sweep_range = (width / 2.0) - bar_width;
-            let bar_local_x = sweep_range * osc_val as f32;
+            let mask_bar_x = sweep_range * osc(phase) as f32;
+
+            // Optional per-pixel phase jitter: a fixed, seeded offset per pixel so the sweep shimmers
+            let jitter = mask.params.get("jitter").and_then(|v| v.as_f64()).unwrap_or(0.0);
+            let jitter_seed = mask.params.get("jitter_seed").and_then(|v| v.as_f64()).unwrap_or(0.0) as u64;
 
             // Debug bar position - DETAILED
             static mut LAST_LOG_TIME: f32 = 0.0;
@@ -653,7 +1168,8 @@ impl LightingEngine {
                 let arr = v.as_array()?;
                 Some([arr.get(0)?.as_u64()? as u8, arr.get(1)?.as_u64()? as u8, arr.get(2)?.as_u64()? as u8])
             }).unwrap_or([0, 255, 255]);
-            let final_color = get_color(m_color);
+            let mask_color = get_color(m_color, 0.0);
+            let edge_softness = mask.params.get("edge_softness").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
 
             // Process each strip
             for i in 0..strips.len() {
@@ -661,14 +1177,15 @@ impl LightingEngine {
                 let pixel_limit = strip.pixel_count.min(strip.data.len());
 
                 for p in 0..pixel_limit {
-                    // 1. Calculate pixel position in world space
-                    let local_pos_x = if strip.flipped {
-                        ((strip.pixel_count - 1).saturating_sub(p)) as f32 * strip.spacing
+                    let (bar_local_x, final_color) = if jitter > 0.0 {
+                        let shift = jitter * pixel_jitter(jitter_seed, strip.id, p) as f64;
+                        (sweep_range * osc(phase + shift * std::f64::consts::TAU) as f32, get_color(m_color, shift))
                     } else {
-                        p as f32 * strip.spacing
+                        (mask_bar_x, mask_color)
                     };
-                    let px = strip.x + local_pos_x;
-                    let py = strip.y;
+
+                    // 1. Calculate pixel position in world space
+                    let (px, py) = strip.pixel_position(p);
 
                     // 2. Transform to mask's local coordinate system
                     let dx = px - mx;
@@ -715,18 +1232,16 @@ impl LightingEngine {
                             } else {
                                 (1.0 - dist_to_bar / bar_width).max(0.0)
                             };
+                            // Fade out near the mask outline to avoid stair-stepping
+                            let dist_inside = (half_w - mask_local_x.abs()).min(half_h - mask_local_y.abs());
+                            let intensity = intensity * edge_falloff(dist_inside, edge_softness);
 
                             if intensity > 0.0 {
                                 let r = (final_color[0] as f32 * intensity) as u8;
                                 let g = (final_color[1] as f32 * intensity) as u8;
                                 let b = (final_color[2] as f32 * intensity) as u8;
 
-                                let curr = strip.data[p];
-                                strip.data[p] = [
-                                    curr[0].saturating_add(r),
-                                    curr[1].saturating_add(g),
-                                    curr[2].saturating_add(b)
-                                ];
+                                strip.data[p] = blend_mask_pixel(strip.data[p], [r, g, b], &compositing);
                             }
                         }
                     }
@@ -743,7 +1258,9 @@ impl LightingEngine {
 
             // Calculate raw phase (0 to 1 for one full orbit)
             let is_sync = mask.params.get("sync").and_then(|v| v.as_bool()).unwrap_or(false);
-            let raw_phase = if is_sync {
+            let raw_phase = if let Some(phase) = preview_phase {
+                phase
+            } else if is_sync {
                 let rate_str = mask.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1/4");
                 let divisor = match rate_str {
                     "4 Bar" => 16.0, "2 Bar" => 8.0, "1 Bar" => 4.0,
@@ -822,7 +1339,7 @@ impl LightingEngine {
                     let arr = v.as_array()?;
                     Some([arr.get(0)?.as_u64()? as u8, arr.get(1)?.as_u64()? as u8, arr.get(2)?.as_u64()? as u8])
                 }).unwrap_or([0, 255, 255]);
-                let final_color = get_color(m_color);
+                let final_color = get_color(m_color, 0.0);
 
                 // Process each strip
                 for strip in strips.iter_mut() {
@@ -830,13 +1347,7 @@ impl LightingEngine {
 
                     for p in 0..pixel_limit {
                         // Calculate pixel position in world space
-                        let local_pos_x = if strip.flipped {
-                            ((strip.pixel_count - 1).saturating_sub(p)) as f32 * strip.spacing
-                        } else {
-                            p as f32 * strip.spacing
-                        };
-                        let px = strip.x + local_pos_x;
-                        let py = strip.y;
+                        let (px, py) = strip.pixel_position(p);
 
                         // Transform to mask's local coordinate system (no rotation for orbit)
                         let mask_local_x = px - mx;
@@ -868,12 +1379,7 @@ impl LightingEngine {
                                     let g = (final_color[1] as f32 * intensity) as u8;
                                     let b = (final_color[2] as f32 * intensity) as u8;
 
-                                    let curr = strip.data[p];
-                                    strip.data[p] = [
-                                        curr[0].saturating_add(r),
-                                        curr[1].saturating_add(g),
-                                        curr[2].saturating_add(b)
-                                    ];
+                                    strip.data[p] = blend_mask_pixel(strip.data[p], [r, g, b], &compositing);
                                 }
                             }
                         }
@@ -882,7 +1388,7 @@ impl LightingEngine {
             }
         } else if mask.mask_type == "radial" {
              let base_radius = mask.params.get("radius").and_then(|v| v.as_f64()).unwrap_or(0.2) as f32;
-             let radius = apply_lfo_modulation(base_radius, &mask.params, "radius", t, beat);
+             let radius = apply_lfo_modulation(base_radius, &mask.params, "radius", 0.0..=5.0, t, beat);
              let debug_fill = mask.params.get("debug_fill").and_then(|v| v.as_bool()).unwrap_or(false);
              let m_color = mask.params.get("color").and_then(|v| {
                 let arr = v.as_array()?;
@@ -893,22 +1399,13 @@ impl LightingEngine {
                 ])
             }).unwrap_or([255, 0, 0]);
             
-            let final_color = get_color(m_color);
+            let final_color = get_color(m_color, 0.0);
+            let edge_softness = mask.params.get("edge_softness").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
 
              for strip in strips.iter_mut() {
-                // ALIGNMENT FIX: Start at 0
-                let start_idx_x = 0.0;
-
                 let pixel_limit = strip.pixel_count.min(strip.data.len());
                 for i in 0..pixel_limit {
-                    let local_x = start_idx_x + (i as f32 * strip.spacing);
-                    let local_y = 0.0;
-                    
-                    let (px, py) = if strip.flipped {
-                         (strip.x - local_x, strip.y)
-                    } else {
-                         (strip.x + local_x, strip.y)
-                    };
+                    let (px, py) = strip.pixel_position(i);
 
                     let dist = ((px - mx).powi(2) + (py - my).powi(2)).sqrt();
                     if dist < radius {
@@ -917,14 +1414,14 @@ impl LightingEngine {
                              continue;
                          }
                          let intensity = 1.0 - (dist / radius);
-                         let intensity = intensity.clamp(0.0, 1.0);
+                         let intensity = intensity.clamp(0.0, 1.0) * edge_falloff(radius - dist, edge_softness);
 
-                         let [r, g, b] = strip.data[i];
-                         strip.data[i] = [
-                              r.saturating_add((final_color[0] as f32 * intensity) as u8),
-                              g.saturating_add((final_color[1] as f32 * intensity) as u8),
-                              b.saturating_add((final_color[2] as f32 * intensity) as u8),
+                         let color = [
+                              (final_color[0] as f32 * intensity) as u8,
+                              (final_color[1] as f32 * intensity) as u8,
+                              (final_color[2] as f32 * intensity) as u8,
                          ];
+                         strip.data[i] = blend_mask_pixel(strip.data[i], color, &compositing);
                     }
                  }
               }
@@ -940,9 +1437,13 @@ impl LightingEngine {
                 Some([arr.get(0)?.as_u64()? as u8, arr.get(1)?.as_u64()? as u8, arr.get(2)?.as_u64()? as u8])
             }).unwrap_or([255, 100, 0]);
 
-            // Get audio volume
-            let audio_vol = if let Some(audio) = &self.audio_listener {
-                audio.current_volume.lock().map(|v| *v).unwrap_or(0.0)
+            // Get audio volume: the smooth level, or the punchy transient envelope
+            let envelope = match mask.params.get("envelope").and_then(|v| v.as_str()) {
+                Some("transient") => "transient",
+                _ => "volume",
+            };
+            let audio_vol = if let Some(listener) = self.audio_listener.as_ref().filter(|_| audio) {
+                listener.level(envelope)
             } else {
                 0.0
             };
@@ -951,24 +1452,21 @@ impl LightingEngine {
             let expansion = (audio_vol * sensitivity).min(1.0);
             let target_radius = base_radius + (max_radius - base_radius) * expansion;
 
-            // Smooth to target
+            // Smooth to target. Without an audio input (or in a scene that isn't audio reactive),
+            // hold at the base radius so the mask still renders
+            let listening = audio && self.audio_listener.is_some();
             let current_radius = self.burst_radius_states.entry(mask.id).or_insert(base_radius);
-            *current_radius = *current_radius + (target_radius - *current_radius) * decay;
-
-            let mx = mask.x;
-            let my = mask.y;
+            if listening {
+                *current_radius = *current_radius + (target_radius - *current_radius) * decay;
+            } else {
+                *current_radius = base_radius;
+            }
 
             // Render like radial mask
             for strip in strips.iter_mut() {
                 let pixel_count = strip.pixel_count.min(strip.data.len());
                 for i in 0..pixel_count {
-                    let local_x = if strip.flipped {
-                         ((strip.pixel_count - 1).saturating_sub(i)) as f32 * strip.spacing
-                    } else {
-                         i as f32 * strip.spacing
-                    };
-                    let px = strip.x + local_x;
-                    let py = strip.y;
+                    let (px, py) = strip.pixel_position(i);
 
                     let dist = ((px - mx).powi(2) + (py - my).powi(2)).sqrt();
                     if dist < *current_radius {
@@ -978,79 +1476,377 @@ impl LightingEngine {
                         let g = (color[1] as f32 * intensity) as u8;
                         let b = (color[2] as f32 * intensity) as u8;
 
-                        strip.data[i] = [
-                            strip.data[i][0].saturating_add(r),
-                            strip.data[i][1].saturating_add(g),
-                            strip.data[i][2].saturating_add(b),
-                        ];
+                        strip.data[i] = blend_mask_pixel(strip.data[i], [r, g, b], &compositing);
                     }
                 }
             }
+        } else if mask.mask_type == "image" {
+            // Image Mask: a picture stretched over a rotated rectangle, sampled at each pixel's
+            // world position and tinted by the mask color
+            let Some(image) = mask.params.get("image") else {
+                return; // Nothing loaded yet
+            };
+            let width = mask.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.5) as f32;
+            let height = mask.params.get("height").and_then(|v| v.as_f64()).unwrap_or(0.5) as f32;
+            let rotation_deg = mask.params.get("rotation").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+            let m_color = mask.params.get("color").and_then(|v| serde_json::from_value(v.clone()).ok()).unwrap_or([255, 255, 255]);
+            let tint = get_color(m_color, 0.0);
+
+            for strip in strips.iter_mut() {
+                let pixel_limit = strip.pixel_count.min(strip.data.len());
+                for i in 0..pixel_limit {
+                    let (px, py) = strip.pixel_position(i);
+                    let Some((u, v)) = image_mask_uv(px - mx, py - my, width, height, rotation_deg) else {
+                        continue;
+                    };
+                    let Some(rgb) = sample_mask_image(image, u, v) else {
+                        continue;
+                    };
+                    let color = [0, 1, 2].map(|c| (rgb[c] as u16 * tint[c] as u16 / 255) as u8);
+                    strip.data[i] = blend_mask_pixel(strip.data[i], color, &compositing);
+                }
+            }
+        }
+    }
+
+    /// Slow a flashing effect's beat divisor until it flashes no faster than max_flash_hz
+    fn limit_flash_divisor(&mut self, divisor: f64) -> f64 {
+        let limited = limit_flash_divisor(divisor, self.tempo, self.max_flash_hz);
+        if limited != divisor {
+            self.flash_limited = true;
+        }
+        limited
+    }
+
+    /// Raise a flash intensity (0..1) so its swing from full brightness is at most max_flash_contrast
+    fn limit_flash_contrast(&mut self, intensity: f32) -> f32 {
+        let floor = 1.0 - self.max_flash_contrast.clamp(0.0, 1.0);
+        if intensity < floor {
+            self.flash_limited = true;
+        }
+        intensity.max(floor)
+    }
+
+    /// True if an effect asked for a faster or harsher flash than the safety limits allowed this frame
+    pub fn flash_limited(&self) -> bool {
+        self.flash_limited
+    }
+
+    /// Which primary the color order check is sending right now, so it can be compared with the rig
+    pub fn color_order_step(&self) -> Option<&'static str> {
+        match self.test_pattern.as_deref() {
+            Some("color_order") => Some(["Red", "Green", "Blue"][color_order_index(self.clock.time as f32)]),
+            _ => None,
         }
     }
 
+    /// World-space heads of the Comet effects rendered this frame
+    pub fn comet_preview(&self) -> &[(f32, f32)] {
+        &self.comet_heads
+    }
+
     pub fn get_bpm(&self) -> f64 {
         let mut session_state = SessionState::new();
         self.link.capture_app_session_state(&mut session_state);
         session_state.tempo()
     }
 
+    /// Blend each strip toward its newly rendered frame. `smoothing` is the fraction of the
+    /// previous frame kept per 60 Hz frame, scaled by `dt` so trails don't depend on frame rate.
+    fn smooth_strips(&mut self, strips: &mut [PixelStrip], smoothing: f32, dt: f32) {
+        if smoothing <= 0.0 {
+            self.smoothed_frames.clear();
+            return;
+        }
+        let retain = smoothing.min(0.99).powf(dt * 60.0);
+        for strip in strips.iter_mut() {
+            let prev = self.smoothed_frames.entry(strip.id).or_default();
+            smooth_pixels(prev, &mut strip.data, retain);
+        }
+        self.smoothed_frames.retain(|id, _| strips.iter().any(|s| s.id == *id));
+    }
+
+    /// Current animation beat, including audio phase correction and the user's beat offset.
+    /// With the flywheel off and Link leading this reads Link live, so it isn't quantized to
+    /// `update` calls; otherwise it's the flywheel beat from the last `update`.
     pub fn get_beat(&self) -> f64 {
-        // Include phase offset for audio sync
-        self.flywheel_beat + self.phase_offset
+        let link_beat = if !self.use_flywheel && self.link_leads {
+            let mut session_state = SessionState::new();
+            self.link.capture_app_session_state(&mut session_state);
+            session_state.beat_at_time(self.adjusted_link_micros(), 1.0)
+        } else {
+            self.link_beat
+        };
+        base_beat(self.use_flywheel, self.link_leads, self.flywheel_beat, link_beat) + self.phase_offset + self.beat_offset
     }
-    
-    pub fn get_time(&self) -> f32 {
-        self.start_time.elapsed().as_secs_f32()
+
+    /// Link clock now, pulled back by the audio latency. Visuals ahead of the audio
+    /// (showing beat 2.0 while you hear 1.9) should show the earlier beat.
+    fn adjusted_link_micros(&self) -> i64 {
+        (self.link.clock_micros() - (self.latency_ms * 1000.0) as i64).max(0)
+    }
+
+    /// Position within the current beat, 0..1
+    pub fn get_phase(&self) -> f64 {
+        self.get_beat().rem_euclid(1.0)
+    }
+
+    /// Position within the current bar (`beats_per_bar` beats), 0..1
+    pub fn get_bar_phase(&self) -> f64 {
+        let beats = self.sync_tuning.beats_per_bar as f64;
+        self.get_beat().rem_euclid(beats) / beats
+    }
+
+    /// Beat within the current bar, 1..=beats_per_bar
+    pub fn get_beat_in_bar(&self) -> u8 {
+        beat_in_bar(self.get_beat(), self.sync_tuning.beats_per_bar)
+    }
+
+    pub fn beats_per_bar(&self) -> u8 {
+        self.sync_tuning.beats_per_bar
     }
     
-    pub fn get_sync_info(&self) -> (String, f64) {
-        let peers = self.link.num_peers();
-        if peers > 0 {
-             let mut session_state = SessionState::new();
-             self.link.capture_app_session_state(&mut session_state);
-             (format!("LINK ({} Peers)", peers), session_state.tempo())
-        } else if self.audio_bpm > 30.0 {
-             ("AUDIO".to_string(), self.audio_bpm)
-        } else {
-             ("MANUAL".to_string(), 120.0 * self.speed as f64)
-        }
+    /// Fire every one-shot (trigger mode) effect once, starting now
+    pub fn fire(&mut self) {
+        self.fire_beat = Some(self.get_beat());
     }
-}
 
-impl LightingEngine {
-    fn apply_global_effect(&mut self, effect: &GlobalEffect, strips: &mut [PixelStrip], t: f32, beat: f64, targets: Option<&Vec<u64>>) {
-        match effect.kind.as_str() {
-            "Solid" => {
-                // Use EXACT same color reading as masks
-                let color = effect.params.get("color").and_then(|v| {
-                    let arr = v.as_array()?;
-                    Some([arr.get(0)?.as_u64()? as u8, arr.get(1)?.as_u64()? as u8, arr.get(2)?.as_u64()? as u8])
-                }).unwrap_or([255, 255, 255]);
+    /// True while tempo sync is considered lost (see SYNC_LOSS_TIMEOUT_SECS)
+    pub fn sync_lost(&self) -> bool {
+        self.sync_lost
+    }
 
-                // Apply color EXACTLY like scanner masks do - with intensity and saturating_add
-                for s in strips.iter_mut() {
-                    if let Some(t) = targets { if !t.contains(&s.id) { continue; } }
-                    
-                    let cnt = s.pixel_count.min(s.data.len());
-                    for i in 0..cnt {
-                        let intensity = 1.0; // Full intensity for solid colors
-                        let r = (color[0] as f32 * intensity) as u8;
-                        let g = (color[1] as f32 * intensity) as u8;
-                        let b = (color[2] as f32 * intensity) as u8;
+    /// Output health for the hardware heartbeat: "ok" | "error" | "idle"
+    pub fn heartbeat_status(&self) -> &'static str {
+        let recent_error = self.last_send_error.is_some_and(|t| t.elapsed().as_secs_f32() < HEARTBEAT_ERROR_HOLD_SECS);
+        heartbeat_status(self.sync_lost, recent_error, self.stats.packets_per_sec > 0.0)
+    }
 
-                        let curr = s.data[i];
-                        s.data[i] = [
-                            curr[0].saturating_add(r),
-                            curr[1].saturating_add(g),
-                            curr[2].saturating_add(b)
-                        ];
+    pub fn output_stats(&self) -> &OutputStats {
+        &self.stats
+    }
+
+    pub fn sync_diagnostics(&self) -> SyncDiagnostics {
+        SyncDiagnostics {
+            link_beat: self.link_beat,
+            flywheel_beat: self.flywheel_beat,
+            link_peers: self.link_peers,
+            in_sync: self.sync_mode,
+            sync_error_secs: self.sync_error_timer,
+            phase_offset: self.phase_offset,
+            last_phase_error: self.last_phase_error,
+        }
+    }
+
+    /// Recover from a wedged output without restarting: rebuild both sACN senders from the
+    /// last network config, forget registered universes (they re-register next frame) and
+    /// resync the beat clock to Link. Scenes, strips and the animation clock are untouched.
+    pub fn reset_output(&mut self) {
+        warn!("[LIGHTS] Output reset requested, rebuilding sACN senders");
+        self.sender = Self::create_sender(&self.last_network);
+        self.redundant_sender = Self::create_redundant_sender(&self.last_network);
+        self.registered_universes.clear();
+        self.redundant_registered.clear();
+        self.over_limit_universes.clear();
+        self.muted_blacked.clear();
+        self.last_send_error = None;
+        self.stats = OutputStats::default();
+
+        // Sync state: snap the flywheel back onto Link and drop any audio correction
+        self.flywheel_beat = self.link_beat;
+        self.phase_offset = 0.0;
+        self.last_phase_error = 0.0;
+        self.sync_error_timer = 0.0;
+        self.sync_mode = true;
+        self.unsynced_since = None;
+        self.sync_lost = false;
+        self.held_clock = None;
+        self.sync_loss_fade = 1.0;
+        self.smoothed_frames.clear();
+    }
+
+    /// Number of sACN universes currently registered on the sender
+    pub fn registered_universe_count(&self) -> usize {
+        self.registered_universes.len()
+    }
+
+    /// Registered sACN universes, ascending
+    pub fn registered_universes(&self) -> Vec<u16> {
+        let mut universes: Vec<u16> = self.registered_universes.iter().copied().collect();
+        universes.sort_unstable();
+        universes
+    }
+
+    /// Universes that have output but are not sent because of the universe limit
+    pub fn over_limit_universe_count(&self) -> usize {
+        self.over_limit_universes.len()
+    }
+
+    pub fn has_audio_input(&self) -> bool {
+        self.audio_listener.is_some()
+    }
+
+    /// Human-readable audio input status for the UI
+    pub fn audio_status(&self) -> String {
+        match &self.audio_listener {
+            Some(audio) => format!("Listening: {}", audio.device_name),
+            None => "No audio input".to_string(),
+        }
+    }
+
+    /// Rebuild the audio listener (e.g. after plugging in an interface). Returns true on success.
+    pub fn retry_audio_init(&mut self) -> bool {
+        // Drop the old stream first so the device is released before reopening
+        self.audio_listener = None;
+        self.audio_listener = AudioListener::new();
+        self.burst_radius_states.clear();
+        self.audio_listener.is_some()
+    }
+
+    /// Start writing every output frame to `path`, replacing any recording in progress
+    pub fn start_recording(&mut self, path: &std::path::Path) -> anyhow::Result<()> {
+        self.stop_recording()?;
+        self.recorder = Some(Recorder::create(path)?);
+        Ok(())
+    }
+
+    /// Finish the recording in progress; returns the number of frames written
+    pub fn stop_recording(&mut self) -> anyhow::Result<Option<u64>> {
+        self.recorder.take().map(Recorder::finish).transpose()
+    }
+
+    /// Frames written and seconds elapsed, while recording
+    pub fn recording_progress(&self) -> Option<(u64, f32)> {
+        self.recorder.as_ref().map(Recorder::progress)
+    }
+
+    /// Send a recording instead of the render, from its first frame
+    pub fn start_playback(&mut self, path: &std::path::Path, looping: bool) -> anyhow::Result<()> {
+        self.player = Some((Player::open(path, looping)?, Instant::now()));
+        Ok(())
+    }
+
+    pub fn stop_playback(&mut self) {
+        self.player = None;
+    }
+
+    /// Seconds into playback, while a recording is playing
+    pub fn playback_elapsed(&self) -> Option<f32> {
+        self.player.as_ref().map(|(_, started)| started.elapsed().as_secs_f32())
+    }
+
+    pub fn set_playback_looping(&mut self, looping: bool) {
+        if let Some((player, _)) = &mut self.player {
+            player.looping = looping;
+        }
+    }
+
+    /// Why recording or playback last stopped on its own, once
+    pub fn take_recording_message(&mut self) -> Option<String> {
+        self.recording_message.take()
+    }
+
+    /// The frame due now from the recording being played; ends playback when it finishes or fails
+    fn playback_frame(&mut self) -> Option<std::collections::HashMap<u16, Vec<u8>>> {
+        let (player, started) = self.player.as_mut()?;
+        let message = match player.frame_at(started.elapsed().as_micros() as u64) {
+            Ok(Some(frame)) => return Some(frame.clone()),
+            Ok(None) => "Playback finished".to_string(),
+            Err(e) => {
+                error!("[LIGHTS] Playback failed: {:?}", e);
+                format!("Playback stopped: {}", e)
+            }
+        };
+        self.player = None;
+        self.recording_message = Some(message);
+        None
+    }
+
+    /// Ids of temporary masks that expired since the last call
+    pub fn take_expired_masks(&mut self) -> Vec<u64> {
+        std::mem::take(&mut self.expired_masks)
+    }
+
+    /// Animation time in seconds, advanced in whole engine ticks
+    pub fn get_time(&self) -> f32 {
+        self.clock.time as f32
+    }
+    
+    pub fn get_sync_info(&self) -> (String, f64) {
+        let peers = self.link.num_peers();
+        match select_beat_source(&self.source_priority, peers, self.audio_bpm) {
+            "link" => {
+                let mut session_state = SessionState::new();
+                self.link.capture_app_session_state(&mut session_state);
+                (format!("LINK ({} Peers)", peers), session_state.tempo())
+            }
+            "audio" => ("AUDIO".to_string(), self.audio_bpm),
+            _ => ("MANUAL".to_string(), self.source_tempo("manual", 0.0)),
+        }
+    }
+
+    /// BPM of `source` ("link" | "audio" | "manual"); manual is the manual BPM times Master Speed
+    fn source_tempo(&self, source: &str, link_tempo: f64) -> f64 {
+        match source {
+            "link" => link_tempo,
+            "audio" => self.audio_bpm,
+            _ => self.sync_tuning.manual_bpm as f64 * self.speed as f64,
+        }
+    }
+
+    /// Detected audio BPM rounded for storing as the manual tempo, if there is one
+    pub fn detected_bpm(&self) -> Option<f32> {
+        (self.audio_bpm > 30.0).then(|| ((self.audio_bpm * 10.0).round() / 10.0) as f32)
+    }
+
+    /// Whether the audio trigger fired since the last call, for the UI to run its action
+    pub fn take_audio_trigger(&mut self) -> bool {
+        std::mem::take(&mut self.audio_triggered)
+    }
+
+    /// BPM auto-latched since the last call, for the UI to store as the manual tempo
+    pub fn take_latched_bpm(&mut self) -> Option<f32> {
+        self.latched_bpm.take().map(|bpm| bpm as f32)
+    }
+}
+
+impl LightingEngine {
+    /// Render one global effect. `slot` (scene id, effect index) keys state kept across frames,
+    /// so several instances of a stateful effect don't share it.
+    fn apply_global_effect(&mut self, effect: &GlobalEffect, strips: &mut [PixelStrip], t: f32, beat: f64, targets: Option<&Vec<u64>>, slot: (u64, usize)) {
+        match effect.kind.as_str() {
+            "Solid" => {
+                // Use EXACT same color reading as masks
+                let color = effect.params.get("color").and_then(|v| {
+                    let arr = v.as_array()?;
+                    Some([arr.get(0)?.as_u64()? as u8, arr.get(1)?.as_u64()? as u8, arr.get(2)?.as_u64()? as u8])
+                }).unwrap_or([255, 255, 255]);
+
+                // Full intensity unless an intensity LFO is breathing it
+                let intensity = apply_lfo_modulation(1.0, &effect.params, "intensity", 0.0..=1.0, t, beat);
+
+                // Apply color EXACTLY like scanner masks do - with intensity and saturating_add
+                for s in strips.iter_mut() {
+                    if let Some(t) = targets { if !t.contains(&s.id) { continue; } }
+                    
+                    let cnt = s.pixel_count.min(s.data.len());
+                    for i in 0..cnt {
+                        let r = (color[0] as f32 * intensity) as u8;
+                        let g = (color[1] as f32 * intensity) as u8;
+                        let b = (color[2] as f32 * intensity) as u8;
+
+                        let curr = s.data[i];
+                        s.data[i] = [
+                            curr[0].saturating_add(r),
+                            curr[1].saturating_add(g),
+                            curr[2].saturating_add(b)
+                        ];
                     }
                 }
             }
             "Rainbow" => {
                 let base_speed = effect.params.get("speed").and_then(|v| v.as_f64()).unwrap_or(0.2) as f32;
-                let speed = apply_lfo_modulation(base_speed, &effect.params, "speed", t, beat);
+                let speed = apply_lfo_modulation(base_speed, &effect.params, "speed", 0.0..=2.0, t, beat);
                 let hue = (t * speed * self.speed).fract();
                 let c = hsv_to_rgb(hue, 1.0, 1.0);
                 for s in strips.iter_mut() {
@@ -1069,20 +1865,31 @@ impl LightingEngine {
 
                 let rate_str = effect.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1 Bar");
                 let divisor = match rate_str {
-                    "4 Bar" => 16.0, "1 Bar" => 4.0, "1/2" => 2.0, "1/4" => 1.0, "1/8" => 0.5, _ => 4.0,
+                    "4 Bar" => 16.0, "2 Bar" => 8.0, "1 Bar" => 4.0, "1/2" => 2.0, "1/4" => 1.0, "1/8" => 0.5, _ => 4.0,
                 };
 
                 let decay = effect.params.get("decay").and_then(|v| v.as_f64()).unwrap_or(5.0);
+                let divisor = self.limit_flash_divisor(divisor);
 
                 // Calculate phase 0..1
-                let phase = (beat / divisor).fract();
+                let phase = if effect.params.get("trigger").and_then(|v| v.as_str()) == Some("oneshot") {
+                    // One-shot: a single cycle from the last fire(), then leave the strips alone
+                    let Some(fire_beat) = self.fire_beat else { return };
+                    let elapsed = (beat - fire_beat) / divisor;
+                    if !(0.0..1.0).contains(&elapsed) {
+                        return;
+                    }
+                    elapsed
+                } else {
+                    (beat / divisor).fract()
+                };
 
                 // Exponential decay: starts at 1.0, drops quickly
                 // To make it flash *on the beat*, we want peak at phase=0.
                 let intensity = (1.0 - phase).powf(decay) as f32;
 
-                // Clamp to ensure valid range
-                let intensity = intensity.clamp(0.0, 1.0);
+                // Clamp to ensure valid range, then keep the swing within the contrast limit
+                let intensity = self.limit_flash_contrast(intensity.clamp(0.0, 1.0));
 
                 // Always apply the color with intensity - don't black out
                 // This prevents the "crash to black" issue
@@ -1109,8 +1916,33 @@ impl LightingEngine {
 
                 const MAX_SPARKLES: usize = 500;
 
+                // Structured patterns: deterministic pixel sets on the same life/decay envelope
+                let pattern = effect.params.get("pattern").and_then(|v| v.as_str()).unwrap_or("random");
+                if pattern != "random" {
+                    let interval = effect.params.get("interval").and_then(|v| v.as_u64()).unwrap_or(4).clamp(2, 64) as usize;
+                    let sync_to_beat = effect.params.get("sync_to_beat").and_then(|v| v.as_bool()).unwrap_or(false);
+                    // One flash cycle per beat when synced, else per `life` seconds
+                    let cycles = if sync_to_beat { beat } else { t as f64 / life.max(0.01) as f64 };
+                    let cycle = cycles.floor() as i64;
+                    let progress = cycles.rem_euclid(1.0) as f32;
+                    let intensity = (1.0 - progress).powf(decay as f32).clamp(0.0, 1.0);
+                    let offset = if pattern == "moving" { cycle.rem_euclid(interval as i64) as usize } else { 0 };
+
+                    for strip in strips.iter_mut() {
+                        if let Some(t) = targets { if !t.contains(&strip.id) { continue; } }
+                        let pixel_count = strip.pixel_count.min(strip.data.len());
+                        let pixel_offset = strip.pixel_offset;
+                        for i in (0..pixel_count).filter(|i| structured_sparkle_lit(*i + pixel_offset, interval, offset)) {
+                            let px = &mut strip.data[i];
+                            for c in 0..3 {
+                                px[c] = px[c].saturating_add((color[c] as f32 * intensity) as u8);
+                            }
+                        }
+                    }
+                }
+
                 // Spawn new sparkles
-                if self.sparkle_states.len() < MAX_SPARKLES {
+                if pattern == "random" && self.sparkle_states.len() < MAX_SPARKLES {
                     for strip in strips.iter() {
                         if let Some(t) = targets { if !t.contains(&strip.id) { continue; } }
                         
@@ -1180,6 +2012,7 @@ impl LightingEngine {
                 let sync_to_beat = effect.params.get("sync_to_beat").and_then(|v| v.as_bool()).unwrap_or(false);
                 let rate_str = effect.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1 Bar");
                 let period = effect.params.get("period").and_then(|v| v.as_f64()).unwrap_or(4.0);
+                let stagger = strip_stagger(&effect.params);
 
                 // Calculate phase (0.0 to 1.0)
                 let phase = if sync_to_beat {
@@ -1197,27 +2030,104 @@ impl LightingEngine {
                     (t as f64 / period).fract()
                 };
 
-                // Apply sine wave for smooth oscillation
-                let sine_phase = ((phase * 2.0 * std::f64::consts::PI).sin() + 1.0) / 2.0;
-
-                // Linear interpolation between color_a and color_b
-                let r = (color_a[0] as f64 * (1.0 - sine_phase) + color_b[0] as f64 * sine_phase) as u8;
-                let g = (color_a[1] as f64 * (1.0 - sine_phase) + color_b[1] as f64 * sine_phase) as u8;
-                let b = (color_a[2] as f64 * (1.0 - sine_phase) + color_b[2] as f64 * sine_phase) as u8;
-
-                // Apply to all targeted strips
+                // Apply to all targeted strips, each shifted by its stagger offset
+                let mut strip_index = 0;
                 for strip in strips.iter_mut() {
                     if let Some(t) = targets {
                         if !t.contains(&strip.id) {
                             continue;
                         }
                     }
+                    let phase_offset = stagger.map_or(0.0, |(amount, by_position)| strip_phase_offset(strip_index, strip, amount, by_position));
+                    strip_index += 1;
+
+                    // Apply sine wave for smooth oscillation
+                    let sine_phase = (((phase + phase_offset as f64) * 2.0 * std::f64::consts::PI).sin() + 1.0) / 2.0;
+
+                    // Linear interpolation between color_a and color_b
+                    let r = (color_a[0] as f64 * (1.0 - sine_phase) + color_b[0] as f64 * sine_phase) as u8;
+                    let g = (color_a[1] as f64 * (1.0 - sine_phase) + color_b[1] as f64 * sine_phase) as u8;
+                    let b = (color_a[2] as f64 * (1.0 - sine_phase) + color_b[2] as f64 * sine_phase) as u8;
 
                     for pixel in &mut strip.data {
                         *pixel = [r, g, b];
                     }
                 }
             }
+            "Comet" => {
+                let color = effect.params.get("color").and_then(|v| {
+                    let arr = v.as_array()?;
+                    Some([arr.get(0)?.as_u64()? as u8,
+                          arr.get(1)?.as_u64()? as u8,
+                          arr.get(2)?.as_u64()? as u8])
+                }).unwrap_or([255, 255, 255]);
+
+                let angle = effect.params.get("angle").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+                let speed = effect.params.get("speed").and_then(|v| v.as_f64()).unwrap_or(0.5) as f32;
+                let sync_to_beat = effect.params.get("sync_to_beat").and_then(|v| v.as_bool()).unwrap_or(false);
+                let rate_str = effect.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1 Bar");
+                let trail = effect.params.get("trail").and_then(|v| v.as_f64()).unwrap_or(0.3).max(0.01) as f32;
+                let width = effect.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.1).max(0.01) as f32;
+
+                // Path runs along `dir` through the middle of the targeted pixels
+                let (sin_a, cos_a) = angle.to_radians().sin_cos();
+                let project = |(x, y): (f32, f32)| (x * cos_a + y * sin_a, -x * sin_a + y * cos_a);
+                let mut along_min = f32::MAX;
+                let mut along_max = f32::MIN;
+                let mut perp_min = f32::MAX;
+                let mut perp_max = f32::MIN;
+                for s in strips.iter() {
+                    if let Some(t) = targets { if !t.contains(&s.id) { continue; } }
+                    for i in 0..s.pixel_count.min(s.data.len()) {
+                        let (a, p) = project(s.pixel_position(i));
+                        along_min = along_min.min(a);
+                        along_max = along_max.max(a);
+                        perp_min = perp_min.min(p);
+                        perp_max = perp_max.max(p);
+                    }
+                }
+                if along_min > along_max { return; }
+                let perp_mid = (perp_min + perp_max) / 2.0;
+                let sweep = along_max - along_min + trail;
+
+                // Advance the head: beat-synced sweeps take one rate division, free ones `speed` sweeps/sec
+                let state = self.comet_states.entry(slot).or_insert(CometState { progress: 0.0, last_update: t, last_beat: beat });
+                let step = if sync_to_beat {
+                    let divisor = match rate_str {
+                        "4 Bar" => 16.0,
+                        "2 Bar" => 8.0,
+                        "1 Bar" => 4.0,
+                        "1/2" => 2.0,
+                        "1/4" => 1.0,
+                        "1/8" => 0.5,
+                        _ => 1.0,
+                    };
+                    ((beat - state.last_beat) / divisor) as f32
+                } else {
+                    (t - state.last_update) * speed * self.speed
+                };
+                state.last_update = t;
+                state.last_beat = beat;
+                state.progress = (state.progress + step.max(0.0)).fract();
+
+                let head = along_min + state.progress * sweep;
+                self.comet_heads.push((head * cos_a - perp_mid * sin_a, head * sin_a + perp_mid * cos_a));
+
+                for s in strips.iter_mut() {
+                    if let Some(t) = targets { if !t.contains(&s.id) { continue; } }
+                    for i in 0..s.pixel_count.min(s.data.len()) {
+                        let (a, p) = project(s.pixel_position(i));
+                        let intensity = comet_intensity(head - a, p - perp_mid, trail, width);
+                        if intensity <= 0.0 { continue; }
+                        let curr = s.data[i];
+                        s.data[i] = [
+                            curr[0].saturating_add((color[0] as f32 * intensity) as u8),
+                            curr[1].saturating_add((color[1] as f32 * intensity) as u8),
+                            curr[2].saturating_add((color[2] as f32 * intensity) as u8),
+                        ];
+                    }
+                }
+            }
             "GlitchSparkle" => {
                 // Parse parameters
                 let background_color = effect.params.get("background_color").and_then(|v| {
@@ -1357,6 +2267,7 @@ impl LightingEngine {
                 let tail_length = effect.params.get("tail_length").and_then(|v| v.as_f64()).unwrap_or(10.0) as f32;
                 let decay = effect.params.get("decay").and_then(|v| v.as_f64()).unwrap_or(2.0) as f32;
                 let direction = effect.params.get("direction").and_then(|v| v.as_str()).unwrap_or("Forward");
+                let stagger = strip_stagger(&effect.params);
 
                 // Calculate speed (pixels per second)
                 let speed = if sync {
@@ -1376,8 +2287,17 @@ impl LightingEngine {
                     manual_speed
                 } as f32;
 
+                // Strips with a pixel offset are segments of one run: they share its length
+                let targeted = |s: &&PixelStrip| match targets { Some(t) => t.contains(&s.id), None => true };
+                let run_len = if strips.iter().filter(targeted).any(|s| s.pixel_offset > 0) {
+                    strips.iter().filter(targeted).map(|s| s.pixel_offset + s.pixel_count).max()
+                } else {
+                    None
+                };
+
                 // Collect strip info and update positions
-                let mut strip_positions: Vec<(u64, usize, f32)> = Vec::new();
+                let mut strip_positions: Vec<(u64, usize, usize, f32)> = Vec::new();
+                let mut strip_index = 0;
 
                 for strip in strips.iter() {
                     if let Some(t) = targets {
@@ -1385,6 +2305,8 @@ impl LightingEngine {
                             continue;
                         }
                     }
+                    let phase_offset = stagger.map_or(0.0, |(amount, by_position)| strip_phase_offset(strip_index, strip, amount, by_position));
+                    strip_index += 1;
 
                     // Find or create pulse state for this strip
                     let pulse_state = self.pulse_states.iter_mut().find(|p| p.strip_id == strip.id);
@@ -1395,16 +2317,16 @@ impl LightingEngine {
                         state.last_update = t;
                         state.position += speed * dt;
 
-                        // Handle wrapping/bouncing based on direction
-                        let strip_len = strip.pixel_count as f32;
+                        // Handle wrapping/bouncing based on direction, staggered per strip
+                        let strip_len = run_len.unwrap_or(strip.pixel_count) as f32;
                         match direction {
                             "Reverse" => {
                                 state.position = state.position % strip_len;
-                                strip_len - state.position
+                                strip_len - (state.position + phase_offset * strip_len).rem_euclid(strip_len)
                             }
                             "Bounce" => {
                                 let cycle_len = strip_len * 2.0;
-                                let pos_in_cycle = state.position % cycle_len;
+                                let pos_in_cycle = (state.position + phase_offset * cycle_len).rem_euclid(cycle_len);
                                 if pos_in_cycle < strip_len {
                                     pos_in_cycle
                                 } else {
@@ -1413,7 +2335,7 @@ impl LightingEngine {
                             }
                             _ => { // "Forward"
                                 state.position = state.position % strip_len;
-                                state.position
+                                (state.position + phase_offset * strip_len).rem_euclid(strip_len)
                             }
                         }
                     } else {
@@ -1426,14 +2348,14 @@ impl LightingEngine {
                         0.0
                     };
 
-                    strip_positions.push((strip.id, strip.pixel_count, position));
+                    strip_positions.push((strip.id, strip.pixel_count, strip.pixel_offset, position));
                 }
 
                 // Now render pulses to strips
-                for (strip_id, pixel_count, position) in strip_positions {
+                for (strip_id, pixel_count, pixel_offset, position) in strip_positions {
                     if let Some(strip_mut) = strips.iter_mut().find(|s| s.id == strip_id) {
                         for i in 0..pixel_count {
-                            let pixel_pos = i as f32;
+                            let pixel_pos = (i + pixel_offset) as f32;
                             let distance = (pixel_pos - position).abs();
 
                             if distance < tail_length {
@@ -1491,17 +2413,21 @@ impl LightingEngine {
                     "1/8" => 0.5,
                     _ => 1.0,
                 };
+                // Pulse mode blinks each group on and off, so it is subject to the flash limits
+                let divisor = if mode == "Pulse" { self.limit_flash_divisor(divisor) } else { divisor };
                 let phase = (beat / divisor).fract();
                 let a_is_active = phase < 0.5;
 
                 // Determine colors based on mode
                 let (color_when_a_active, color_when_b_active) = match mode {
                     "Pulse" => {
-                        // Pulse mode: active gets color, inactive gets black
+                        // Pulse mode: active gets color, inactive gets black (or the contrast floor)
+                        let floor = self.limit_flash_contrast(0.0);
+                        let dim = |c: [u8; 3]| c.map(|v| (v as f32 * floor) as u8);
                         if a_is_active {
-                            (group_a_color, [0, 0, 0])
+                            (group_a_color, dim(group_b_color))
                         } else {
-                            ([0, 0, 0], group_b_color)
+                            (dim(group_a_color), group_b_color)
                         }
                     }
                     _ => { // "Swap" mode (default)
@@ -1540,6 +2466,498 @@ impl LightingEngine {
     }
 }
 
+/// Apply a mask's audio band mappings (`audio_<target>_band`, see `AUDIO_MOD_TARGETS`) to a copy
+/// of it. `level` reads a band's raw level, which `audio_gain` scales into 0..1. None when unmapped.
+fn audio_modulated_mask(mask: &Mask, level: impl Fn(&str) -> f32) -> Option<Mask> {
+    let gain = mask.params.get("audio_gain").and_then(|v| v.as_f64()).unwrap_or(4.0) as f32;
+    let amounts: Vec<(&str, f32)> = AUDIO_MOD_TARGETS.iter()
+        .filter_map(|target| {
+            let band = mask.params.get(&format!("audio_{}_band", target)).and_then(|v| v.as_str())?;
+            (band != "off").then(|| (*target, (level(band) * gain).clamp(0.0, 1.0)))
+        })
+        .collect();
+    if amounts.is_empty() {
+        return None;
+    }
+
+    let mut m = mask.clone();
+    let colors = |m: &mut Mask, f: &dyn Fn([u8; 3]) -> [u8; 3]| {
+        for key in ["color", "color2"] {
+            if let Some(c) = m.params.get(key).and_then(|v| serde_json::from_value::<[u8; 3]>(v.clone()).ok()) {
+                m.params.insert(key.into(), serde_json::json!(f(c)));
+            }
+        }
+        if let Some(list) = m.params.get("gradient_colors").and_then(|v| serde_json::from_value::<Vec<[u8; 3]>>(v.clone()).ok()) {
+            let list: Vec<[u8; 3]> = list.into_iter().map(f).collect();
+            m.params.insert("gradient_colors".into(), serde_json::json!(list));
+        }
+    };
+    for (target, amount) in amounts {
+        match target {
+            // Grow up to double size
+            "size" => {
+                for key in ["width", "height", "bar_width", "radius"] {
+                    if let Some(v) = m.params.get(key).and_then(|v| v.as_f64()) {
+                        m.params.insert(key.into(), (v * (1.0 + amount as f64)).into());
+                    }
+                }
+            }
+            // Rise by up to a quarter of the canvas
+            "position" => m.y -= amount * 0.25,
+            "intensity" => colors(&mut m, &|c| c.map(|v| (v as f32 * amount) as u8)),
+            // Rotate hue by up to a full turn
+            "color" => colors(&mut m, &|c| {
+                let (h, s, v) = rgb_to_hsv(c);
+                hsv_to_rgb((h + amount).rem_euclid(1.0), s, v)
+            }),
+            _ => {}
+        }
+    }
+    Some(m)
+}
+
+/// Color for the "audio" color mode: hue from `audio_hue_source` (default the band centroid,
+/// bass red through treble violet) and brightness from `audio_value_source` ("off" = full).
+/// `level` reads a source's raw level; band levels are scaled by `audio_gain`, the centroid isn't.
+fn audio_color(mask: &Mask, level: impl Fn(&str) -> f32) -> [u8; 3] {
+    let gain = mask.params.get("audio_gain").and_then(|v| v.as_f64()).unwrap_or(4.0) as f32;
+    let amount = |source: &str| {
+        let raw = level(source);
+        if source == "centroid" { raw.clamp(0.0, 1.0) } else { (raw * gain).clamp(0.0, 1.0) }
+    };
+    let hue_source = mask.params.get("audio_hue_source").and_then(|v| v.as_str()).unwrap_or("centroid");
+    let value_source = mask.params.get("audio_value_source").and_then(|v| v.as_str()).unwrap_or("volume");
+    // Stop short of a full turn so the top of the range doesn't wrap back to the bottom's red
+    let hue = amount(hue_source) * 0.8;
+    let value = if value_source == "off" { 1.0 } else { amount(value_source) };
+    hsv_to_rgb(hue, 1.0, value)
+}
+
+/// How long the heartbeat stays red after a send error
+const HEARTBEAT_ERROR_HOLD_SECS: f32 = 2.0;
+
+/// Errors and sync loss win over everything; otherwise "ok" while packets are going out
+fn heartbeat_status(sync_lost: bool, recent_send_error: bool, sending: bool) -> &'static str {
+    if sync_lost || recent_send_error {
+        "error"
+    } else if sending {
+        "ok"
+    } else {
+        "idle"
+    }
+}
+
+/// Beat before phase correction: raw Link when the flywheel is off and Link leads, otherwise the flywheel
+fn base_beat(use_flywheel: bool, link_leads: bool, flywheel_beat: f64, link_beat: f64) -> f64 {
+    if !use_flywheel && link_leads {
+        link_beat
+    } else {
+        flywheel_beat
+    }
+}
+
+/// First source in `priority` that currently has a tempo: Link needs peers, audio needs a
+/// detected BPM, manual is always available (and is the fallback if the list runs out)
+fn select_beat_source(priority: &[String], link_peers: u64, audio_bpm: f64) -> &'static str {
+    for source in priority {
+        match source.as_str() {
+            "link" if link_peers > 0 => return "link",
+            "audio" if audio_bpm > 30.0 => return "audio",
+            "manual" => return "manual",
+            _ => {}
+        }
+    }
+    "manual"
+}
+
+/// Wrap one universe's DMX channels for the wire. sACN carries the DMX start code inline
+/// as the first property value (513 bytes); Art-Net's ArtDmx has no start code slot, so
+/// its payload is the channels alone, padded to the even length the spec requires.
+pub fn frame_for_protocol(protocol: &str, dmx: &[u8]) -> Vec<u8> {
+    let channels = &dmx[..dmx.len().min(512)];
+    match protocol {
+        "artnet" => {
+            let mut frame = channels.to_vec();
+            if frame.len() % 2 == 1 || frame.is_empty() {
+                frame.push(0);
+            }
+            frame
+        }
+        _ => {
+            let mut frame = Vec::with_capacity(channels.len() + 1);
+            frame.push(0u8); // Start Code: null (dimmer) data
+            frame.extend_from_slice(channels);
+            frame
+        }
+    }
+}
+
+/// Pack strip pixels (dimmer curve, LUT, then color order, or luma for 1-channel pixels) and fixture channels into 512-byte DMX
+/// buffers keyed by output universe, after universe reversal and the global offset
+pub fn pack_universes(state: &AppState) -> std::collections::HashMap<u16, Vec<u8>> {
+    // Coalesce data by universe
+    let mut universe_data: std::collections::HashMap<u16, Vec<u8>> = std::collections::HashMap::new();
+
+    let global_universe_offset = state.network.universe_offset();
+    let strip_universe_range = state.strip_universe_range();
+
+    for strip in &state.strips {
+         // specific strip universe (mirrored for reverse-numbered controllers) + global offset (clamped to valid sACN range 1-63999)
+         let u = state.network.effective_universe(strip.universe, strip_universe_range);
+
+         // sACN allows multiple strips in one universe if channels don't overlap
+         let prefix_start = (strip.start_channel as usize).saturating_sub(1);
+
+         // Ensure we have a buffer (512 bytes for DMX)
+         let entry = universe_data.entry(u).or_insert_with(|| vec![0; 512]);
+
+         // Static control block (e.g. master dimmer) first, pixels right after it
+         for (i, value) in strip.channel_prefix.iter().enumerate() {
+             if let Some(slot) = entry.get_mut(prefix_start + i) {
+                 *slot = *value;
+             }
+         }
+         let start = prefix_start + strip.channel_prefix.len();
+         let curve = dimmer_curve_lut(&strip.dimmer_curve);
+
+         for (i, pixel) in strip.data.iter().enumerate() {
+             let idx = start + i * strip.channels_per_pixel as usize;
+             let pixel = match curve {
+                 Some(table) => pixel.map(|c| table[c as usize]),
+                 None => *pixel,
+             };
+             let pixel = match &strip.lut {
+                 Some(lut) => lut.apply(pixel),
+                 None => pixel,
+             };
+             if strip.channels_per_pixel == 1 {
+                 if let Some(slot) = entry.get_mut(idx) {
+                     *slot = strip.luma(pixel);
+                 }
+                 continue;
+             }
+             // Bounds check: ensure idx, idx+1, idx+2 are all valid
+             if let Some(max_idx) = idx.checked_add(2) {
+                 if max_idx < entry.len() {
+                     for (slot, component) in color_order_indices(&strip.color_order).into_iter().enumerate() {
+                         entry[idx + slot] = pixel[component];
+                     }
+                 }
+             }
+         }
+    }
+
+
+    for fixture in &state.fixtures {
+        let u = fixture.universe.saturating_add(global_universe_offset).clamp(1, 63999);
+        let start = (fixture.start_channel as usize).saturating_sub(1);
+        let entry = universe_data.entry(u).or_insert_with(|| vec![0; 512]);
+        for (i, value) in fixture.channel_values().into_iter().enumerate() {
+            if let Some(slot) = entry.get_mut(start + i) {
+                *slot = value;
+            }
+        }
+    }
+
+    universe_data
+}
+
+/// Drop muted universes from the output, except for one all-zero frame the first time each
+/// is muted so the node doesn't hold its last color. `blacked` tracks which have had it.
+fn mute_universes(universe_data: &mut std::collections::HashMap<u16, Vec<u8>>, muted: &[u16], blacked: &mut std::collections::HashSet<u16>) {
+    blacked.retain(|u| muted.contains(u));
+    for &u in muted {
+        if blacked.contains(&u) {
+            universe_data.remove(&u);
+        } else if let Some(data) = universe_data.get_mut(&u) {
+            data.fill(0);
+            blacked.insert(u);
+        }
+    }
+}
+
+/// Double a beat divisor until one flash per `divisor` beats at `tempo` BPM is at most `max_hz`.
+/// Doubling keeps the slowed flash on the beat grid.
+fn limit_flash_divisor(divisor: f64, tempo: f64, max_hz: f32) -> f64 {
+    let max_hz = (max_hz as f64).max(0.1);
+    let mut divisor = divisor;
+    while tempo / 60.0 / divisor > max_hz && divisor < 64.0 {
+        divisor *= 2.0;
+    }
+    divisor
+}
+
+/// Per-strip stagger from effect params: `(cycles per step, step by world position)`,
+/// or None when unstaggered. Index mode steps once per targeted strip; position mode
+/// steps per canvas unit of the strip's height, so staggered banks form diagonal waves.
+fn strip_stagger(params: &std::collections::HashMap<String, serde_json::Value>) -> Option<(f32, bool)> {
+    let amount = params.get("stagger").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+    if amount == 0.0 {
+        return None;
+    }
+    let by_position = params.get("stagger_by").and_then(|v| v.as_str()) == Some("position");
+    Some((amount, by_position))
+}
+
+/// Phase offset, in cycles, for the `index`th targeted strip
+fn strip_phase_offset(index: usize, strip: &PixelStrip, stagger: f32, by_position: bool) -> f32 {
+    let steps = if by_position { strip.y } else { index as f32 };
+    steps * stagger
+}
+
+/// Comet brightness for a pixel `behind` the head along the path and `perp` off it:
+/// a squared fade over `trail` and a linear falloff across `width`
+fn comet_intensity(behind: f32, perp: f32, trail: f32, width: f32) -> f32 {
+    if !(0.0..=trail).contains(&behind) || perp.abs() >= width {
+        return 0.0;
+    }
+    (1.0 - behind / trail).powi(2) * (1.0 - perp.abs() / width)
+}
+
+/// Structured sparkle: every `interval`th pixel is lit, shifted along by `offset` pixels
+fn structured_sparkle_lit(pixel: usize, interval: usize, offset: usize) -> bool {
+    pixel % interval == offset % interval
+}
+
+/// 1-based beat number within a bar for a beat count (or Link bar phase)
+fn beat_in_bar(beat: f64, beats_per_bar: u8) -> u8 {
+    let beats_per_bar = beats_per_bar.max(1);
+    (beat.rem_euclid(beats_per_bar as f64).floor() as u8).min(beats_per_bar - 1) + 1
+}
+
+/// Sync tuning with every constant clamped to its valid range
+fn clamped_sync_tuning(audio: &AudioConfig) -> AudioConfig {
+    let clamp = |v: f32, r: &std::ops::RangeInclusive<f32>| if v.is_finite() { v.clamp(*r.start(), *r.end()) } else { *r.start() };
+    AudioConfig {
+        flywheel_jump_beats: clamp(audio.flywheel_jump_beats, &FLYWHEEL_JUMP_BEATS_RANGE),
+        flywheel_recovery_secs: clamp(audio.flywheel_recovery_secs, &FLYWHEEL_RECOVERY_SECS_RANGE),
+        flywheel_nudge: clamp(audio.flywheel_nudge, &FLYWHEEL_NUDGE_RANGE),
+        phase_correction: clamp(audio.phase_correction, &PHASE_CORRECTION_RANGE),
+        phase_decay: clamp(audio.phase_decay, &PHASE_DECAY_RANGE),
+        manual_bpm: clamp(audio.manual_bpm, &MANUAL_BPM_RANGE),
+        beats_per_bar: audio.beats_per_bar.clamp(*BEATS_PER_BAR_RANGE.start(), *BEATS_PER_BAR_RANGE.end()),
+        trigger_threshold: clamp(audio.trigger_threshold, &TRIGGER_THRESHOLD_RANGE),
+        trigger_cooldown_secs: clamp(audio.trigger_cooldown_secs, &TRIGGER_COOLDOWN_SECS_RANGE),
+        ..audio.clone()
+    }
+}
+
+/// Layer the mask pixel against the base. The top layer's brightest channel acts as its
+/// coverage, so soft mask edges blend instead of punching hard holes: with `base_over` the base
+/// covers the masks, otherwise the masks cover (replace) the base.
+fn composite_pixel(base: [u8; 3], mask: [u8; 3], base_over: bool) -> [u8; 3] {
+    let (top, bottom) = if base_over { (base, mask) } else { (mask, base) };
+    let coverage = top.iter().copied().max().unwrap_or(0) as f32 / 255.0;
+    std::array::from_fn(|c| (top[c] as f32 + bottom[c] as f32 * (1.0 - coverage)).round().min(255.0) as u8)
+}
+
+/// Intensity multiplier for a pixel `dist_inside` units inside a mask outline: ramps from 0 at
+/// the edge to 1 at `softness` inward. Softness 0 keeps the hard cut.
+fn edge_falloff(dist_inside: f32, softness: f32) -> f32 {
+    if softness <= 0.0 {
+        return 1.0;
+    }
+    (dist_inside / softness).clamp(0.0, 1.0)
+}
+
+/// Built-in commissioning patterns as (kind, label)
+pub const TEST_PATTERNS: [(&str, &str); 4] = [
+    ("color_order", "Color order (R, G, B)"),
+    ("rgb_walk", "Solid R/G/B/W walk"),
+    ("gradient", "Index gradient"),
+    ("bar", "Moving bar"),
+];
+
+/// Seconds each primary is held by the color order check
+const COLOR_ORDER_HOLD: f32 = 2.0;
+
+fn color_order_index(t: f32) -> usize {
+    (t / COLOR_ORDER_HOLD).rem_euclid(3.0) as usize % 3
+}
+
+/// Overwrite every strip with a test pattern. Gradients and bars run from each strip's first
+/// pixel, so reversed or short strips show up on the rig. "red", "green" and "blue" hold a
+/// single primary (the guided color order check steps through them).
+fn render_test_pattern(pattern: &str, strips: &mut [PixelStrip], t: f32) {
+    const PRIMARIES: [[u8; 3]; 3] = [[255, 0, 0], [0, 255, 0], [0, 0, 255]];
+    for strip in strips.iter_mut() {
+        let count = strip.pixel_count.min(strip.data.len());
+        match pattern {
+            "color_order" => strip.data.fill(PRIMARIES[color_order_index(t)]),
+            "red" => strip.data.fill(PRIMARIES[0]),
+            "green" => strip.data.fill(PRIMARIES[1]),
+            "blue" => strip.data.fill(PRIMARIES[2]),
+            "rgb_walk" => {
+                let step = t.rem_euclid(4.0) as usize % 4;
+                strip.data.fill(if step == 3 { [255, 255, 255] } else { PRIMARIES[step] });
+            }
+            "gradient" => {
+                // Dim to bright by index, first pixel green and last pixel red as end markers
+                for i in 0..count {
+                    let v = (((i + 1) as f32 / count as f32) * 255.0) as u8;
+                    strip.data[i] = [v, v, v];
+                }
+                if count > 1 {
+                    strip.data[0] = [0, 255, 0];
+                    strip.data[count - 1] = [255, 0, 0];
+                }
+            }
+            "bar" => {
+                // A 5-pixel bar crossing the strip every 2 seconds
+                let head = ((t / 2.0).rem_euclid(1.0) * count as f32) as usize;
+                let start = head.saturating_sub(4);
+                strip.data.fill([0, 0, 0]);
+                for px in strip.data.iter_mut().take(count).skip(start).take(head + 1 - start) {
+                    *px = [255, 255, 255];
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Deterministic per-pixel phase offset in -0.5..0.5 cycles, from a mask's jitter seed and the
+/// pixel's strip and index (splitmix64), so the pattern is the same every frame
+fn pixel_jitter(seed: u64, strip_id: u64, index: usize) -> f32 {
+    let mut z = seed ^ strip_id.rotate_left(32) ^ (index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^= z >> 31;
+    (z >> 40) as f32 / (1u64 << 24) as f32 - 0.5
+}
+
+/// Image coordinates (0..1 across, 0..1 down) of a point at offset (dx, dy) from the center of an
+/// image mask `width` x `height` rotated by `rotation_deg`, or None if the point is outside it
+fn image_mask_uv(dx: f32, dy: f32, width: f32, height: f32, rotation_deg: f32) -> Option<(f32, f32)> {
+    if width <= 0.0 || height <= 0.0 {
+        return None;
+    }
+    // Same local frame as the scanner mask
+    let (sin_rot, cos_rot) = rotation_deg.to_radians().sin_cos();
+    let local_x = dx * cos_rot + dy * sin_rot;
+    let local_y = -dx * sin_rot + dy * cos_rot;
+    let u = local_x / width + 0.5;
+    let v = local_y / height + 0.5;
+    ((0.0..=1.0).contains(&u) && (0.0..=1.0).contains(&v)).then_some((u, v))
+}
+
+/// Nearest pixel of an image mask's "image" param (a `MaskImage`) at image coordinates (u, v).
+/// Reads the JSON in place so the picture isn't decoded every frame.
+fn sample_mask_image(image: &serde_json::Value, u: f32, v: f32) -> Option<[u8; 3]> {
+    let width = image.get("width")?.as_u64()? as usize;
+    let height = image.get("height")?.as_u64()? as usize;
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let x = ((u * width as f32) as usize).min(width - 1);
+    let y = ((v * height as f32) as usize).min(height - 1);
+    let rgb = image.get("pixels")?.as_array()?.get(y * width + x)?.as_array()?;
+    Some([rgb.first()?.as_u64()? as u8, rgb.get(1)?.as_u64()? as u8, rgb.get(2)?.as_u64()? as u8])
+}
+
+/// Combine a mask's contribution with what's already on the pixel, per `AppState::mask_compositing`.
+/// "max" keeps the brighter value per channel (HTP), "ltp" lets the later mask win, anything else adds.
+fn blend_mask_pixel(curr: [u8; 3], color: [u8; 3], mode: &str) -> [u8; 3] {
+    match mode {
+        "max" => [curr[0].max(color[0]), curr[1].max(color[1]), curr[2].max(color[2])],
+        "ltp" => color,
+        _ => [
+            curr[0].saturating_add(color[0]),
+            curr[1].saturating_add(color[1]),
+            curr[2].saturating_add(color[2]),
+        ],
+    }
+}
+
+/// Remove masks whose lifetime has run out and return their ids. A spawn time in the future
+/// (the clock restarted since it was set) restarts the countdown from now.
+fn expire_masks(scenes: &mut [Scene], time: f64, beat: f64) -> Vec<u64> {
+    let mut expired = Vec::new();
+    for scene in scenes.iter_mut() {
+        for mask in scene.masks.iter_mut().filter(|m| m.params.contains_key("lifetime")) {
+            let now = if mask.lifetime_beats() { beat } else { time };
+            if mask.params.get("spawn_time").and_then(|v| v.as_f64()).is_some_and(|spawn| spawn > now) {
+                mask.params.insert("spawn_time".into(), now.into());
+            }
+        }
+        scene.masks.retain(|m| {
+            let dead = m.life_remaining(time, beat) == Some(0.0);
+            if dead {
+                expired.push(m.id);
+            }
+            !dead
+        });
+    }
+    expired
+}
+
+/// Blend strips back towards `before` so only `amount` of what was drawn since remains
+fn fade_from(strips: &mut [PixelStrip], before: &[Vec<[u8; 3]>], amount: f32) {
+    for (strip, before) in strips.iter_mut().zip(before) {
+        for (px, b) in strip.data.iter_mut().zip(before) {
+            for c in 0..3 {
+                px[c] = (b[c] as f32 + (px[c] as f32 - b[c] as f32) * amount).round() as u8;
+            }
+        }
+    }
+}
+
+/// Black out every strip at its current pixel_count. Reuses each strip's buffer, so a count edited
+/// in the UI takes effect without reallocating every frame.
+fn clear_strips(strips: &mut [PixelStrip]) {
+    for strip in strips {
+        strip.data.clear();
+        strip.data.resize(strip.pixel_count, [0, 0, 0]);
+    }
+}
+
+/// Pixels currently on the rig: every strip's data, then each fixture's color as a one-pixel strip
+fn capture_frame(strips: &[PixelStrip], fixtures: &[Fixture]) -> Vec<Vec<[u8; 3]>> {
+    strips.iter().map(|s| s.data.clone())
+        .chain(fixtures.iter().map(|f| vec![f.color]))
+        .collect()
+}
+
+/// Put a captured frame back. Strips whose size changed (or that are new) since the capture go dark.
+fn restore_frame(strips: &mut [PixelStrip], held: &[Vec<[u8; 3]>]) {
+    for (i, strip) in strips.iter_mut().enumerate() {
+        strip.data = match held.get(i) {
+            Some(data) if data.len() == strip.pixel_count => data.clone(),
+            _ => vec![[0, 0, 0]; strip.pixel_count],
+        };
+    }
+}
+
+/// Multiply every pixel by `factor` (clamped to 0..1); no-op at full intensity
+fn scale_strips(strips: &mut [PixelStrip], factor: f32) {
+    let factor = factor.clamp(0.0, 1.0);
+    if factor >= 1.0 {
+        return;
+    }
+    for strip in strips.iter_mut() {
+        for px in strip.data.iter_mut() {
+            for c in px.iter_mut() {
+                *c = (*c as f32 * factor) as u8;
+            }
+        }
+    }
+}
+
+/// Lerp `data` toward `prev` by `retain` (0 = no smoothing) and store the result back in `prev`
+fn smooth_pixels(prev: &mut Vec<[f32; 3]>, data: &mut [[u8; 3]], retain: f32) {
+    if prev.len() != data.len() {
+        // New or resized strip: start from the current frame
+        *prev = data.iter().map(|px| px.map(|c| c as f32)).collect();
+        return;
+    }
+    for (p, px) in prev.iter_mut().zip(data.iter_mut()) {
+        for c in 0..3 {
+            p[c] = p[c] * retain + px[c] as f32 * (1.0 - retain);
+            px[c] = p[c].round() as u8;
+        }
+    }
+}
+
 pub fn hsv_to_rgb(h: f32, s: f32, v: f32) -> [u8; 3] {
     let h_i = (h * 6.0) as i32;
     let f = h * 6.0 - h_i as f32;
@@ -1559,22 +2977,135 @@ pub fn hsv_to_rgb(h: f32, s: f32, v: f32) -> [u8; 3] {
     [(r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8]
 }
 
-/// Apply LFO modulation to a parameter value
+/// Inverse of `hsv_to_rgb`: (hue 0..1, saturation 0..1, value 0..1)
+pub fn rgb_to_hsv(c: [u8; 3]) -> (f32, f32, f32) {
+    let [r, g, b] = c.map(|v| v as f32 / 255.0);
+    let max = r.max(g).max(b);
+    let delta = max - r.min(g).min(b);
+    let h = if delta == 0.0 {
+        0.0
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.0) / 6.0
+    } else if max == g {
+        ((b - r) / delta + 2.0) / 6.0
+    } else {
+        ((r - g) / delta + 4.0) / 6.0
+    };
+    let s = if max == 0.0 { 0.0 } else { delta / max };
+    (h, s, max)
+}
+
+/// sRGB to Oklab (L, a, b)
+fn rgb_to_oklab(c: [u8; 3]) -> [f32; 3] {
+    let [r, g, b] = c.map(|v| {
+        let v = v as f32 / 255.0;
+        if v <= 0.04045 { v / 12.92 } else { ((v + 0.055) / 1.055).powf(2.4) }
+    });
+    let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+    [
+        0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    ]
+}
+
+/// Oklab (L, a, b) back to sRGB, clipped to the displayable range
+fn oklab_to_rgb(lab: [f32; 3]) -> [u8; 3] {
+    let l = (lab[0] + 0.396_337_78 * lab[1] + 0.215_803_76 * lab[2]).powi(3);
+    let m = (lab[0] - 0.105_561_346 * lab[1] - 0.063_854_17 * lab[2]).powi(3);
+    let s = (lab[0] - 0.089_484_18 * lab[1] - 1.291_485_5 * lab[2]).powi(3);
+    let linear = [
+        4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+        -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+        -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+    ];
+    linear.map(|v| {
+        let v = v.clamp(0.0, 1.0);
+        let srgb = if v <= 0.003_130_8 { v * 12.92 } else { 1.055 * v.powf(1.0 / 2.4) - 0.055 };
+        (srgb * 255.0).round() as u8
+    })
+}
+
+/// Fraction of each swing a "Hold" motion spends resting at the ends
+const MOTION_HOLD_FRACTION: f64 = 0.25;
+
+/// Back-and-forth position (-1..1) at `phase` radians, shaped like `phase.sin()` by a motion
+/// easing: "Smooth" (sine), "Linear" (constant speed, turns sharply), "Ease In-Out" (cubic,
+/// lingers longer at the ends) or "Hold" (linear travel, then rests at each end)
+pub fn motion_curve(phase: f64, motion: &str) -> f64 {
+    let linear = (2.0 / std::f64::consts::PI) * phase.sin().asin();
+    match motion {
+        "Linear" => linear,
+        "Ease In-Out" => {
+            let s = (linear + 1.0) / 2.0;
+            let eased = if s < 0.5 { 4.0 * s.powi(3) } else { 1.0 - (2.0 - 2.0 * s).powi(3) / 2.0 };
+            eased * 2.0 - 1.0
+        }
+        "Hold" => (linear / (1.0 - MOTION_HOLD_FRACTION)).clamp(-1.0, 1.0),
+        _ => phase.sin(),
+    }
+}
+
+/// Blend two gradient stops at `t` (0..1) in the given space: "rgb" (straight lerp),
+/// "hsv" (shortest way around the hue wheel, keeps saturation) or "oklab" (perceptually even)
+pub fn interpolate_color(a: [u8; 3], b: [u8; 3], t: f32, space: &str) -> [u8; 3] {
+    let lerp = |x: f32, y: f32| x + (y - x) * t;
+    match space {
+        "hsv" => {
+            let (h1, s1, v1) = rgb_to_hsv(a);
+            let (h2, s2, v2) = rgb_to_hsv(b);
+            // A gray stop has no hue of its own; borrow the other stop's so only saturation changes
+            let h1 = if s1 == 0.0 { h2 } else { h1 };
+            let h2 = if s2 == 0.0 { h1 } else { h2 };
+            let mut dh = h2 - h1;
+            if dh > 0.5 { dh -= 1.0; } else if dh < -0.5 { dh += 1.0; }
+            hsv_to_rgb((h1 + dh * t).rem_euclid(1.0), lerp(s1, s2), lerp(v1, v2))
+        }
+        "oklab" => {
+            let (la, lb) = (rgb_to_oklab(a), rgb_to_oklab(b));
+            oklab_to_rgb([lerp(la[0], lb[0]), lerp(la[1], lb[1]), lerp(la[2], lb[2])])
+        }
+        _ => [
+            lerp(a[0] as f32, b[0] as f32) as u8,
+            lerp(a[1] as f32, b[1] as f32) as u8,
+            lerp(a[2] as f32, b[2] as f32) as u8,
+        ],
+    }
+}
+
+/// Apply LFO modulation to a parameter value: the param's LFO and optional second LFO are summed,
+/// and the result is clamped to the parameter's valid range
 fn apply_lfo_modulation(
     base_value: f32,
     params: &std::collections::HashMap<String, serde_json::Value>,
     param_name: &str,
+    range: std::ops::RangeInclusive<f32>,
+    t: f32,
+    beat: f64,
+) -> f32 {
+    let modulation = lfo_value(params, &format!("{}_lfo", param_name), t, beat)
+        + lfo_value(params, &format!("{}_lfo2", param_name), t, beat);
+    (base_value * (1.0 + modulation)).clamp(*range.start(), *range.end())
+}
+
+/// Current output of the LFO whose keys start with `prefix`, 0 if disabled.
+/// Bipolar swings -depth..depth around the base; unipolar only dips below it (-depth..0).
+fn lfo_value(
+    params: &std::collections::HashMap<String, serde_json::Value>,
+    prefix: &str,
     t: f32,
     beat: f64,
 ) -> f32 {
-    let lfo_key = |suffix: &str| format!("{}_lfo_{}", param_name, suffix);
+    let lfo_key = |suffix: &str| format!("{}_{}", prefix, suffix);
 
     let enabled = params.get(&lfo_key("enabled"))
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
     if !enabled {
-        return base_value;
+        return 0.0;
     }
 
     let depth = params.get(&lfo_key("depth"))
@@ -1589,6 +3120,11 @@ fn apply_lfo_modulation(
         .and_then(|v| v.as_bool())
         .unwrap_or(false);
 
+    // Phase offset shifts this LFO against others running at the same rate
+    let phase_offset = params.get(&lfo_key("phase"))
+        .and_then(|v| v.as_f64())
+        .unwrap_or(0.0) as f32;
+
     let phase = if is_sync {
         let rate_str = params.get(&lfo_key("rate"))
             .and_then(|v| v.as_str())
@@ -1607,6 +3143,7 @@ fn apply_lfo_modulation(
             .unwrap_or(1.0) as f32;
         (t * hz).fract()
     };
+    let phase = (phase + phase_offset).rem_euclid(1.0);
 
     let wave_value = match waveform {
         "sine" => (phase * std::f32::consts::TAU).sin(),
@@ -1615,9 +3152,691 @@ fn apply_lfo_modulation(
             tri * 2.0 - 1.0
         },
         "sawtooth" => phase * 2.0 - 1.0,
+        "ease" => motion_curve(phase as f64 * std::f64::consts::TAU, "Ease In-Out") as f32,
+        "hold" => motion_curve(phase as f64 * std::f64::consts::TAU, "Hold") as f32,
         _ => 0.0,
     };
 
-    let modulation = wave_value * depth;
-    base_value * (1.0 + modulation)
+    let mode = params.get(&lfo_key("mode"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("bipolar");
+
+    if mode == "unipolar" {
+        -(wave_value + 1.0) * 0.5 * depth
+    } else {
+        wave_value * depth
+    }
+}
+
+/// Registered universes that are neither in this frame's output nor muted, ascending
+fn stale_universes(registered: &std::collections::HashSet<u16>, universe_data: &std::collections::HashMap<u16, Vec<u8>>, muted: &[u16]) -> Vec<u16> {
+    let mut stale: Vec<u16> = registered.iter()
+        .filter(|u| !universe_data.contains_key(u) && !muted.contains(u))
+        .copied()
+        .collect();
+    stale.sort_unstable();
+    stale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_stale_universes_exclude_output_and_muted() {
+        let registered = std::collections::HashSet::from([1u16, 2, 3, 4]);
+        let data = HashMap::from([(1u16, vec![0u8; 512]), (5u16, vec![0u8; 512])]);
+        assert_eq!(stale_universes(&registered, &data, &[3]), vec![2, 4]);
+        assert!(stale_universes(&registered, &HashMap::from([(1u16, vec![]), (2, vec![]), (3, vec![]), (4, vec![])]), &[]).is_empty());
+    }
+
+    fn lfo_params(mode: &str) -> HashMap<String, serde_json::Value> {
+        let mut params = HashMap::new();
+        params.insert("radius_lfo_enabled".into(), true.into());
+        params.insert("radius_lfo_depth".into(), 0.5.into());
+        params.insert("radius_lfo_waveform".into(), "sawtooth".into());
+        params.insert("radius_lfo_sync".into(), true.into());
+        params.insert("radius_lfo_rate".into(), "1 Bar".into());
+        params.insert("radius_lfo_mode".into(), mode.into());
+        params
+    }
+
+    /// Radius at a point in the bar; the synced sawtooth runs -1..1 over 4 beats
+    fn radius_at(params: &HashMap<String, serde_json::Value>, beat: f64) -> f32 {
+        apply_lfo_modulation(1.0, params, "radius", 0.0..=5.0, 0.0, beat)
+    }
+
+    #[test]
+    fn test_bipolar_lfo_swings_around_base() {
+        let params = lfo_params("bipolar");
+        assert!((radius_at(&params, 0.0) - 0.5).abs() < 1e-4);
+        assert!((radius_at(&params, 2.0) - 1.0).abs() < 1e-4);
+        assert!((radius_at(&params, 3.0) - 1.25).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_unipolar_lfo_only_dips_below_base() {
+        let params = lfo_params("unipolar");
+        assert!((radius_at(&params, 0.0) - 1.0).abs() < 1e-4);
+        assert!((radius_at(&params, 2.0) - 0.75).abs() < 1e-4);
+        for i in 0..16 {
+            let r = radius_at(&params, i as f64 * 0.25);
+            assert!((0.5..=1.0).contains(&r), "radius {} out of unipolar range", r);
+        }
+    }
+
+    #[test]
+    fn test_lfo_result_is_clamped_to_range() {
+        let mut params = lfo_params("bipolar");
+        params.insert("radius_lfo2_enabled".into(), true.into());
+        params.insert("radius_lfo2_depth".into(), 1.0.into());
+        params.insert("radius_lfo2_waveform".into(), "sawtooth".into());
+        params.insert("radius_lfo2_sync".into(), true.into());
+        params.insert("radius_lfo2_rate".into(), "1 Bar".into());
+        // Both LFOs at their minimum sum to -1.5, which would go negative unclamped
+        assert_eq!(radius_at(&params, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_motion_curves_share_the_sine_cycle() {
+        use std::f64::consts::{FRAC_PI_2, PI};
+        for motion in ["Smooth", "Linear", "Ease In-Out", "Hold"] {
+            // Every curve starts centered heading up and reaches both edges
+            assert!(motion_curve(0.0, motion).abs() < 1e-9, "{}", motion);
+            assert!((motion_curve(FRAC_PI_2, motion) - 1.0).abs() < 1e-9, "{}", motion);
+            assert!((motion_curve(3.0 * FRAC_PI_2, motion) + 1.0).abs() < 1e-9, "{}", motion);
+        }
+        // Ease In-Out is nearer the edge than Smooth late in a swing; Hold is already resting
+        let near_edge = FRAC_PI_2 * 0.85;
+        assert!(motion_curve(near_edge, "Ease In-Out") > motion_curve(near_edge, "Smooth"));
+        assert_eq!(motion_curve(near_edge, "Hold"), 1.0);
+        assert!(motion_curve(PI * 0.3, "Linear") < motion_curve(PI * 0.3, "Smooth"));
+    }
+
+    /// Helper to build a strip with the given pixel data and output address
+    fn test_strip(universe: u16, start_channel: u16, data: Vec<[u8; 3]>) -> PixelStrip {
+        PixelStrip {
+            id: rand::random(),
+            universe,
+            start_channel,
+            pixel_count: data.len(),
+            data,
+            ..PixelStrip::default()
+        }
+    }
+
+    #[test]
+    fn test_pack_mono_strip_sends_luma_per_pixel() {
+        let mut state = AppState::default();
+        let mut mono = test_strip(1, 1, vec![[255, 255, 255], [255, 0, 0], [0, 0, 0]]);
+        mono.channels_per_pixel = 1;
+        state.strips.push(mono);
+        // The next strip starts right after the mono strip's three channels
+        state.strips.push(test_strip(1, 1 + state.strips[0].channel_span() as u16, vec![[1, 2, 3]]));
+
+        let packed = pack_universes(&state);
+        assert_eq!(&packed[&1][..6], &[255, 54, 0, 1, 2, 3]);
+
+        state.strips[0].luma_weights = [1.0, 1.0, 0.0];
+        assert_eq!(pack_universes(&state)[&1][1], 128);
+    }
+
+    #[test]
+    fn test_protocol_framing_of_packed_universe() {
+        let mut state = AppState::default();
+        state.strips.push(test_strip(1, 1, vec![[9, 8, 7]]));
+        let packed = pack_universes(&state);
+        let dmx = &packed[&1];
+
+        let sacn = frame_for_protocol("sacn", dmx);
+        assert_eq!(sacn.len(), 513);
+        assert_eq!(&sacn[..4], &[0, 9, 8, 7]);
+
+        let artnet = frame_for_protocol("artnet", dmx);
+        assert_eq!(artnet.len(), 512);
+        assert_eq!(&artnet[..3], &[9, 8, 7]);
+        assert_eq!(frame_for_protocol("artnet", &[1, 2, 3]), vec![1, 2, 3, 0]);
+    }
+
+    #[test]
+    fn test_auto_pack_removes_overlaps_and_spills() {
+        let mut state = AppState::default();
+        state.strips.push(test_strip(1, 1, vec![[1, 1, 1]; 100]));
+        let mut overlapping = test_strip(1, 50, vec![[2, 2, 2]; 60]);
+        overlapping.channel_prefix = vec![255, 255];
+        state.strips.push(overlapping);
+        state.strips.push(test_strip(3, 1, vec![[3, 3, 3]; 20]));
+        assert_eq!(crate::model::strip_channel_overlaps(&state.strips).len(), 1);
+
+        let patch = crate::model::auto_pack_strips(&state.strips);
+        let placed: Vec<(u16, u16)> = patch.iter().map(|&(_, u, ch)| (u, ch)).collect();
+        assert_eq!(placed, vec![(1, 1), (1, 301), (2, 1)]);
+        for (strip, &(_, universe, start)) in state.strips.iter_mut().zip(&patch) {
+            strip.universe = universe;
+            strip.start_channel = start;
+        }
+        assert!(crate::model::strip_channel_overlaps(&state.strips).is_empty());
+
+        // Packing writes every strip where the patch says, back to back
+        let packed = pack_universes(&state);
+        assert_eq!(packed[&1][299], 1);
+        assert_eq!(&packed[&1][300..303], &[255, 255, 2]);
+        assert_eq!(packed[&1][481], 2);
+        assert_eq!(packed[&1][482], 0);
+        assert_eq!(&packed[&2][..3], &[3, 3, 3]);
+    }
+
+    #[test]
+    fn test_temporary_masks_fade_and_expire() {
+        let mut scenes: Vec<Scene> = serde_json::from_value(serde_json::json!([{
+            "id": 1, "name": "Accents", "kind": "Masks", "global": null,
+            "masks": [
+                { "id": 10, "mask_type": "burst", "x": 0.5, "y": 0.5, "params": {} },
+                { "id": 11, "mask_type": "burst", "x": 0.5, "y": 0.5, "params": { "lifetime": 2.0, "spawn_time": 10.0 } },
+                { "id": 12, "mask_type": "burst", "x": 0.5, "y": 0.5, "params": { "lifetime": 4.0, "lifetime_unit": "beats", "spawn_time": 8.0 } },
+                { "id": 13, "mask_type": "burst", "x": 0.5, "y": 0.5, "params": { "lifetime": 1.0 } }
+            ]
+        }])).unwrap();
+        let life = |scenes: &[Scene], id: u64, time: f64, beat: f64| {
+            scenes[0].masks.iter().find(|m| m.id == id).unwrap().life_remaining(time, beat)
+        };
+        // Permanent masks and templates (no spawn time) never fade
+        assert_eq!(life(&scenes, 10, 11.0, 9.0), None);
+        assert_eq!(life(&scenes, 13, 11.0, 9.0), None);
+        assert_eq!(life(&scenes, 11, 11.0, 9.0), Some(0.5));
+        assert_eq!(life(&scenes, 12, 11.0, 9.0), Some(0.75));
+
+        assert!(expire_masks(&mut scenes, 11.0, 9.0).is_empty());
+        assert_eq!(expire_masks(&mut scenes, 12.0, 9.0), vec![11]);
+        assert_eq!(expire_masks(&mut scenes, 12.0, 12.0), vec![12]);
+        assert_eq!(scenes[0].masks.iter().map(|m| m.id).collect::<Vec<_>>(), vec![10, 13]);
+
+        // A clock that restarted behind the spawn time restarts the countdown
+        scenes[0].masks[1].params.insert("spawn_time".into(), 50.0.into());
+        assert!(expire_masks(&mut scenes, 1.0, 0.0).is_empty());
+        assert_eq!(life(&scenes, 13, 1.5, 0.0), Some(0.5));
+
+        let mut strips = vec![test_strip(1, 1, vec![[200, 100, 0]])];
+        fade_from(&mut strips, &[vec![[0, 100, 50]]], 0.5);
+        assert_eq!(strips[0].data[0], [100, 100, 25]);
+    }
+
+    #[test]
+    fn test_dimmer_curves_shape_packed_levels() {
+        assert!(dimmer_curve_lut("linear").is_none());
+        assert!(dimmer_curve_lut("unknown").is_none());
+        for (name, _) in crate::model::DIMMER_CURVES.iter().skip(1) {
+            let table = dimmer_curve_lut(name).unwrap();
+            // Every curve keeps the ends fixed and never dims as the input rises
+            assert_eq!((table[0], table[255]), (0, 255));
+            assert!(table.windows(2).all(|w| w[0] <= w[1]));
+        }
+        let mid = |curve| dimmer_curve_lut(curve).unwrap()[128];
+        assert!(mid("gamma") < 64);
+        assert_eq!(mid("s_curve"), 128);
+        assert!(mid("log") > 200);
+
+        let mut state = AppState::default();
+        let mut strip = test_strip(1, 1, vec![[128, 0, 255]]);
+        strip.dimmer_curve = "gamma".into();
+        state.strips.push(strip);
+        let packed = pack_universes(&state);
+        assert_eq!(&packed[&1][..3], &[dimmer_curve_lut("gamma").unwrap()[128], 0, 255]);
+    }
+
+    #[test]
+    fn test_pack_writes_channel_prefix_before_pixels() {
+        let mut state = AppState::default();
+        let mut strip = test_strip(1, 10, vec![[1, 2, 3]]);
+        strip.channel_prefix = vec![255, 7];
+        state.strips.push(strip);
+
+        let packed = pack_universes(&state);
+        assert_eq!(&packed[&1][9..14], &[255, 7, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_pack_places_pixels_at_start_channel() {
+        let mut state = AppState::default();
+        state.strips.push(test_strip(2, 4, vec![[1, 2, 3], [4, 5, 6]]));
+
+        let packed = pack_universes(&state);
+        assert_eq!(packed.len(), 1);
+        let dmx = &packed[&2];
+        assert_eq!(dmx.len(), 512);
+        assert_eq!(&dmx[0..3], &[0, 0, 0]);
+        assert_eq!(&dmx[3..9], &[1, 2, 3, 4, 5, 6]);
+        assert!(dmx[9..].iter().all(|&v| v == 0));
+    }
+
+    #[test]
+    fn test_pack_reorders_color_channels() {
+        let mut state = AppState::default();
+        for (universe, order) in [(1, "RGB"), (2, "GRB"), (3, "BGR")] {
+            let mut strip = test_strip(universe, 1, vec![[10, 20, 30]]);
+            strip.color_order = order.into();
+            state.strips.push(strip);
+        }
+
+        let packed = pack_universes(&state);
+        assert_eq!(&packed[&1][0..3], &[10, 20, 30]);
+        assert_eq!(&packed[&2][0..3], &[20, 10, 30]);
+        assert_eq!(&packed[&3][0..3], &[30, 20, 10]);
+    }
+
+    #[test]
+    fn test_pack_applies_global_universe_offset_and_reversal() {
+        let mut state = AppState::default();
+        state.network.universe = 10;
+        for universe in 1..=3 {
+            state.strips.push(test_strip(universe, 1, vec![[universe as u8, 0, 0]]));
+        }
+
+        let packed = pack_universes(&state);
+        let mut universes: Vec<u16> = packed.keys().copied().collect();
+        universes.sort();
+        assert_eq!(universes, vec![10, 11, 12]);
+        assert_eq!(packed[&10][0], 1);
+
+        // Reversed: the highest strip universe goes out first
+        state.network.reverse_universe_order = true;
+        let packed = pack_universes(&state);
+        assert_eq!(packed[&10][0], 3);
+        assert_eq!(packed[&12][0], 1);
+
+        // Absolute: strip universes are sent as entered, ignoring the global universe
+        state.network.reverse_universe_order = false;
+        state.network.universe_mode = "absolute".into();
+        let packed = pack_universes(&state);
+        let mut universes: Vec<u16> = packed.keys().copied().collect();
+        universes.sort();
+        assert_eq!(universes, vec![1, 2, 3]);
+        assert_eq!(state.network.effective_universe(2, state.strip_universe_range()), 2);
+    }
+
+    #[test]
+    fn test_muted_universe_sends_one_black_frame_then_nothing() {
+        let mut blacked = std::collections::HashSet::new();
+        let frame = || HashMap::from([(1u16, vec![9u8; 512]), (2u16, vec![9u8; 512])]);
+
+        let mut data = frame();
+        mute_universes(&mut data, &[2], &mut blacked);
+        assert_eq!(data[&1], vec![9u8; 512]);
+        assert_eq!(data[&2], vec![0u8; 512]);
+
+        let mut data = frame();
+        mute_universes(&mut data, &[2], &mut blacked);
+        assert!(!data.contains_key(&2));
+
+        // Unmuting resumes output and re-arms the black frame for next time
+        let mut data = frame();
+        mute_universes(&mut data, &[], &mut blacked);
+        assert_eq!(data[&2], vec![9u8; 512]);
+        assert!(blacked.is_empty());
+    }
+
+    #[test]
+    fn test_pack_shares_universe_and_writes_fixtures() {
+        let mut state = AppState::default();
+        state.strips.push(test_strip(1, 1, vec![[255, 0, 0]]));
+        // Overflowing pixels are dropped instead of spilling into the next universe
+        state.strips.push(test_strip(1, 510, vec![[1, 1, 1], [2, 2, 2]]));
+        state.fixtures.push(Fixture {
+            id: 1,
+            name: "Par".into(),
+            universe: 1,
+            start_channel: 100,
+            channels: vec!["dimmer".into(), "red".into(), "green".into(), "blue".into()],
+            x: 0.5,
+            y: 0.5,
+            color: [0, 128, 255],
+        });
+
+        let packed = pack_universes(&state);
+        assert_eq!(packed.len(), 1);
+        let dmx = &packed[&1];
+        assert_eq!(&dmx[0..3], &[255, 0, 0]);
+        assert_eq!(&dmx[99..103], &[255, 0, 128, 255]);
+        assert_eq!(&dmx[509..512], &[1, 1, 1]);
+    }
+
+    #[test]
+    fn test_composite_pixel_layers_by_coverage() {
+        let base = [0, 0, 200];
+        // Fully lit mask replaces the base, unlit mask leaves it alone
+        assert_eq!(composite_pixel(base, [255, 0, 0], false), [255, 0, 0]);
+        assert_eq!(composite_pixel(base, [0, 0, 0], false), base);
+        // Half-covered mask edge lets half the base through
+        assert_eq!(composite_pixel(base, [128, 0, 0], false), [128, 0, 100]);
+        // Base over masks: masks only show where the base is dark
+        assert_eq!(composite_pixel([255, 255, 255], [255, 0, 0], true), [255, 255, 255]);
+        assert_eq!(composite_pixel([0, 0, 0], [255, 0, 0], true), [255, 0, 0]);
+    }
+
+    #[test]
+    fn test_sync_tuning_is_clamped() {
+        let audio = AudioConfig {
+            flywheel_jump_beats: 100.0,
+            flywheel_recovery_secs: -1.0,
+            flywheel_nudge: f32::NAN,
+            ..AudioConfig::default()
+        };
+        let tuning = clamped_sync_tuning(&audio);
+        assert_eq!(tuning.flywheel_jump_beats, *FLYWHEEL_JUMP_BEATS_RANGE.end());
+        assert_eq!(tuning.flywheel_recovery_secs, *FLYWHEEL_RECOVERY_SECS_RANGE.start());
+        assert_eq!(tuning.flywheel_nudge, *FLYWHEEL_NUDGE_RANGE.start());
+        assert_eq!(tuning.phase_correction, AudioConfig::default().phase_correction);
+    }
+
+    #[test]
+    fn test_beat_in_bar_follows_the_meter() {
+        assert_eq!(beat_in_bar(0.0, 4), 1);
+        assert_eq!(beat_in_bar(3.99, 4), 4);
+        assert_eq!(beat_in_bar(4.0, 4), 1);
+        // 3/4 wraps after three beats; 6/8 counted in eighths after six
+        assert_eq!(beat_in_bar(2.5, 3), 3);
+        assert_eq!(beat_in_bar(3.0, 3), 1);
+        assert_eq!(beat_in_bar(11.0, 6), 6);
+        assert_eq!(beat_in_bar(-0.5, 3), 3);
+        // A zero meter from bad data counts every beat as the downbeat
+        assert_eq!(beat_in_bar(5.0, 0), 1);
+        let tuning = clamped_sync_tuning(&AudioConfig { beats_per_bar: 0, ..AudioConfig::default() });
+        assert_eq!(tuning.beats_per_bar, 1);
+    }
+
+    #[test]
+    fn test_bpm_latch_waits_for_a_steady_tempo() {
+        let mut latch = BpmLatch::default();
+        // No estimate, then a tempo that keeps moving
+        assert_eq!(latch.update(0.0, 10.0), None);
+        for bpm in [100.0, 110.0, 120.0] {
+            assert_eq!(latch.update(bpm, 5.0), None);
+        }
+        // Small wobble within tolerance still counts as steady
+        assert_eq!(latch.update(120.4, 5.0), None);
+        assert_eq!(latch.update(119.8, 3.0), Some(120.0));
+        // Latched once; the same tempo doesn't latch again
+        assert_eq!(latch.update(120.0, 20.0), None);
+        // A new steady tempo does
+        assert_eq!(latch.update(128.0, 1.0), None);
+        assert_eq!(latch.update(128.0, 8.0), Some(128.0));
+    }
+
+    #[test]
+    fn test_corrected_color_order_from_observed_colors() {
+        // Configured RGB, but red shows green and green shows red: the strip is GRB
+        assert_eq!(crate::model::corrected_color_order("RGB", [1, 0, 2]).as_deref(), Some("GRB"));
+        // Configured GRB on an RGB strip swaps the same pair back
+        assert_eq!(crate::model::corrected_color_order("GRB", [1, 0, 2]).as_deref(), Some("RGB"));
+        assert_eq!(crate::model::corrected_color_order("BRG", [0, 1, 2]).as_deref(), Some("BRG"));
+        // Two sends looking the same is a wiring fault, not an order to fix
+        assert_eq!(crate::model::corrected_color_order("RGB", [0, 0, 2]), None);
+        assert_eq!(crate::model::color_order_indices("GBR"), [1, 2, 0]);
+    }
+
+    #[test]
+    fn test_pattern_gradient_and_color_order() {
+        let mut strips = vec![PixelStrip {
+            pixel_count: 10,
+            data: vec![[9, 9, 9]; 10],
+            ..Default::default()
+        }];
+        render_test_pattern("gradient", &mut strips, 0.0);
+        assert_eq!(strips[0].data[0], [0, 255, 0]);
+        assert_eq!(strips[0].data[9], [255, 0, 0]);
+        assert!(strips[0].data[2][0] < strips[0].data[8][0]);
+
+        render_test_pattern("color_order", &mut strips, 0.0);
+        assert!(strips[0].data.iter().all(|px| *px == [255, 0, 0]));
+        render_test_pattern("color_order", &mut strips, COLOR_ORDER_HOLD + 0.1);
+        assert!(strips[0].data.iter().all(|px| *px == [0, 255, 0]));
+        render_test_pattern("color_order", &mut strips, 2.0 * COLOR_ORDER_HOLD + 0.1);
+        assert!(strips[0].data.iter().all(|px| *px == [0, 0, 255]));
+    }
+
+    #[test]
+    fn test_flywheel_off_follows_raw_link_beat() {
+        // Flywheel off with Link leading: the raw beat, not the smoothed one
+        assert_eq!(base_beat(false, true, 10.0, 10.37), 10.37);
+        // Flywheel on, or Link not leading: the flywheel beat
+        assert_eq!(base_beat(true, true, 10.0, 10.37), 10.0);
+        assert_eq!(base_beat(false, false, 10.0, 10.37), 10.0);
+    }
+
+    #[test]
+    fn test_pixel_jitter_is_seeded_and_stable() {
+        let offsets: Vec<f32> = (0..64).map(|i| pixel_jitter(7, 42, i)).collect();
+        assert!(offsets.iter().all(|o| (-0.5..0.5).contains(o)));
+        // Same seed, strip and index: same offset every frame
+        assert_eq!(offsets, (0..64).map(|i| pixel_jitter(7, 42, i)).collect::<Vec<_>>());
+        // Neighbouring pixels differ, and a new seed gives a new pattern
+        assert!(offsets.windows(2).any(|w| (w[0] - w[1]).abs() > 0.1));
+        assert_ne!(offsets, (0..64).map(|i| pixel_jitter(8, 42, i)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_heartbeat_status_prefers_errors() {
+        assert_eq!(heartbeat_status(false, false, true), "ok");
+        assert_eq!(heartbeat_status(false, false, false), "idle");
+        assert_eq!(heartbeat_status(false, true, true), "error");
+        assert_eq!(heartbeat_status(true, false, true), "error");
+    }
+
+    #[test]
+    fn test_audio_bands_drive_separate_targets() {
+        let mut mask = Mask {
+            id: 1,
+            mask_type: "scanner".into(),
+            x: 0.5,
+            y: 0.5,
+            params: std::collections::HashMap::new(),
+            anchor_strip: None,
+            target_strips: Vec::new(),
+        };
+        mask.params.insert("width".into(), 0.4.into());
+        mask.params.insert("color".into(), serde_json::json!([200, 100, 0]));
+        // Unmapped masks are left alone
+        assert!(audio_modulated_mask(&mask, |_| 1.0).is_none());
+
+        mask.params.insert("audio_size_band".into(), "bass".into());
+        mask.params.insert("audio_intensity_band".into(), "treble".into());
+        mask.params.insert("audio_gain".into(), 1.0.into());
+        let levels = |band: &str| if band == "bass" { 1.0 } else { 0.5 };
+        let m = audio_modulated_mask(&mask, levels).unwrap();
+        assert_eq!(m.params["width"].as_f64(), Some(0.8)); // Bass at full: double size
+        assert_eq!(m.params["color"], serde_json::json!([100, 50, 0])); // Treble at half: half brightness
+        assert_eq!(m.y, 0.5); // Position not mapped
+    }
+
+    #[test]
+    fn test_blend_mask_pixel_modes() {
+        let under = [200, 40, 0];
+        let over = [100, 100, 0];
+        assert_eq!(blend_mask_pixel(under, over, "add"), [255, 140, 0]);
+        assert_eq!(blend_mask_pixel(under, over, "max"), [200, 100, 0]);
+        assert_eq!(blend_mask_pixel(under, over, "ltp"), [100, 100, 0]);
+        // Unknown policies fall back to additive so older configs render as before
+        assert_eq!(blend_mask_pixel(under, over, "bogus"), [255, 140, 0]);
+    }
+
+    #[test]
+    fn test_tick_clock_tracks_wall_clock() {
+        let tick = 1.0 / 120.0;
+        let mut clock = TickClock::default();
+        let mut wall = 0.0;
+        let mut advanced = 0.0;
+        for i in 0..10_000 {
+            // Jittery frame pacing between 2 ms and 40 ms
+            let real_dt = 0.002 + (i * 7919 % 39) as f64 * 0.001;
+            wall += real_dt;
+            let step = clock.advance(real_dt, tick);
+            assert!((step / tick - (step / tick).round()).abs() < 1e-6, "advances in whole ticks");
+            advanced += step;
+        }
+        assert!((clock.time - advanced).abs() < 1e-9);
+        assert!(wall - clock.time >= -1e-9 && wall - clock.time < tick);
+    }
+
+    #[test]
+    fn test_beat_source_follows_priority() {
+        let default_order = AudioConfig::default().beat_source_order();
+        assert_eq!(select_beat_source(&default_order, 2, 128.0), "link");
+        assert_eq!(select_beat_source(&default_order, 0, 128.0), "audio");
+        assert_eq!(select_beat_source(&default_order, 0, 0.0), "manual");
+
+        let audio_first = AudioConfig { source_priority: vec!["audio".into()], ..AudioConfig::default() }.beat_source_order();
+        assert_eq!(audio_first, vec!["audio", "link", "manual"]);
+        assert_eq!(select_beat_source(&audio_first, 2, 128.0), "audio");
+        assert_eq!(select_beat_source(&audio_first, 2, 0.0), "link");
+
+        let manual_first = vec!["manual".to_string(), "link".to_string()];
+        assert_eq!(select_beat_source(&manual_first, 2, 128.0), "manual");
+    }
+
+    #[test]
+    fn test_strip_stagger_by_index_or_position() {
+        let mut params = HashMap::new();
+        assert_eq!(strip_stagger(&params), None);
+        params.insert("stagger".to_string(), serde_json::json!(0.25));
+        assert_eq!(strip_stagger(&params), Some((0.25, false)));
+        params.insert("stagger_by".to_string(), serde_json::json!("position"));
+        assert_eq!(strip_stagger(&params), Some((0.25, true)));
+
+        let mut strip = test_strip(1, 1, vec![[0, 0, 0]]);
+        strip.y = 0.5;
+        assert_eq!(strip_phase_offset(3, &strip, 0.25, false), 0.75);
+        assert_eq!(strip_phase_offset(3, &strip, 0.25, true), 0.125);
+    }
+
+    #[test]
+    fn test_comet_intensity_fades_behind_head() {
+        assert_eq!(comet_intensity(0.0, 0.0, 0.5, 0.1), 1.0);
+        assert!(comet_intensity(0.25, 0.0, 0.5, 0.1) < 1.0);
+        assert!(comet_intensity(0.25, 0.05, 0.5, 0.1) < comet_intensity(0.25, 0.0, 0.5, 0.1));
+        // Nothing ahead of the head, past the trail, or outside the width
+        assert_eq!(comet_intensity(-0.01, 0.0, 0.5, 0.1), 0.0);
+        assert_eq!(comet_intensity(0.6, 0.0, 0.5, 0.1), 0.0);
+        assert_eq!(comet_intensity(0.1, 0.2, 0.5, 0.1), 0.0);
+    }
+
+    #[test]
+    fn test_structured_sparkle_pattern() {
+        let lit: Vec<usize> = (0..10).filter(|i| structured_sparkle_lit(*i, 4, 0)).collect();
+        assert_eq!(lit, vec![0, 4, 8]);
+        // Moving pattern advances one pixel per cycle and wraps
+        let lit: Vec<usize> = (0..10).filter(|i| structured_sparkle_lit(*i, 4, 5)).collect();
+        assert_eq!(lit, vec![1, 5, 9]);
+    }
+
+    #[test]
+    fn test_gradient_interpolation_spaces() {
+        let red = [255, 0, 0];
+        let cyan = [0, 255, 255];
+
+        // Linear RGB passes through gray halfway between complementary colors
+        assert_eq!(interpolate_color(red, cyan, 0.5, "rgb"), [127, 127, 127]);
+
+        // HSV keeps full saturation and value along the way
+        let mid = interpolate_color(red, cyan, 0.5, "hsv");
+        let (_, s, v) = rgb_to_hsv(mid);
+        assert!(s > 0.99 && v > 0.99, "hsv midpoint {:?} lost saturation", mid);
+
+        // Every space hits the stops exactly
+        for space in ["rgb", "hsv", "oklab"] {
+            assert_eq!(interpolate_color(red, cyan, 0.0, space), red, "{} start", space);
+            assert_eq!(interpolate_color(red, cyan, 1.0, space), cyan, "{} end", space);
+        }
+    }
+
+    #[test]
+    fn test_flash_rate_is_limited_on_the_beat_grid() {
+        // 1/8 notes at 120 BPM flash 4 times a second
+        assert_eq!(limit_flash_divisor(0.5, 120.0, 3.0), 1.0);
+        assert_eq!(limit_flash_divisor(0.5, 120.0, 5.0), 0.5);
+        // 1/8 at 180 BPM = 6 Hz, needs two doublings to get under 2 Hz
+        assert_eq!(limit_flash_divisor(0.5, 180.0, 2.0), 2.0);
+        // Slow flashes are left alone
+        assert_eq!(limit_flash_divisor(4.0, 128.0, 3.0), 4.0);
+    }
+
+    #[test]
+    fn test_audio_trigger_respects_threshold_and_cooldown() {
+        let mut trigger = AudioTrigger::default();
+        assert!(!trigger.update(0.4, 0.1, 0.6, 8.0));
+        assert!(trigger.update(0.9, 0.1, 0.6, 8.0));
+        // The same drop keeps ringing, but the cooldown holds it to one trigger
+        assert!(!trigger.update(0.9, 0.1, 0.6, 8.0));
+        assert!(!trigger.update(0.9, 7.0, 0.6, 8.0));
+        assert!(trigger.update(0.9, 1.0, 0.6, 8.0));
+    }
+
+    #[test]
+    fn test_audio_color_follows_centroid_and_level() {
+        let mut mask = Mask {
+            id: 1,
+            mask_type: "radial".into(),
+            x: 0.5,
+            y: 0.5,
+            params: HashMap::new(),
+            anchor_strip: None,
+            target_strips: Vec::new(),
+        };
+        mask.params.insert("color_mode".into(), "audio".into());
+        // Defaults: hue from the centroid, brightness from volume (scaled by the default gain of 4)
+        let bassy = audio_color(&mask, |source| if source == "centroid" { 0.0 } else { 0.25 });
+        assert_eq!(bassy, [255, 0, 0]);
+        let quiet = audio_color(&mask, |source| if source == "centroid" { 0.0 } else { 0.05 });
+        assert!(quiet[0] < 60);
+
+        mask.params.insert("audio_hue_source".into(), "treble".into());
+        mask.params.insert("audio_value_source".into(), "off".into());
+        let (h, _, v) = rgb_to_hsv(audio_color(&mask, |source| if source == "treble" { 0.125 } else { 0.0 }));
+        assert!((h - 0.4).abs() < 0.01 && v == 1.0);
+    }
+
+    #[test]
+    fn test_clear_strips_follows_pixel_count() {
+        let mut strips = vec![test_strip(1, 1, vec![[9, 9, 9]; 5]), test_strip(1, 16, vec![[9, 9, 9]; 2])];
+        strips[0].pixel_count = 3;
+        strips[1].pixel_count = 4;
+        let capacity = strips[0].data.capacity();
+        clear_strips(&mut strips);
+        assert_eq!(strips[0].data, vec![[0, 0, 0]; 3]);
+        assert_eq!(strips[0].data.capacity(), capacity);
+        assert_eq!(strips[1].data, vec![[0, 0, 0]; 4]);
+    }
+
+    #[test]
+    fn test_freeze_restores_captured_frame() {
+        let strips = vec![test_strip(1, 1, vec![[10, 20, 30]; 2]), test_strip(1, 7, vec![[5, 5, 5]])];
+        let fixture = Fixture { color: [200, 0, 0], ..Fixture::default() };
+        let held = capture_frame(&strips, std::slice::from_ref(&fixture));
+
+        // Next frame: cleared strips plus the fixture proxy, one strip resized meanwhile
+        let mut next = vec![test_strip(1, 1, vec![[0, 0, 0]; 2]), test_strip(1, 7, vec![[9, 9, 9]; 3]), fixture.proxy_strip()];
+        restore_frame(&mut next, &held);
+        assert_eq!(next[0].data, vec![[10, 20, 30]; 2]);
+        assert_eq!(next[1].data, vec![[0, 0, 0]; 3]);
+        assert_eq!(next[2].data, vec![[200, 0, 0]]);
+    }
+
+    #[test]
+    fn test_image_mask_samples_by_world_position() {
+        let image = serde_json::to_value(crate::model::MaskImage {
+            width: 2,
+            height: 1,
+            pixels: vec![[255, 0, 0], [0, 0, 255]],
+        }).unwrap();
+        // Left half of a 1 x 0.5 image is red, right half blue; outside is untouched
+        let left = image_mask_uv(-0.25, 0.0, 1.0, 0.5, 0.0).unwrap();
+        assert_eq!(sample_mask_image(&image, left.0, left.1), Some([255, 0, 0]));
+        let right = image_mask_uv(0.4, 0.2, 1.0, 0.5, 0.0).unwrap();
+        assert_eq!(sample_mask_image(&image, right.0, right.1), Some([0, 0, 255]));
+        assert!(image_mask_uv(0.0, 0.3, 1.0, 0.5, 0.0).is_none());
+        // Rotated a quarter turn, the image's right side points down
+        let below = image_mask_uv(0.0, 0.4, 1.0, 0.5, 90.0).unwrap();
+        assert_eq!(sample_mask_image(&image, below.0, below.1), Some([0, 0, 255]));
+        assert!(sample_mask_image(&serde_json::json!({"width": 0}), 0.5, 0.5).is_none());
+    }
 }
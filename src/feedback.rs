@@ -0,0 +1,178 @@
+//! State feedback for control surfaces: the live scene, master levels and tempo sent back out
+//! over OSC (UDP) and as MIDI CCs so motorized faders and LED rings follow the app.
+//!
+//! Only values that changed since they were last sent go out. OSC addresses:
+//!
+//! - `/lightspeed/scene` (string): live scene name, empty when none is selected
+//! - `/lightspeed/scene/index` (int): live scene's position in the list, -1 when none
+//! - `/lightspeed/master` (float 0..1): master brightness
+//! - `/lightspeed/speed` (float): master speed multiplier
+//! - `/lightspeed/submaster` (float 0..1): live scene submaster
+//! - `/lightspeed/bpm` (float): tempo, to 0.1 BPM
+//!
+//! MIDI feedback sends each mapped fader CC (see `AppState::cc_mappings`) its control's value.
+
+use crate::model::AppState;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+/// One OSC argument
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    Str(String),
+}
+
+/// Encode an OSC 1.0 message: address and type tags as padded strings, then big-endian arguments
+pub fn osc_message(address: &str, args: &[OscArg]) -> Vec<u8> {
+    fn push_padded(buf: &mut Vec<u8>, s: &str) {
+        buf.extend_from_slice(s.as_bytes());
+        // At least one null terminator, then pad to a multiple of 4
+        buf.resize(buf.len() + 4 - s.len() % 4, 0);
+    }
+    let mut buf = Vec::new();
+    push_padded(&mut buf, address);
+    let tags: String = std::iter::once(',')
+        .chain(args.iter().map(|arg| match arg {
+            OscArg::Int(_) => 'i',
+            OscArg::Float(_) => 'f',
+            OscArg::Str(_) => 's',
+        }))
+        .collect();
+    push_padded(&mut buf, &tags);
+    for arg in args {
+        match arg {
+            OscArg::Int(v) => buf.extend_from_slice(&v.to_be_bytes()),
+            OscArg::Float(v) => buf.extend_from_slice(&v.to_be_bytes()),
+            OscArg::Str(s) => push_padded(&mut buf, s),
+        }
+    }
+    buf
+}
+
+/// Current OSC feedback values for `state` at `bpm`, one argument per address
+pub fn osc_values(state: &AppState, bpm: f64) -> Vec<(&'static str, OscArg)> {
+    let live = state.selected_scene_id
+        .and_then(|id| state.scenes.iter().position(|s| s.id == id));
+    let scene = live.map(|i| &state.scenes[i]);
+    vec![
+        ("/lightspeed/scene", OscArg::Str(scene.map(|s| s.name.clone()).unwrap_or_default())),
+        ("/lightspeed/scene/index", OscArg::Int(live.map_or(-1, |i| i as i32))),
+        ("/lightspeed/master", OscArg::Float(state.master_brightness)),
+        ("/lightspeed/speed", OscArg::Float(state.master_speed)),
+        ("/lightspeed/submaster", OscArg::Float(scene.map_or(0.0, |s| s.master_intensity))),
+        ("/lightspeed/bpm", OscArg::Float((bpm * 10.0).round() as f32 / 10.0)),
+    ]
+}
+
+/// What was last sent on each OSC address and CC, so only changes go out
+#[derive(Default)]
+pub struct FeedbackDiff {
+    osc: HashMap<&'static str, OscArg>,
+    cc: HashMap<u8, u8>,
+}
+
+impl FeedbackDiff {
+    /// The OSC values that differ from what was last sent, now recorded as sent
+    pub fn changed_osc(&mut self, values: Vec<(&'static str, OscArg)>) -> Vec<(&'static str, OscArg)> {
+        values.into_iter()
+            .filter(|(address, arg)| self.osc.insert(address, arg.clone()).as_ref() != Some(arg))
+            .collect()
+    }
+
+    /// The (CC, value) pairs that differ from what was last sent, now recorded as sent
+    pub fn changed_cc(&mut self, values: Vec<(u8, u8)>) -> Vec<(u8, u8)> {
+        values.into_iter()
+            .filter(|(cc, value)| self.cc.insert(*cc, *value) != Some(*value))
+            .collect()
+    }
+
+    /// Forget the sent CCs so they all go out again, e.g. after the MIDI device reconnects
+    pub fn reset_cc(&mut self) {
+        self.cc.clear();
+    }
+
+    /// Forget the sent OSC values so they all go out again, e.g. to a new target
+    pub fn reset_osc(&mut self) {
+        self.osc.clear();
+    }
+}
+
+/// Sends OSC messages over UDP to one `host:port`
+pub struct OscSender {
+    socket: UdpSocket,
+    target: SocketAddr,
+}
+
+impl OscSender {
+    pub fn new(target: &str) -> Result<Self> {
+        let target = target.to_socket_addrs()
+            .with_context(|| format!("Invalid OSC target '{}'", target))?
+            .next()
+            .with_context(|| format!("OSC target '{}' didn't resolve", target))?;
+        let socket = UdpSocket::bind(("0.0.0.0", 0)).context("Failed to open OSC socket")?;
+        Ok(Self { socket, target })
+    }
+
+    pub fn send(&self, address: &str, arg: &OscArg) -> Result<()> {
+        self.socket.send_to(&osc_message(address, std::slice::from_ref(arg)), self.target)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_osc_message_encoding() {
+        let msg = osc_message("/a", &[OscArg::Int(1), OscArg::Float(0.5), OscArg::Str("hi".into())]);
+        let mut expected = b"/a\0\0,ifs\0\0\0\0".to_vec();
+        expected.extend_from_slice(&[0, 0, 0, 1]);
+        expected.extend_from_slice(&0.5f32.to_be_bytes());
+        expected.extend_from_slice(b"hi\0\0");
+        assert_eq!(msg, expected);
+        // A four-byte string still gets a terminator, padded to the next word
+        assert_eq!(osc_message("/abc", &[]), b"/abc\0\0\0\0,\0\0\0".to_vec());
+    }
+
+    #[test]
+    fn test_feedback_sends_only_changes() {
+        let mut state = AppState::default();
+        let mut diff = FeedbackDiff::default();
+        assert_eq!(diff.changed_osc(osc_values(&state, 120.04)).len(), 6);
+        assert!(diff.changed_osc(osc_values(&state, 120.01)).is_empty());
+
+        state.master_brightness = 0.5;
+        assert_eq!(diff.changed_osc(osc_values(&state, 120.0)), vec![("/lightspeed/master", OscArg::Float(0.5))]);
+
+        assert_eq!(diff.changed_cc(vec![(7, 64), (8, 0)]), vec![(7, 64), (8, 0)]);
+        assert_eq!(diff.changed_cc(vec![(7, 64), (8, 1)]), vec![(8, 1)]);
+        diff.reset_cc();
+        assert_eq!(diff.changed_cc(vec![(7, 64)]), vec![(7, 64)]);
+    }
+
+    #[test]
+    fn test_cc_feedback_reads_back_mapped_controls() {
+        let mut state = AppState::default();
+        state.set_cc("brightness", Some(7));
+        state.set_cc("speed", Some(8));
+        state.set_cc("submaster", Some(9)); // No live scene: nothing to send
+        state.apply_cc(7, 100);
+        state.apply_cc(8, 96);
+        assert_eq!(state.cc_feedback(), vec![(7, 100), (8, 96)]);
+    }
+
+    #[test]
+    fn test_shared_cc_sends_one_value() {
+        let mut state = AppState::default();
+        state.set_cc("brightness", Some(9));
+        state.set_cc("speed", Some(9));
+        state.master_brightness = 1.0; // 127, while speed 1x reads back as 64
+        let mut diff = FeedbackDiff::default();
+        assert_eq!(diff.changed_cc(state.cc_feedback()), vec![(9, 127)]);
+        assert!(diff.changed_cc(state.cc_feedback()).is_empty());
+    }
+}
@@ -0,0 +1,17 @@
+//! Lightspeed's lighting core, usable without the UI
+//!
+//! - [`model`]: scenes, strips, masks and the persisted `AppState`
+//! - [`engine`]: the render loop, tempo sync and sACN output
+//! - [`db`]: SQLite persistence and JSON import/export
+//! - [`scanner`]: scanner mask geometry
+//! - [`audio`]: input-level beat detection used by the engine
+//! - [`recording`]: recording output frames to a file and playing them back
+//! - [`feedback`]: sending the app's state back out to control surfaces over OSC and MIDI
+
+pub mod model;
+pub mod engine;
+pub mod db;
+pub mod scanner;
+pub mod audio;
+pub mod recording;
+pub mod feedback;
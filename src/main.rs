@@ -1,26 +1,39 @@
 #![cfg_attr(all(target_os = "windows", not(debug_assertions)), windows_subsystem = "windows")]
 
-mod model;
-mod engine;
-mod audio;
-mod scanner;
 mod midi;
-mod db;
+
+use lightspeed::{audio, db, engine, feedback, model};
 
 use eframe::egui;
-use model::{AppState, PixelStrip, Mask};
-use engine::LightingEngine;
-use db::Database;
+use model::{AppState, PixelStrip, Mask, Scene, StripPreset};
+use engine::{LightingEngine, interpolate_color, motion_curve};
+use db::{Database, ImportReport, ViewBookmark, WindowGeometry};
 use std::fs;
 use std::process::Command;
 use std::path::{Path, PathBuf};
 use std::sync::mpsc::{Sender, Receiver};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 struct ViewState {
     offset: egui::Vec2,
     scale: f32,
     drag_id: Option<u64>, 
     drag_type: DragType,
+    pixel_lines: bool, // Render strips as polylines instead of dots
+    fast_render: bool, // Aggressively thin out pixels when zoomed out, for very large rigs
+    measuring: bool, // Canvas clicks place measurement points instead of selecting
+    measure_points: Vec<(f32, f32)>, // Up to two canvas points; a third click starts over
+}
+
+/// What the stage preview window draws, refreshed from the editor every frame
+#[derive(Default)]
+struct StagePreview {
+    strips: Vec<PixelStrip>,
+    scale: f32,
+    offset: egui::Vec2, // Canvas pan as a fraction of the canvas size, so the framing matches at any window size
+    pixel_lines: bool,
+    fast_render: bool,
+    closed: bool, // The window was closed from its own title bar
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -51,6 +64,10 @@ impl Default for ViewState {
             scale: 1.0, 
             drag_id: None, 
             drag_type: DragType::None,
+            pixel_lines: false,
+            fast_render: false,
+            measuring: false,
+            measure_points: Vec::new(),
         }
     }
 }
@@ -68,10 +85,23 @@ fn main() -> eframe::Result<()> {
     // Load app icon
     let icon_data = load_icon();
 
+    // Restore the last window placement, ignoring anything that couldn't be a real window
+    let saved_geometry = Database::open(&user_db_path())
+        .ok()
+        .and_then(|db| db.load_window_geometry().ok().flatten())
+        .filter(window_geometry_is_sane);
+
     let mut viewport = egui::ViewportBuilder::default()
-        .with_inner_size([1200.0, 800.0])
+        .with_inner_size(saved_geometry.map_or([1200.0, 800.0], |g| g.inner_size))
         .with_drag_and_drop(true);
 
+    if let Some(geometry) = saved_geometry {
+        if let Some(pos) = geometry.position {
+            viewport = viewport.with_position(pos);
+        }
+        viewport = viewport.with_maximized(geometry.maximized);
+    }
+
     if let Some(icon) = icon_data {
         viewport = viewport.with_icon(icon);
     }
@@ -88,19 +118,47 @@ fn main() -> eframe::Result<()> {
     )
 }
 
+/// Reject stored geometry that is too small to use or far outside any plausible desktop
+fn window_geometry_is_sane(geometry: &WindowGeometry) -> bool {
+    let [w, h] = geometry.inner_size;
+    let size_ok = w.is_finite() && h.is_finite() && w >= 400.0 && h >= 300.0;
+    let pos_ok = match geometry.position {
+        Some([x, y]) => x.is_finite() && y.is_finite() && x.abs() < 16384.0 && y.abs() < 16384.0,
+        None => true,
+    };
+    size_ok && pos_ok
+}
+
 fn load_icon() -> Option<egui::IconData> {
-    // Try to load the generated icon PNG
-    let icon_bytes = include_bytes!("../generated_icon.png");
+    // A branded icon.png next to the config overrides the embedded one
+    let external = user_asset_path("icon.png");
+    if let Ok(bytes) = fs::read(&external) {
+        match decode_icon(&bytes) {
+            Some(icon) => {
+                log::info!("Using app icon from {:?}", external);
+                return Some(icon);
+            }
+            None => log::warn!("Ignoring invalid icon at {:?} (expected a square PNG, 16-1024 px)", external),
+        }
+    }
+
+    // Embedded icon is the guaranteed fallback
+    decode_icon(include_bytes!("../generated_icon.png"))
+}
 
-    // Decode the PNG
-    let image = image::load_from_memory(icon_bytes).ok()?;
+/// Decode a square PNG of a sane size into window icon data
+fn decode_icon(bytes: &[u8]) -> Option<egui::IconData> {
+    let image = image::load_from_memory_with_format(bytes, image::ImageFormat::Png).ok()?;
     let rgba = image.to_rgba8();
     let (width, height) = rgba.dimensions();
+    if width != height || !(16..=1024).contains(&width) {
+        return None;
+    }
 
     Some(egui::IconData {
         rgba: rgba.into_raw(),
-        width: width as u32,
-        height: height as u32,
+        width,
+        height,
     })
 }
 
@@ -113,7 +171,7 @@ struct MyApp {
     // Scenes UI state
     new_scene_open: bool,
     new_scene_name: String,
-    new_scene_kind: String, // "Masks" or "Global"
+    new_scene_kind: String, // "Masks", "Global" or "Blackout"
     new_scene_category: String,
     // Scene Manager
     scene_manager_open: bool,
@@ -129,6 +187,7 @@ struct MyApp {
     import_dialog_open: bool,
     import_merge_mode: bool,
     import_file_path: Option<PathBuf>,
+    import_preview: Option<Result<ImportReport, String>>,
     // MIDI
     midi_sender: Sender<midi::MidiCommand>,
     midi_receiver: Receiver<midi::MidiEvent>,
@@ -136,6 +195,56 @@ struct MyApp {
     last_midi_detection: Option<Instant>,
     // Scene Reordering
     dragged_scene_id: Option<u64>,
+    // Mask multi-selection for batch editing
+    selected_mask_ids: std::collections::HashSet<u64>,
+    // Waiting for a MIDI press to map to the Fire button
+    fire_midi_learn: bool,
+    // Waiting for a MIDI press to map to the panic (full white) button
+    panic_midi_learn: bool,
+    // Waiting for a MIDI press to map to the Freeze toggle
+    freeze_midi_learn: bool,
+    // Panic MIDI button currently held down
+    panic_midi_held: bool,
+    // Panic button in the header held down (as of the last frame)
+    panic_ui_held: bool,
+    // Waiting for a MIDI press to pick the heartbeat pad
+    heartbeat_midi_learn: bool,
+    // Heartbeat LED as last sent (button, is_cc, status), so it's only resent on change
+    heartbeat_sent: Option<(u8, bool, &'static str)>,
+    // State feedback to control surfaces: what was last sent, and the OSC socket for its target
+    feedback_sent: feedback::FeedbackDiff,
+    osc_feedback: Option<(String, Option<feedback::OscSender>)>,
+    stage_preview: Option<Arc<Mutex<StagePreview>>>, // Some while the stage preview window is open
+    stage_preview_fullscreen: bool,
+    // Guided color order check: (strip id, color seen for each of R, G, B sent so far)
+    color_check: Option<(u64, Vec<usize>)>,
+    // Auto-pack: the proposed patch being previewed, and the patch it replaced (for undo)
+    auto_pack_preview: Option<Vec<model::StripPatch>>,
+    auto_pack_undo: Option<Vec<model::StripPatch>>,
+    // Performance mode: the live scene the user chose to edit anyway (relocks on scene change)
+    performance_unlocked: Option<u64>,
+    // Mask editor "freeze phase": (mask id, cycle phase 0..1) shown instead of live motion
+    mask_phase_preview: Option<(u64, f32)>,
+    // Staged editing: scenes touched since the last Apply, as they were then
+    staged_scenes: std::collections::HashMap<u64, Scene>,
+    // Record/Playback: whether playback starts over at the end
+    playback_loop: bool,
+    // Strip editor's physical layout solver: which of "count" | "length" | "pitch" is computed
+    strip_layout_solve: String,
+    // Snapshots browser
+    snapshots_open: bool,
+    snapshots: Vec<(PathBuf, std::time::SystemTime)>, // Listed when the browser opens and after a save or delete
+    snapshot_name: String,
+    snapshot_delete_confirm: Option<PathBuf>,
+    scene_delete_confirm: Option<u64>, // Scene awaiting confirmation before it's deleted
+    // Window placement, saved shortly after the user stops moving/resizing
+    window_geometry: Option<WindowGeometry>,
+    window_geometry_changed: Option<Instant>,
+    // User-saved strip types, and the name field for saving a new one
+    strip_presets: Vec<StripPreset>,
+    strip_preset_name: String,
+    // Canvas pan/zoom bookmarks: Ctrl+1..9 saves, 1..9 recalls
+    view_bookmarks: Vec<ViewBookmark>,
 }
 
 impl Default for MyApp {
@@ -162,6 +271,8 @@ impl Default for MyApp {
                     x: 0.5,
                     y: 0.5,
                     params: std::collections::HashMap::new(),
+                    anchor_strip: None,
+                    target_strips: Vec::new(),
                 });
 
                 // Create a dummy database (will retry on next launch)
@@ -172,21 +283,10 @@ impl Default for MyApp {
         };
 
         // Load state from database
-        match db.load_state() {
+        let loaded_ok = match db.load_state() {
             Ok(loaded) => {
                 state = loaded;
-                // MIGRATION: Move deprecated `global` into `global_effects` if needed
-                for scene in &mut state.scenes {
-                    if scene.kind == "Global" && scene.global_effects.is_empty() && scene.global.is_some() {
-                         if let Some(old_global) = scene.global.take() {
-                             scene.global_effects.push(model::GlobalEffectConfig {
-                                 effect: old_global,
-                                 targets: None, // Apply to all
-                             });
-                             println!("Migrated scene '{}' global effect", scene.name);
-                         }
-                    }
-                }
+                true
             }
             Err(e) => {
                 eprintln!("Failed to load state from database: {}", e);
@@ -201,28 +301,67 @@ impl Default for MyApp {
                     x: 0.5,
                     y: 0.5,
                     params: std::collections::HashMap::new(),
+                    anchor_strip: None,
+                    target_strips: Vec::new(),
                 });
+                false
+            }
+        };
+
+        // First run: seed from a default_config.json shipped in the config directory, if any
+        if state.strips.is_empty() && state.scenes.is_empty() && state.fixtures.is_empty() {
+            let defaults_path = user_asset_path("default_config.json");
+            if let Ok(json) = fs::read_to_string(&defaults_path) {
+                match db.import_from_json(&json, false).and_then(|_| db.load_state()) {
+                    Ok(loaded) => {
+                        state = loaded;
+                        status = format!("Loaded default configuration from {:?}", defaults_path);
+                    }
+                    Err(e) => {
+                        eprintln!("Ignoring invalid default config at {:?}: {}", defaults_path, e);
+                    }
+                }
+            }
+        }
+
+        let strip_presets = db.load_strip_presets().unwrap_or_else(|e| {
+            eprintln!("Failed to load strip presets: {}", e);
+            Vec::new()
+        });
+        let view_bookmarks = db.load_view_bookmarks().unwrap_or_else(|e| {
+            eprintln!("Failed to load view bookmarks: {}", e);
+            Vec::new()
+        });
+
+        // Keep one sACN CID per install so receivers track us as the same source across restarts.
+        // Only the CID column is written: a full save after a failed load would wipe the show.
+        if state.network.ensure_source_cid() {
+            if let Err(e) = db.save_source_cid(&state.network.source_cid) {
+                eprintln!("Failed to save sACN CID: {}", e);
             }
         }
+
+        // Legacy top-level masks are only editable through a scene.
+        // Not saved after a failed load, where the seeded defaults would replace the show.
+        if state.migrate_global_masks() && loaded_ok {
+            if let Err(e) = db.save_state(&state) {
+                eprintln!("Failed to save migrated masks: {}", e);
+            }
+            if status == "Ready" {
+                status = "Moved global masks into scene 'Migrated Masks'".to_owned();
+            }
+        }
+        let duplicate_pads = state.duplicate_launchpad_scenes().len();
+        if duplicate_pads > 0 && status == "Ready" {
+            status = format!("{} scene(s) share a Launchpad button with another scene", duplicate_pads);
+        }
         
         // Init MIDI
         let (tx_event, rx_event) = std::sync::mpsc::channel();
         let tx_cmd = midi::start_midi_service(tx_event);
 
         // Send initial colors
-        let _ = tx_cmd.send(midi::MidiCommand::ClearAll);
-        // Small delay to ensure clear processes if needed, but channel order is preserved usually.
-        
-        for s in &state.scenes {
-            if let (Some(btn), Some(col)) = (s.launchpad_btn, s.launchpad_color) {
-                 let cmd = if s.launchpad_is_cc {
-                     midi::MidiCommand::SetButtonColor { cc: btn, color: col }
-                 } else {
-                     midi::MidiCommand::SetPadColor { note: btn, color: col }
-                 };
-                 let _ = tx_cmd.send(cmd);
-            }
-        }
+        send_launchpad_colors(&tx_cmd, &state);
 
         Self {
             state,
@@ -244,18 +383,57 @@ impl Default for MyApp {
             import_dialog_open: false,
             import_merge_mode: false,
             import_file_path: None,
+            import_preview: None,
             midi_sender: tx_cmd,
             midi_receiver: rx_event,
             midi_connected: false,
             last_midi_detection: None,
             dragged_scene_id: None,
+            selected_mask_ids: std::collections::HashSet::new(),
+            fire_midi_learn: false,
+            panic_midi_learn: false,
+            freeze_midi_learn: false,
+            panic_midi_held: false,
+            panic_ui_held: false,
+            heartbeat_midi_learn: false,
+            heartbeat_sent: None,
+            feedback_sent: feedback::FeedbackDiff::default(),
+            osc_feedback: None,
+            stage_preview: None,
+            stage_preview_fullscreen: true,
+            color_check: None,
+            playback_loop: true,
+            strip_layout_solve: "length".to_string(),
+            auto_pack_preview: None,
+            auto_pack_undo: None,
+            performance_unlocked: None,
+            mask_phase_preview: None,
+            staged_scenes: std::collections::HashMap::new(),
+            snapshots_open: false,
+            snapshots: Vec::new(),
+            snapshot_name: String::new(),
+            snapshot_delete_confirm: None,
+            scene_delete_confirm: None,
+            window_geometry: None,
+            window_geometry_changed: None,
+            strip_presets,
+            view_bookmarks,
+            strip_preset_name: String::new(),
         }
     }
 }
 
 impl MyApp {
     fn save_state(&mut self) {
-        match self.db.save_state(&self.state) {
+        let result = if self.staged_scenes.is_empty() {
+            self.db.save_state(&self.state)
+        } else {
+            // Unapplied staged edits aren't saved
+            let mut state = self.state.clone();
+            state.scenes = self.committed_scenes();
+            self.db.save_state(&state)
+        };
+        match result {
             Ok(_) => {
                 self.status = "Saved to database".into();
                 self.last_change_time = None; // Reset debounce timer
@@ -267,6 +445,53 @@ impl MyApp {
         }
     }
 
+    /// Track the window's size/position/maximized state and persist it once it settles
+    fn track_window_geometry(&mut self, ctx: &egui::Context) {
+        let (inner, outer, maximized, minimized, on_monitor) = ctx.input(|i| {
+            let vp = i.viewport();
+            (vp.inner_rect, vp.outer_rect, vp.maximized, vp.minimized, vp.monitor_size.is_some())
+        });
+
+        // A restored position can point at a monitor that is no longer connected
+        if self.window_geometry.is_none() && outer.is_some() && !on_monitor {
+            ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(egui::pos2(50.0, 50.0)));
+        }
+
+        let Some(inner) = inner else { return };
+        let mut geometry = self.window_geometry.unwrap_or(WindowGeometry {
+            position: None,
+            inner_size: [inner.width(), inner.height()],
+            maximized: false,
+        });
+        geometry.maximized = maximized.unwrap_or(false);
+        // Keep the restored (un-maximized) placement while maximized or minimized
+        if !geometry.maximized && minimized != Some(true) {
+            geometry.inner_size = [inner.width(), inner.height()];
+            geometry.position = outer.map(|r| [r.min.x, r.min.y]);
+        }
+
+        if self.window_geometry != Some(geometry) {
+            // The first reading is just the startup placement, nothing to save yet
+            if self.window_geometry.is_some() {
+                self.window_geometry_changed = Some(Instant::now());
+            }
+            self.window_geometry = Some(geometry);
+        }
+
+        if self.window_geometry_changed.is_some_and(|t| t.elapsed() >= Duration::from_secs(1)) {
+            self.save_window_geometry();
+        }
+    }
+
+    fn save_window_geometry(&mut self) {
+        self.window_geometry_changed = None;
+        if let Some(geometry) = self.window_geometry {
+            if let Err(e) = self.db.save_window_geometry(&geometry) {
+                eprintln!("Failed to save window geometry: {}", e);
+            }
+        }
+    }
+
     fn mark_state_changed(&mut self) {
         self.last_change_time = Some(Instant::now());
     }
@@ -298,6 +523,84 @@ impl MyApp {
         }
     }
 
+    /// Save just the rig (strips, network, audio) to a layout file
+    fn export_layout(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .set_file_name("lightspeed_layout.json")
+            .add_filter("JSON", &["json"])
+            .save_file()
+        {
+            self.save_state(); // Export what's on screen
+            match self.db.export_layout().and_then(|json| Ok(fs::write(&path, json)?)) {
+                Ok(()) => self.status = format!("Exported layout to {}", path.display()),
+                Err(e) => {
+                    self.status = format!("Layout export failed: {}", e);
+                    eprintln!("Layout export error: {:#}", e);
+                }
+            }
+        }
+    }
+
+    /// Replace the rig with a layout file's, keeping scenes
+    fn import_layout(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        self.save_state(); // Unsaved scene edits survive the reload
+        let result = fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| self.db.import_layout(&json))
+            .and_then(|remapped| Ok((remapped, self.db.load_state()?)));
+        match result {
+            Ok((remapped, state)) => {
+                self.state = state;
+                self.staged_scenes.clear(); // The reloaded scenes are what's saved
+                self.status = match remapped {
+                    0 => format!("Imported layout from {}", path.display()),
+                    n => format!("Imported layout; {} strip id(s) appeared twice in the file and got new ids", n),
+                };
+            }
+            Err(e) => {
+                self.status = format!("Layout import failed: {}", e);
+                eprintln!("Layout import error: {:#}", e);
+            }
+        }
+    }
+
+    /// Write the current state as a timestamped JSON file in the snapshots folder
+    fn save_snapshot(&mut self) {
+        // Snapshot what's on screen, not the last autosave
+        self.save_state();
+        let dir = snapshots_dir();
+        let name: String = self.snapshot_name.trim().chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+        let stamp = utc_timestamp(std::time::SystemTime::now()).replace([' ', ':'], "_");
+        let file_name = if name.is_empty() { format!("{}.json", stamp) } else { format!("{}_{}.json", stamp, name) };
+        let path = dir.join(file_name);
+
+        let result = self.db.export_to_json()
+            .and_then(|json| {
+                fs::create_dir_all(&dir)?;
+                fs::write(&path, json)?;
+                Ok(())
+            });
+        match result {
+            Ok(_) => {
+                self.status = format!("Saved snapshot {}", path.display());
+                self.snapshot_name.clear();
+                self.snapshots = list_snapshots();
+            }
+            Err(e) => {
+                self.status = format!("Snapshot failed: {}", e);
+                eprintln!("Failed to save snapshot: {}", e);
+            }
+        }
+    }
+
     fn import_from_json(&mut self) {
         // Use native file dialog to choose file
         if let Some(path) = rfd::FileDialog::new()
@@ -306,7 +609,237 @@ impl MyApp {
         {
             self.import_file_path = Some(path);
             self.import_dialog_open = true;
+            self.refresh_import_preview();
+        }
+    }
+
+    fn refresh_import_preview(&mut self) {
+        self.import_preview = self.import_file_path.as_ref().map(|path| {
+            fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read file: {}", e))
+                .and_then(|json| {
+                    self.db.preview_import(&json, self.import_merge_mode)
+                        .map_err(|e| format!("{:#}", e))
+                })
+        });
+    }
+
+    /// Clear the Launchpad and resend the current page's button colors
+    fn refresh_launchpad_colors(&mut self) {
+        send_launchpad_colors(&self.midi_sender, &self.state);
+        self.heartbeat_sent = None; // ClearAll turned it off too
+        self.feedback_sent.reset_cc();
+    }
+
+    /// Dispatch the audio trigger's configured action
+    fn run_audio_trigger(&mut self) {
+        let action = self.state.audio.trigger_action.clone();
+        let target = match action.as_str() {
+            "fire" => {
+                self.engine.fire();
+                self.status = "Audio trigger: fired one-shots".into();
+                return;
+            }
+            "next_scene" => self.state.next_scene_id(),
+            other => other.strip_prefix("scene:")
+                .and_then(|id| id.parse::<u64>().ok())
+                .filter(|id| self.state.scenes.iter().any(|s| s.id == *id)),
+        };
+        if let Some(scene) = target.and_then(|id| self.state.scenes.iter().find(|s| s.id == id)) {
+            self.status = format!("Audio trigger: {}", scene.name);
+            self.state.selected_scene_id = Some(scene.id);
+        }
+    }
+
+    /// Staged editing: remember the selected scene as last applied, before this frame's edits
+    fn stage_selected_scene(&mut self) {
+        if !self.state.staged_editing {
+            return;
+        }
+        let Some(id) = self.state.selected_scene_id else { return };
+        if !self.staged_scenes.contains_key(&id) {
+            if let Some(scene) = self.state.scenes.iter().find(|s| s.id == id) {
+                self.staged_scenes.insert(id, scene.clone());
+            }
+        }
+    }
+
+    /// Scenes with staged edits undone. Submaster levels stay live; they're performance controls.
+    fn committed_scenes(&self) -> Vec<Scene> {
+        self.state.scenes.iter()
+            .map(|s| match self.staged_scenes.get(&s.id) {
+                Some(committed) => Scene { master_intensity: s.master_intensity, ..committed.clone() },
+                None => s.clone(),
+            })
+            .collect()
+    }
+
+    fn has_staged_changes(&self) -> bool {
+        self.state.scenes.iter().any(|s| {
+            self.staged_scenes.get(&s.id).is_some_and(|committed| {
+                let committed = Scene { master_intensity: s.master_intensity, ..committed.clone() };
+                serde_json::to_value(&committed).ok() != serde_json::to_value(s).ok()
+            })
+        })
+    }
+
+    /// Keep the staged edits: they become what's saved
+    fn apply_staged(&mut self) {
+        self.staged_scenes.clear();
+        self.mark_state_changed();
+    }
+
+    /// Throw the staged edits away
+    fn revert_staged(&mut self) {
+        self.state.scenes = self.committed_scenes();
+        self.staged_scenes.clear();
+        self.selected_mask_ids.clear();
+    }
+
+    /// Performance mode is on and the live scene hasn't been unlocked for editing
+    fn live_scene_locked(&self) -> bool {
+        self.state.performance_mode && self.performance_unlocked != self.state.selected_scene_id
+    }
+
+    /// Show the stage preview window: the strips' output pixels on black, framed like the
+    /// canvas, with no editor chrome. Double-click toggles full screen; closing it turns it off.
+    fn show_stage_preview(&mut self, ctx: &egui::Context) {
+        let Some(preview) = &self.stage_preview else { return };
+        if preview.lock().unwrap().closed {
+            self.stage_preview = None;
+            return;
+        }
+        let preview = preview.clone();
+        let builder = egui::ViewportBuilder::default()
+            .with_title("Lightspeed Stage Preview")
+            .with_inner_size([960.0, 540.0])
+            .with_fullscreen(self.stage_preview_fullscreen);
+        ctx.show_viewport_deferred(egui::ViewportId::from_hash_of("stage_preview"), builder, move |ctx, class| {
+            let draw = |ui: &mut egui::Ui| {
+                let (response, painter) = ui.allocate_painter(ui.available_size(), egui::Sense::click());
+                let rect = response.rect;
+                painter.rect_filled(rect, 0.0, egui::Color32::BLACK);
+                let mut preview = preview.lock().unwrap();
+                let view = ViewState {
+                    scale: preview.scale,
+                    offset: preview.offset * rect.size(),
+                    pixel_lines: preview.pixel_lines,
+                    fast_render: preview.fast_render,
+                    ..Default::default()
+                };
+                for s in &preview.strips {
+                    draw_strip_pixels(&painter, s, rect, &view, egui::Color32::BLACK);
+                }
+                if response.double_clicked() {
+                    let fullscreen = ui.ctx().input(|i| i.viewport().fullscreen.unwrap_or(false));
+                    ui.ctx().send_viewport_cmd(egui::ViewportCommand::Fullscreen(!fullscreen));
+                }
+                if ui.ctx().input(|i| i.viewport().close_requested()) {
+                    preview.closed = true;
+                }
+            };
+            if class == egui::ViewportClass::Embedded {
+                // No native multi-window support: fall back to a window inside the editor
+                let mut open = true;
+                egui::Window::new("Stage Preview").open(&mut open).default_size([480.0, 270.0]).show(ctx, draw);
+                if !open {
+                    preview.lock().unwrap().closed = true;
+                }
+            } else {
+                egui::CentralPanel::default().frame(egui::Frame::none()).show(ctx, draw);
+            }
+            ctx.request_repaint();
+        });
+    }
+
+    /// Send changed state to control surfaces: mapped fader CCs over MIDI, and the live scene,
+    /// masters and tempo over OSC (addresses in feedback.rs)
+    fn publish_feedback(&mut self) {
+        if self.state.feedback_midi && self.midi_connected {
+            for (cc, value) in self.feedback_sent.changed_cc(self.state.cc_feedback()) {
+                let _ = self.midi_sender.send(midi::MidiCommand::ControlChange { cc, value });
+            }
+        } else {
+            self.feedback_sent.reset_cc();
+        }
+
+        if !self.state.feedback_osc {
+            self.osc_feedback = None;
+            return;
+        }
+        // Open the socket once per target; a bad target is reported once, not every frame
+        let target = &self.state.feedback_osc_target;
+        if self.osc_feedback.as_ref().map(|(t, _)| t) != Some(target) {
+            self.feedback_sent.reset_osc();
+            let sender = feedback::OscSender::new(target)
+                .map_err(|e| self.status = format!("OSC feedback: {:#}", e))
+                .ok();
+            self.osc_feedback = Some((target.clone(), sender));
+        }
+        let Some((_, Some(sender))) = &self.osc_feedback else {
+            return;
+        };
+        let (_, bpm) = self.engine.get_sync_info();
+        for (address, arg) in self.feedback_sent.changed_osc(feedback::osc_values(&self.state, bpm)) {
+            if let Err(e) = sender.send(address, &arg) {
+                log::debug!("[OSC] Feedback send failed: {}", e);
+            }
+        }
+    }
+
+    /// Drive the heartbeat pad from output health: pulsing green while sACN is sending,
+    /// red on send errors or sync loss, dim white when nothing is being sent
+    fn update_heartbeat_led(&mut self) {
+        let Some(button) = self.state.heartbeat_midi_btn else {
+            self.heartbeat_sent = None;
+            return;
+        };
+        let status = self.engine.heartbeat_status();
+        let current = (button, self.state.heartbeat_midi_is_cc, status);
+        if !self.midi_connected || self.heartbeat_sent == Some(current) {
+            return;
+        }
+        let (color, pulse) = match status {
+            "ok" => (21, true),   // Green
+            "error" => (5, false), // Red
+            _ => (1, false),       // Dim white
+        };
+        let _ = self.midi_sender.send(midi::MidiCommand::SetHeartbeat {
+            button,
+            is_cc: self.state.heartbeat_midi_is_cc,
+            color,
+            pulse,
+        });
+        self.heartbeat_sent = Some(current);
+    }
+
+    /// Switch the Launchpad grid to another scene page
+    fn set_launchpad_page(&mut self, page: u8) {
+        let page = page.min(model::SCENE_PAGES - 1);
+        if page != self.state.launchpad_page {
+            self.state.launchpad_page = page;
+            self.refresh_launchpad_colors();
+            self.mark_state_changed();
+        }
+    }
+
+    /// Remove a scene, first turning off its Launchpad pad if it's lit on the current page
+    fn delete_scene(&mut self, id: u64) {
+        let Some(i) = self.state.scenes.iter().position(|s| s.id == id) else {
+            return;
+        };
+        let scene = &self.state.scenes[i];
+        if let Some(btn) = scene.launchpad_btn.filter(|btn| *btn > 0 && scene.page == self.state.launchpad_page) {
+            let cmd = if scene.launchpad_is_cc {
+                midi::MidiCommand::SetButtonColor { cc: btn, color: 0 }
+            } else {
+                midi::MidiCommand::SetPadColor { note: btn, color: 0 }
+            };
+            let _ = self.midi_sender.send(cmd);
         }
+        self.state.scenes.remove(i);
+        self.staged_scenes.remove(&id);
+        self.mark_state_changed();
     }
 
     fn do_import(&mut self) {
@@ -319,7 +852,12 @@ impl MyApp {
                             match self.db.load_state() {
                                 Ok(state) => {
                                     self.state = state;
-                                    self.status = "Import successful".into();
+                                    self.staged_scenes.clear();
+                                    self.status = match self.state.duplicate_launchpad_scenes().len() {
+                                        0 => "Import successful".into(),
+                                        n => format!("Imported; {} scene(s) share a Launchpad button with another scene", n),
+                                    };
+                                    self.refresh_launchpad_colors();
                                     // Restart engine with new state
                                     self.engine = LightingEngine::new();
                                 }
@@ -386,6 +924,52 @@ fn user_config_path() -> PathBuf {
     }
 }
 
+/// Folder holding "Save Snapshot" JSON files, next to the database
+fn snapshots_dir() -> PathBuf {
+    user_asset_path("snapshots")
+}
+
+/// Snapshot files with their modification time, newest first
+fn list_snapshots() -> Vec<(PathBuf, std::time::SystemTime)> {
+    let mut snapshots: Vec<_> = fs::read_dir(snapshots_dir())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let path = entry.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((path, modified))
+        })
+        .collect();
+    snapshots.sort_by_key(|(_, modified)| std::cmp::Reverse(*modified));
+    snapshots
+}
+
+/// "YYYY-MM-DD HH:MM:SS" in UTC
+fn utc_timestamp(time: std::time::SystemTime) -> String {
+    let secs = time.duration_since(std::time::UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (days, rem) = (secs / 86400, secs % 86400);
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02} {:02}:{:02}:{:02}", year, month, day, rem / 3600, rem % 3600 / 60, rem % 60)
+}
+
+/// Optional integrator-supplied file in the config directory (next to the database)
+fn user_asset_path(file_name: &str) -> PathBuf {
+    user_db_path().with_file_name(file_name)
+}
+
 fn user_db_path() -> PathBuf {
     // Cross-platform database path (same location as config but .db extension)
     #[cfg(target_os = "macos")]
@@ -462,6 +1046,19 @@ impl eframe::App for MyApp {
                 self.save_state();
             }
         });
+        self.track_window_geometry(ctx);
+        if ctx.style().visuals.dark_mode == self.state.light_ui {
+            ctx.set_visuals(if self.state.light_ui { egui::Visuals::light() } else { egui::Visuals::dark() });
+        }
+
+        // F fires one-shot effects, unless a text field has focus
+        if ctx.memory(|m| m.focus().is_none()) && ctx.input(|i| i.key_pressed(egui::Key::F) && i.modifiers.is_none()) {
+            self.engine.fire();
+        }
+        // H toggles freeze, same focus rule
+        if ctx.memory(|m| m.focus().is_none()) && ctx.input(|i| i.key_pressed(egui::Key::H) && i.modifiers.is_none()) {
+            self.engine.frozen = !self.engine.frozen;
+        }
 
         // 1. Detection Logic (Runs on Main Thread)
         // Only run MIDI detection if midi_enabled is true
@@ -512,71 +1109,533 @@ impl eframe::App for MyApp {
         while let Ok(event) = self.midi_receiver.try_recv() {
             match event {
                 midi::MidiEvent::NoteOn { note, velocity: _ } => {
+                     if self.heartbeat_midi_learn {
+                         self.state.heartbeat_midi_btn = Some(note);
+                         self.state.heartbeat_midi_is_cc = false;
+                         self.heartbeat_midi_learn = false;
+                         self.refresh_launchpad_colors();
+                         self.mark_state_changed();
+                         continue;
+                     }
+                     if self.panic_midi_learn {
+                         self.state.panic_midi_btn = Some(note);
+                         self.state.panic_midi_is_cc = false;
+                         self.panic_midi_learn = false;
+                         self.mark_state_changed();
+                         continue;
+                     }
+                     if !self.state.panic_midi_is_cc && self.state.panic_midi_btn == Some(note) {
+                         self.panic_midi_held = true;
+                         continue;
+                     }
+                     if self.fire_midi_learn {
+                         self.state.fire_midi_btn = Some(note);
+                         self.state.fire_midi_is_cc = false;
+                         self.fire_midi_learn = false;
+                         self.mark_state_changed();
+                         continue;
+                     }
+                     if !self.state.fire_midi_is_cc && self.state.fire_midi_btn == Some(note) {
+                         self.engine.fire();
+                         continue;
+                     }
+                     if self.freeze_midi_learn {
+                         self.state.freeze_midi_btn = Some(note);
+                         self.state.freeze_midi_is_cc = false;
+                         self.freeze_midi_learn = false;
+                         self.mark_state_changed();
+                         continue;
+                     }
+                     if !self.state.freeze_midi_is_cc && self.state.freeze_midi_btn == Some(note) {
+                         self.engine.frozen = !self.engine.frozen;
+                         continue;
+                     }
                      // Check for scene mapped to this note (and is NOT cc)
-                     if let Some(s) = self.state.scenes.iter().find(|s| !s.launchpad_is_cc && s.launchpad_btn == Some(note)) {
+                     if let Some(s) = self.state.launchpad_scene(note, false) {
                          self.state.selected_scene_id = Some(s.id);
                      }
                 }
-                midi::MidiEvent::ControlChange { controller, value: _ } => {
-                     // Check for scene mapped to this CC
-                     if let Some(s) = self.state.scenes.iter().find(|s| s.launchpad_is_cc && s.launchpad_btn == Some(controller)) {
+                midi::MidiEvent::NoteOff { note } => {
+                     if !self.state.panic_midi_is_cc && self.state.panic_midi_btn == Some(note) {
+                         self.panic_midi_held = false;
+                     }
+                }
+                midi::MidiEvent::ControlChange { controller, value } => {
+                     // Fader CCs (brightness, speed, submasters) take every value, including 0
+                     if self.state.apply_cc(controller, value) {
+                         self.mark_state_changed();
+                         continue;
+                     }
+                     if self.state.panic_midi_is_cc && self.state.panic_midi_btn == Some(controller) {
+                         self.panic_midi_held = value > 0;
+                         continue;
+                     }
+                     // Everything else acts on press only
+                     if value == 0 {
+                         continue;
+                     }
+                     if self.heartbeat_midi_learn {
+                         self.state.heartbeat_midi_btn = Some(controller);
+                         self.state.heartbeat_midi_is_cc = true;
+                         self.heartbeat_midi_learn = false;
+                         self.refresh_launchpad_colors();
+                         self.mark_state_changed();
+                         continue;
+                     }
+                     if self.panic_midi_learn {
+                         self.state.panic_midi_btn = Some(controller);
+                         self.state.panic_midi_is_cc = true;
+                         self.panic_midi_learn = false;
+                         self.mark_state_changed();
+                         continue;
+                     }
+                     if self.fire_midi_learn {
+                         self.state.fire_midi_btn = Some(controller);
+                         self.state.fire_midi_is_cc = true;
+                         self.fire_midi_learn = false;
+                         self.mark_state_changed();
+                         continue;
+                     }
+                     if self.state.fire_midi_is_cc && self.state.fire_midi_btn == Some(controller) {
+                         self.engine.fire();
+                         continue;
+                     }
+                     if self.freeze_midi_learn {
+                         self.state.freeze_midi_btn = Some(controller);
+                         self.state.freeze_midi_is_cc = true;
+                         self.freeze_midi_learn = false;
+                         self.mark_state_changed();
+                         continue;
+                     }
+                     if self.state.freeze_midi_is_cc && self.state.freeze_midi_btn == Some(controller) {
+                         self.engine.frozen = !self.engine.frozen;
+                         continue;
+                     }
+                     // Check for scene mapped to this CC; a scene on this page wins over the page buttons
+                     if let Some(s) = self.state.launchpad_scene(controller, true) {
                          self.state.selected_scene_id = Some(s.id);
+                     } else if controller == LAUNCHPAD_PAGE_UP_CC {
+                         self.set_launchpad_page(self.state.launchpad_page.saturating_sub(1));
+                     } else if controller == LAUNCHPAD_PAGE_DOWN_CC {
+                         self.set_launchpad_page(self.state.launchpad_page + 1);
                      }
                 }
                 midi::MidiEvent::Connected => {
                     println!("Launchpad connected! Refreshing button colors...");
                     self.midi_connected = true;
-                    // Clear all buttons
-                    let _ = self.midi_sender.send(midi::MidiCommand::ClearAll);
-                    // Resend all scene button colors
-                    for s in &self.state.scenes {
-                        if let (Some(btn), Some(col)) = (s.launchpad_btn, s.launchpad_color) {
-                            let cmd = if s.launchpad_is_cc {
-                                midi::MidiCommand::SetButtonColor { cc: btn, color: col }
-                            } else {
-                                midi::MidiCommand::SetPadColor { note: btn, color: col }
-                            };
-                            let _ = self.midi_sender.send(cmd);
-                        }
-                    }
+                    self.refresh_launchpad_colors();
                 }
                 midi::MidiEvent::Disconnected => {
                     println!("Launchpad disconnected. Will retry connection...");
                     self.midi_connected = false;
+                    self.panic_midi_held = false; // The release will never arrive
                     self.last_midi_detection = Some(std::time::Instant::now()); // Delay retry slightly
                 }
             }
         }
 
-        // Import confirmation dialog
-        if self.import_dialog_open {
-            egui::Window::new("Import from JSON")
+        // Color order check
+        if let Some((strip_id, seen)) = self.color_check.as_mut() {
+            const NAMES: [&str; 3] = ["Red", "Green", "Blue"];
+            let strip_id = *strip_id;
+            let mut open = true;
+            let mut close = false;
+            let mut fix = None;
+            let order = self.state.strips.iter().find(|s| s.id == strip_id).map(|s| s.color_order.clone());
+            if seen.len() < 3 && order.is_some() {
+                self.engine.test_pattern = Some(["red", "green", "blue"][seen.len()].to_string());
+                self.engine.test_pattern_strip = Some(strip_id);
+            } else {
+                self.engine.test_pattern = None;
+                self.engine.test_pattern_strip = None;
+            }
+            egui::Window::new("Color order check")
+                .open(&mut open)
                 .collapsible(false)
                 .resizable(false)
                 .show(ctx, |ui| {
-                    ui.label("Import will update your current configuration.");
-                    ui.label("Make sure you have saved any changes first!");
-
-                    ui.separator();
+                    let Some(order) = &order else {
+                        ui.label("The strip was removed.");
+                        close = ui.button("Close").clicked();
+                        return;
+                    };
+                    ui.label(format!("Strip::{} is set to {}. Other strips are blacked out.", strip_id, order));
+                    if seen.len() < 3 {
+                        ui.label(format!("Sending pure {} ({}/3). What color does the strip show?", NAMES[seen.len()].to_lowercase(), seen.len() + 1));
+                        ui.horizontal(|ui| {
+                            for (i, name) in NAMES.iter().enumerate() {
+                                if ui.button(*name).clicked() {
+                                    seen.push(i);
+                                }
+                            }
+                        });
+                    } else {
+                        match model::corrected_color_order(order, [seen[0], seen[1], seen[2]]) {
+                            Some(actual) if actual == *order => {
+                                ui.colored_label(egui::Color32::GREEN, format!("✓ {} is correct", order));
+                            }
+                            Some(actual) => {
+                                ui.colored_label(egui::Color32::YELLOW, format!("⚠ This strip is {}, not {}", actual, order));
+                                if ui.button(format!("Set to {}", actual)).clicked() {
+                                    fix = Some(actual);
+                                }
+                            }
+                            None => {
+                                ui.colored_label(egui::Color32::RED, "⚠ Two colors looked the same: check the wiring or a dead channel rather than the color order");
+                            }
+                        }
+                        if ui.button("↺ Again").clicked() {
+                            seen.clear();
+                        }
+                    }
+                    if ui.button("Done").clicked() {
+                        close = true;
+                    }
+                });
+            if let Some(actual) = fix {
+                if let Some(s) = self.state.strips.iter_mut().find(|s| s.id == strip_id) {
+                    s.color_order = actual.clone();
+                }
+                self.status = format!("Strip::{} color order set to {}", strip_id, actual);
+                self.mark_state_changed();
+                close = true;
+            }
+            if close || !open {
+                self.color_check = None;
+                self.engine.test_pattern = None;
+                self.engine.test_pattern_strip = None;
+            }
+        }
 
+        // Auto-pack preview
+        if let Some(patch) = self.auto_pack_preview.clone() {
+            let mut open = true;
+            let mut apply = false;
+            let mut cancel = false;
+            let base = self.state.channel_base();
+            let overlaps = model::strip_channel_overlaps(&self.state.strips).len();
+            egui::Window::new("Auto-pack strips")
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    if overlaps > 0 {
+                        ui.colored_label(egui::Color32::YELLOW, format!("⚠ {} overlapping strip pair(s) in the current patch", overlaps));
+                    }
+                    ui.label("Fixtures keep their addresses.");
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        egui::Grid::new("auto_pack_preview").striped(true).num_columns(3).show(ui, |ui| {
+                            ui.strong("Strip");
+                            ui.strong("Now");
+                            ui.strong("New");
+                            ui.end_row();
+                            for &(id, universe, start) in &patch {
+                                let Some(s) = self.state.strips.iter().find(|s| s.id == id) else { continue };
+                                ui.label(format!("Strip::{}", id));
+                                ui.label(format!("U{} ch {}", s.universe, s.start_channel - 1 + base));
+                                let text = format!("U{} ch {}", universe, start - 1 + base);
+                                if (s.universe, s.start_channel) != (universe, start) {
+                                    ui.strong(text);
+                                } else {
+                                    ui.weak(text);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                    });
                     ui.horizontal(|ui| {
-                        ui.radio_value(&mut self.import_merge_mode, false, "Replace All");
-                        ui.radio_value(&mut self.import_merge_mode, true, "Merge (add scenes/strips)");
+                        apply = ui.button("Apply").clicked();
+                        cancel = ui.button("Cancel").clicked();
                     });
+                });
+            if apply {
+                let previous = self.state.strips.iter().map(|s| (s.id, s.universe, s.start_channel)).collect();
+                apply_strip_patch(&mut self.state.strips, &patch);
+                self.auto_pack_undo = Some(previous);
+                self.status = "Strips re-patched (Undo auto-pack restores the old patch)".into();
+                self.mark_state_changed();
+            }
+            if apply || cancel || !open {
+                self.auto_pack_preview = None;
+            }
+        }
 
-                    ui.separator();
-
+        // Snapshots browser
+        if self.snapshots_open {
+            let mut open = true;
+            egui::Window::new("Snapshots")
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| {
                     ui.horizontal(|ui| {
-                        if ui.button("Cancel").clicked() {
-                            self.import_dialog_open = false;
+                        ui.add(egui::TextEdit::singleline(&mut self.snapshot_name).hint_text("Optional name").desired_width(160.0));
+                        if ui.button("📸 Save Snapshot").clicked() {
+                            self.save_snapshot();
                         }
+                    });
+                    ui.separator();
 
-                        if ui.button("Import").clicked() {
-                            self.do_import();
-                            self.import_dialog_open = false;
-                        }
+                    let snapshots = self.snapshots.clone();
+                    if snapshots.is_empty() {
+                        ui.label("No snapshots yet.");
+                    }
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        egui::Grid::new("snapshots_grid").num_columns(3).striped(true).show(ui, |ui| {
+                            for (path, modified) in snapshots {
+                                let name = path.file_stem().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                                ui.label(name).on_hover_text(path.display().to_string());
+                                ui.weak(utc_timestamp(modified));
+                                ui.horizontal(|ui| {
+                                    if ui.button("Load...").on_hover_text("Review and import this snapshot").clicked() {
+                                        self.import_file_path = Some(path.clone());
+                                        self.import_merge_mode = false;
+                                        self.import_dialog_open = true;
+                                        self.refresh_import_preview();
+                                    }
+                                    if self.snapshot_delete_confirm.as_ref() == Some(&path) {
+                                        if ui.button(egui::RichText::new("Confirm delete").color(egui::Color32::RED)).clicked() {
+                                            match fs::remove_file(&path) {
+                                                Ok(_) => self.status = format!("Deleted snapshot {}", path.display()),
+                                                Err(e) => self.status = format!("Delete failed: {}", e),
+                                            }
+                                            self.snapshot_delete_confirm = None;
+                                            self.snapshots = list_snapshots();
+                                        }
+                                    } else if ui.button("🗑").on_hover_text("Delete snapshot").clicked() {
+                                        self.snapshot_delete_confirm = Some(path.clone());
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                        });
                     });
                 });
+            if !open {
+                self.snapshots_open = false;
+                self.snapshot_delete_confirm = None;
+            }
+        }
+
+        // Scene delete confirmation
+        if let Some(id) = self.scene_delete_confirm {
+            let mut open = true;
+            let mut decided = false;
+            match self.state.scenes.iter().find(|s| s.id == id) {
+                Some(scene) => {
+                    let masks = scene.masks.len();
+                    let summary = match scene.launchpad_btn {
+                        Some(btn) => format!("{} mask(s), mapped to Launchpad pad {}", masks, btn),
+                        None => format!("{} mask(s)", masks),
+                    };
+                    let name = scene.name.clone();
+                    egui::Window::new("Delete Scene?")
+                        .collapsible(false)
+                        .resizable(false)
+                        .open(&mut open)
+                        .show(ctx, |ui| {
+                            ui.label(format!("Delete \"{}\"?", name));
+                            ui.label(summary);
+                            ui.label("This can't be undone.");
+                            ui.horizontal(|ui| {
+                                if ui.button(egui::RichText::new("Delete").color(egui::Color32::RED)).clicked() {
+                                    self.delete_scene(id);
+                                    decided = true;
+                                }
+                                if ui.button("Cancel").clicked() {
+                                    decided = true;
+                                }
+                            });
+                        });
+                }
+                None => decided = true, // Gone already, e.g. replaced by an import
+            }
+            if decided || !open {
+                self.scene_delete_confirm = None;
+            }
+        }
+
+        // Import confirmation dialog
+        if self.import_dialog_open {
+            egui::Window::new("Import from JSON")
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.label("Import will update your current configuration.");
+                    ui.label("Make sure you have saved any changes first!");
+
+                    ui.separator();
+
+                    let mut mode_changed = false;
+                    ui.horizontal(|ui| {
+                        mode_changed |= ui.radio_value(&mut self.import_merge_mode, false, "Replace All").changed();
+                        mode_changed |= ui.radio_value(&mut self.import_merge_mode, true, "Merge (add scenes/strips)").changed();
+                    });
+                    if mode_changed {
+                        self.refresh_import_preview();
+                    }
+
+                    ui.separator();
+
+                    // Dry-run report
+                    let mut can_import = false;
+                    match &self.import_preview {
+                        Some(Ok(report)) => {
+                            can_import = true;
+                            ui.label(egui::RichText::new("Preview").strong());
+                            egui::Grid::new("import_preview_grid").num_columns(4).show(ui, |ui| {
+                                ui.label("");
+                                ui.label("Added");
+                                ui.label("Replaced");
+                                ui.label("Removed");
+                                ui.end_row();
+
+                                ui.label("Strips");
+                                ui.label(report.strips_added.to_string());
+                                ui.label(report.strips_replaced.to_string());
+                                ui.label(report.strips_removed.to_string());
+                                ui.end_row();
+
+                                ui.label("Scenes");
+                                ui.label(report.scenes_added.to_string());
+                                ui.label(report.scenes_replaced.to_string());
+                                ui.label(report.scenes_removed.to_string());
+                                ui.end_row();
+
+                                ui.label("Masks");
+                                ui.label(report.masks_added.to_string());
+                                ui.label("-");
+                                ui.label(report.masks_removed.to_string());
+                                ui.end_row();
+
+                                ui.label("Fixtures");
+                                if report.fixtures_remapped > 0 {
+                                    ui.label(format!("{} ({} new ids)", report.fixtures_added, report.fixtures_remapped));
+                                } else {
+                                    ui.label(report.fixtures_added.to_string());
+                                }
+                                ui.label(report.fixtures_replaced.to_string());
+                                ui.label(report.fixtures_removed.to_string());
+                                ui.end_row();
+                            });
+
+                            if !report.conflicts.is_empty() {
+                                egui::CollapsingHeader::new(format!("ID conflicts ({})", report.conflicts.len()))
+                                    .show(ui, |ui| {
+                                        egui::ScrollArea::vertical().max_height(120.0).show(ui, |ui| {
+                                            for c in &report.conflicts {
+                                                ui.label(c);
+                                            }
+                                        });
+                                    });
+                            }
+
+                            for w in &report.warnings {
+                                ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", w));
+                            }
+                        }
+                        Some(Err(e)) => {
+                            ui.colored_label(egui::Color32::RED, format!("Cannot import: {}", e));
+                        }
+                        None => {}
+                    }
+
+                    ui.separator();
+
+                    ui.horizontal(|ui| {
+                        if ui.button("Cancel").clicked() {
+                            self.import_dialog_open = false;
+                            self.import_preview = None;
+                        }
+
+                        if ui.add_enabled(can_import, egui::Button::new("Import")).clicked() {
+                            self.do_import();
+                            self.import_dialog_open = false;
+                            self.import_preview = None;
+                        }
+                    });
+                });
+        }
+
+        // Batch edit window for multi-selected masks
+        let selected_count = self.state.active_masks().iter()
+            .filter(|m| self.selected_mask_ids.contains(&m.id))
+            .count();
+        if selected_count > 1 {
+            let mut open = true;
+            let mut clear_selection = false;
+            let mut changed = false;
+            let selected = self.selected_mask_ids.clone();
+            let locked = self.live_scene_locked();
+            egui::Window::new(format!("Batch Edit ({} masks)", selected_count))
+                .open(&mut open)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    let mut masks: Vec<&mut Mask> = self.state.active_masks_mut().into_iter()
+                        .flat_map(|masks| masks.iter_mut())
+                        .filter(|m| selected.contains(&m.id))
+                        .collect();
+                    if locked {
+                        ui.colored_label(egui::Color32::from_rgb(255, 160, 0), "🔒 Performance mode: the live scene is locked");
+                    }
+                    ui.add_enabled_ui(!locked, |ui| {
+                        ui.label("Changes apply to every selected mask that has the parameter.");
+                        ui.separator();
+
+                        for (key, label) in BATCH_EDIT_PARAMS {
+                            // Seed the slider from the first selected mask that has this param
+                            let Some(current) = masks.iter().find_map(|m| m.params.get(key).and_then(|v| v.as_f64())) else {
+                                continue;
+                            };
+                            let Some((min, max)) = model::mask_param_range(key) else {
+                                continue;
+                            };
+                            let mut value = current as f32;
+                            if ui.add(egui::Slider::new(&mut value, min as f32..=max as f32).text(label)).changed() {
+                                for m in masks.iter_mut().filter(|m| m.params.contains_key(key)) {
+                                    m.params.insert(key.into(), value.into());
+                                }
+                                changed = true;
+                            }
+                        }
+
+                        for (key, label) in [("hard_edge", "Hard Edge"), ("sync", "Sync to BPM")] {
+                            let Some(current) = masks.iter().find_map(|m| m.params.get(key).and_then(|v| v.as_bool())) else {
+                                continue;
+                            };
+                            let mut value = current;
+                            if ui.checkbox(&mut value, label).changed() {
+                                for m in masks.iter_mut().filter(|m| m.params.contains_key(key)) {
+                                    m.params.insert(key.into(), value.into());
+                                }
+                                changed = true;
+                            }
+                        }
+
+                        let current_color = masks.iter().find_map(|m| {
+                            m.params.get("color").and_then(|v| serde_json::from_value::<[u8; 3]>(v.clone()).ok())
+                        });
+                        if let Some(mut color) = current_color {
+                            ui.horizontal(|ui| {
+                                ui.label("Color:");
+                                if color_picker(ui, &mut color, "batch_edit_color") {
+                                    for m in masks.iter_mut().filter(|m| m.params.contains_key("color")) {
+                                        m.params.insert("color".into(), serde_json::json!(color));
+                                    }
+                                    changed = true;
+                                }
+                            });
+                        }
+                    });
+                    ui.separator();
+                    if ui.button("Clear Selection").clicked() {
+                        clear_selection = true;
+                    }
+                });
+            if !open || clear_selection {
+                self.selected_mask_ids.clear();
+            }
+            if changed {
+                self.mark_state_changed();
+            }
         }
 
         // Scene Manager Window
@@ -641,7 +1700,8 @@ impl eframe::App for MyApp {
                         // Build a map of button -> scene
                         let mut pad_map: std::collections::HashMap<u8, &model::Scene> = std::collections::HashMap::new();
 
-                        for scene in &self.state.scenes {
+                        ui.label(format!("Page {} of {}", self.state.launchpad_page + 1, model::SCENE_PAGES));
+                        for scene in self.state.scenes.iter().filter(|s| s.page == self.state.launchpad_page) {
                             if let Some(btn) = scene.launchpad_btn {
                                 if btn > 0 && !scene.launchpad_is_cc {
                                     pad_map.insert(btn, scene);
@@ -831,6 +1891,29 @@ impl eframe::App for MyApp {
                         ui.close_menu();
                     }
 
+                    if ui.button("Export Layout...").on_hover_text("Strips, network and audio settings only, without scenes").clicked() {
+                        self.export_layout();
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Import Layout...").on_hover_text("Replace the strips, network and audio settings, keeping your scenes").clicked() {
+                        self.import_layout();
+                        ui.close_menu();
+                    }
+
+                    ui.separator();
+
+                    if ui.button("Save Snapshot").clicked() {
+                        self.save_snapshot();
+                        ui.close_menu();
+                    }
+
+                    if ui.button("Snapshots...").clicked() {
+                        self.snapshots_open = true;
+                        self.snapshots = list_snapshots();
+                        ui.close_menu();
+                    }
+
                     ui.separator();
 
                     if ui.button("Reveal Config in Finder").clicked() {
@@ -840,16 +1923,84 @@ impl eframe::App for MyApp {
                         ui.close_menu();
                     }
                 });
+
+                ui.menu_button("View", |ui| {
+                    let mut open = self.stage_preview.is_some();
+                    if ui.checkbox(&mut open, "Stage Preview")
+                        .on_hover_text("A separate window showing only the pixel output, for a second screen")
+                        .changed()
+                    {
+                        self.stage_preview = open.then(Default::default);
+                        ui.close_menu();
+                    }
+                    ui.add_enabled(open, egui::Checkbox::new(&mut self.stage_preview_fullscreen, "Full Screen Preview"));
+                });
             });
         });
+        self.show_stage_preview(ctx);
         
+        // Panic: full white on every pixel while W, the header button or the mapped pad is held
+        let panic_key_held = ctx.memory(|m| m.focus().is_none()) && ctx.input(|i| i.key_down(egui::Key::W) && i.modifiers.is_none());
+        self.engine.force_white = panic_key_held || self.panic_midi_held || self.panic_ui_held;
+
         // Update Loop (Physics/Networking)
         self.engine.update(&mut self.state);
+        self.update_heartbeat_led();
+        self.publish_feedback();
+        let expired = self.engine.take_expired_masks();
+        if !expired.is_empty() {
+            self.selected_mask_ids.retain(|id| !expired.contains(id));
+            self.mark_state_changed();
+        }
+        if let Some(message) = self.engine.take_recording_message() {
+            self.status = message;
+        }
+        if self.engine.take_audio_trigger() {
+            self.run_audio_trigger();
+        }
+        if let Some(bpm) = self.engine.take_latched_bpm() {
+            self.state.audio.manual_bpm = bpm;
+            self.status = format!("Latched {:.1} BPM as the manual tempo", bpm);
+            self.mark_state_changed();
+        }
+
+        self.stage_selected_scene();
+        let mut phase_preview_shown = false;
 
         egui::CentralPanel::default().show(ctx, |ui| {
             // HEADER AND STATUS
             ui.horizontal(|ui| {
                 ui.heading("Lightspeed");
+                if ui.selectable_label(self.state.performance_mode, "🔒 Perform")
+                    .on_hover_text("Performance mode: the live scene can't be edited by accident. Scene switching and submasters still work.")
+                    .clicked()
+                {
+                    self.state.performance_mode = !self.state.performance_mode;
+                    self.performance_unlocked = None;
+                    self.mark_state_changed();
+                }
+                if self.state.performance_mode {
+                    ui.label(egui::RichText::new(" PERFORMANCE MODE ").strong().color(egui::Color32::BLACK).background_color(egui::Color32::from_rgb(255, 160, 0)));
+                }
+                if ui.selectable_label(self.state.staged_editing, "🧪 Staged")
+                    .on_hover_text("Staged editing: scene edits show live but aren't saved until you Apply; Revert discards them. Turning it off keeps pending edits.")
+                    .clicked()
+                {
+                    self.state.staged_editing = !self.state.staged_editing;
+                    if !self.state.staged_editing {
+                        self.apply_staged();
+                    }
+                    self.mark_state_changed();
+                }
+                if self.state.staged_editing && self.has_staged_changes() {
+                    ui.colored_label(egui::Color32::from_rgb(255, 160, 0), "Unapplied changes");
+                    if ui.button("✔ Apply").clicked() {
+                        self.apply_staged();
+                    }
+                    if ui.button("↺ Revert").clicked() {
+                        self.revert_staged();
+                    }
+                }
                 ui.separator();
                 
                 // Unified Sync Status
@@ -860,21 +2011,69 @@ impl eframe::App for MyApp {
                 
                 ui.label(egui::RichText::new(source).color(source_color).strong());
                 
-                let beat = self.engine.get_beat();
-                let beat_in_bar = ((beat % 4.0).floor() as i32) + 1;
+                let beat_in_bar = self.engine.get_beat_in_bar();
                 
                 // Beat Indicator using progress bar or text
                 // Let's use text for now as requested "transparent"
                 ui.separator();
                 ui.label(egui::RichText::new(format!("{:.1} BPM", bpm)).size(18.0).strong());
-                ui.label(egui::RichText::new(format!("Beat: {}", beat_in_bar)).size(18.0));
+                ui.separator();
+                let master = ui.add(egui::Slider::new(&mut self.state.master_brightness, 0.0..=1.0).text("Master").show_value(false))
+                    .on_hover_text(format!("Master brightness: {:.0}%", self.state.master_brightness * 100.0));
+                if let Some(cc) = self.state.cc_for("brightness") {
+                    ui.weak(format!("CC {}", cc));
+                }
+                if master.changed() {
+                    self.mark_state_changed();
+                }
+                ui.label(egui::RichText::new(format!("Beat: {}/{}", beat_in_bar, self.engine.beats_per_bar())).size(18.0));
+                if ui.add(egui::DragValue::new(&mut self.state.audio.beat_offset)
+                        .speed(0.01)
+                        .clamp_range(-1.0..=1.0)
+                        .prefix("Offset: ")
+                        .suffix(" beats"))
+                    .on_hover_text("Shift visuals against the music. Positive = earlier. Separate from Audio Latency, which compensates hardware delay.")
+                    .changed()
+                {
+                    self.mark_state_changed();
+                }
+                if ui.button("⚡ Fire").on_hover_text("Fire one-shot effects once (F)").clicked() {
+                    self.engine.fire();
+                }
+                let panic_button = ui.add(egui::Button::new("⚪ Test White").sense(egui::Sense::drag()))
+                    .on_hover_text("Hold to force every pixel to full white (or hold W)");
+                self.panic_ui_held = panic_button.is_pointer_button_down_on();
+                if ui.selectable_label(self.engine.frozen, "❄ Freeze")
+                    .on_hover_text("Hold the current output on the rig; it keeps being sent (H)")
+                    .clicked()
+                {
+                    self.engine.frozen = !self.engine.frozen;
+                }
+                if self.engine.force_white {
+                    ui.colored_label(egui::Color32::RED, "FULL WHITE");
+                } else if self.engine.test_pattern.is_some() {
+                    ui.colored_label(egui::Color32::YELLOW, "TEST PATTERN");
+                } else if self.engine.frozen {
+                    ui.label(egui::RichText::new("FROZEN").strong().color(egui::Color32::WHITE).background_color(egui::Color32::from_rgb(40, 110, 200)));
+                }
                 
-                // Small visual metronome?
-                let phase = beat.fract();
-                if phase < 0.2 {
-                    ui.label("🔴");
-                } else {
-                    ui.label("⚪");
+                // Metronome ring: fills over each beat, flashes red on the downbeat
+                let phase = self.engine.get_phase() as f32;
+                let (ring_rect, _) = ui.allocate_exact_size(egui::vec2(22.0, 22.0), egui::Sense::hover());
+                let center = ring_rect.center();
+                let radius = 8.0;
+                let painter = ui.painter();
+                painter.circle_stroke(center, radius, egui::Stroke::new(2.0, egui::Color32::from_gray(60)));
+                let ring_color = if phase < 0.2 { egui::Color32::RED } else { egui::Color32::WHITE };
+                let segments = 32;
+                let arc: Vec<egui::Pos2> = (0..=((phase * segments as f32).ceil() as usize))
+                    .map(|i| {
+                        let a = (i as f32 / segments as f32).min(phase) * std::f32::consts::TAU - std::f32::consts::FRAC_PI_2;
+                        center + egui::vec2(a.cos(), a.sin()) * radius
+                    })
+                    .collect();
+                if arc.len() > 1 {
+                    painter.add(egui::Shape::line(arc, egui::Stroke::new(2.5, ring_color)));
                 }
 
                 ui.separator();
@@ -884,6 +2083,36 @@ impl eframe::App for MyApp {
                     self.status = "Saved".into();
                 }
                 ui.label(&self.status);
+
+                let dropped = self.engine.over_limit_universe_count();
+                let registered = self.engine.registered_universe_count();
+                let max = self.state.network.max_universes as usize;
+                if dropped > 0 {
+                    ui.colored_label(egui::Color32::RED, format!("⚠ {} universe(s) over limit", dropped))
+                        .on_hover_text("Raise Max Universes in Network Output to send them.");
+                } else if registered * 5 >= max * 4 {
+                    ui.colored_label(egui::Color32::YELLOW, format!("⚠ {} / {} universes", registered, max))
+                        .on_hover_text("Close to Max Universes in Network Output. Output for universes beyond it is not sent.");
+                }
+                if self.engine.flash_limited() {
+                    ui.colored_label(egui::Color32::YELLOW, "⚠ Flash limited")
+                        .on_hover_text("An effect asked for a faster or harsher flash than the safety limits in Global Settings allow.");
+                }
+                let duplicate_pads = self.state.duplicate_launchpad_scenes();
+                if !duplicate_pads.is_empty() {
+                    let names: Vec<&str> = self.state.scenes.iter()
+                        .filter(|s| duplicate_pads.contains(&s.id))
+                        .map(|s| s.name.as_str())
+                        .collect();
+                    ui.colored_label(egui::Color32::YELLOW, format!("⚠ {} duplicate pad mapping(s)", duplicate_pads.len()))
+                        .on_hover_text(format!("These scenes share a Launchpad button with an earlier scene, so the pad can't reach them: {}", names.join(", ")));
+                    if ui.small_button("Unmap duplicates").clicked() {
+                        let cleared = self.state.clear_duplicate_launchpad_mappings();
+                        self.status = format!("Unmapped {} duplicate Launchpad button(s)", cleared);
+                        self.refresh_launchpad_colors();
+                        self.mark_state_changed();
+                    }
+                }
             });
             ui.separator(); // This separator is *after* the horizontal block.
 
@@ -896,16 +2125,72 @@ impl eframe::App for MyApp {
                         ui.collapsing("Global Settings", |ui| {
                             ui.horizontal(|ui| {
                                  ui.label("Master Speed");
-                                 ui.add(egui::Slider::new(&mut self.engine.speed, 0.1..=5.0));
+                                 if ui.add(egui::Slider::new(&mut self.state.master_speed, model::MASTER_SPEED_RANGE)).changed() {
+                                     self.mark_state_changed();
+                                 }
+                                 if let Some(cc) = self.state.cc_for("speed") {
+                                     ui.weak(format!("CC {}", cc));
+                                 }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Manual BPM");
+                                let mut changed = ui.add(egui::DragValue::new(&mut self.state.audio.manual_bpm).clamp_range(model::MANUAL_BPM_RANGE).speed(0.1).max_decimals(1))
+                                    .on_hover_text("Tempo when no Link peer or audio BPM is available, before Master Speed")
+                                    .changed();
+                                let detected = self.engine.detected_bpm();
+                                let latch = ui.add_enabled(detected.is_some(), egui::Button::new("📌 Latch"))
+                                    .on_hover_text(match detected {
+                                        Some(bpm) => format!("Use the detected {:.1} BPM as the manual tempo", bpm),
+                                        None => "No audio BPM detected yet".to_string(),
+                                    });
+                                if let Some(bpm) = detected.filter(|_| latch.clicked()) {
+                                    self.state.audio.manual_bpm = bpm;
+                                    changed = true;
+                                }
+                                changed |= ui.checkbox(&mut self.state.audio.auto_latch_bpm, "Auto")
+                                    .on_hover_text("Latch the detected BPM automatically once it has held steady for a few seconds")
+                                    .changed();
+                                if changed {
+                                    self.mark_state_changed();
+                                }
                             });
                             ui.horizontal(|ui| {
                                  ui.label("Audio Latency (ms)");
                                  ui.add(egui::Slider::new(&mut self.state.audio.latency_ms, -200.0..=500.0));
                             });
                             ui.horizontal(|ui| {
-                                 ui.checkbox(&mut self.state.audio.use_flywheel, "Beat Smoothing (Flywheel)");
+                                 ui.checkbox(&mut self.state.audio.use_flywheel, "Beat Smoothing (Flywheel)")
+                                     .on_hover_text("Smooths Link's beat instead of following it directly. Audio and manual tempo always run through the flywheel.");
+                            });
+                            ui.horizontal(|ui| {
+                                 ui.label("Smoothing");
+                                 if ui.add(egui::Slider::new(&mut self.state.output_smoothing, 0.0..=0.95))
+                                     .on_hover_text("Blends each frame with the previous one for motion blur / trails. 0 = off.")
+                                     .changed()
+                                 {
+                                     self.mark_state_changed();
+                                 }
+                            });
+                            ui.horizontal(|ui| {
+                                 ui.label("Engine tick (Hz)");
+                                 if ui.add(egui::Slider::new(&mut self.state.engine_tick_hz, model::ENGINE_TICK_HZ_RANGE))
+                                     .on_hover_text("Animation advances in fixed steps at this rate, so timing stays even when the UI frame rate wobbles.")
+                                     .changed()
+                                 {
+                                     self.mark_state_changed();
+                                 }
                             });
                             ui.separator();
+                            ui.horizontal(|ui| {
+                                ui.label(self.engine.audio_status());
+                                if ui.button("🔄 Retry audio init").clicked() {
+                                    self.status = if self.engine.retry_audio_init() {
+                                        self.engine.audio_status()
+                                    } else {
+                                        "No audio input found".into()
+                                    };
+                                }
+                            });
                             ui.label("Hybrid Sync (Audio)");
                             ui.horizontal(|ui| {
                                 ui.checkbox(&mut self.state.audio.hybrid_sync, "Enable Audio Snap");
@@ -913,17 +2198,428 @@ impl eframe::App for MyApp {
                                      ui.add(egui::Slider::new(&mut self.state.audio.sensitivity, 0.0..=1.0).text("Sens"));
                                 }
                             });
+                            ui.horizontal(|ui| {
+                                ui.label("Beats per bar");
+                                if ui.add(egui::DragValue::new(&mut self.state.audio.beats_per_bar).clamp_range(model::BEATS_PER_BAR_RANGE))
+                                    .on_hover_text("Meter of the music: 4 for 4/4, 3 for 3/4, 6 for 6/8 counted in eighths. Also the Link quantum for bar phase.")
+                                    .changed()
+                                {
+                                    self.mark_state_changed();
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Audio trigger");
+                                let scenes: Vec<(u64, String)> = self.state.scenes.iter().map(|s| (s.id, s.name.clone())).collect();
+                                let action_label = |action: &str| match action {
+                                    "next_scene" => "Next scene".to_string(),
+                                    "fire" => "Fire one-shots".to_string(),
+                                    other => other.strip_prefix("scene:")
+                                        .and_then(|id| id.parse::<u64>().ok())
+                                        .and_then(|id| scenes.iter().find(|(s, _)| *s == id))
+                                        .map_or("Off".to_string(), |(_, name)| format!("Go to {}", name)),
+                                };
+                                let before = self.state.audio.trigger_action.clone();
+                                egui::ComboBox::from_id_source("audio_trigger_action")
+                                    .selected_text(action_label(&before))
+                                    .show_ui(ui, |ui| {
+                                        let actions = ["off".to_string(), "next_scene".to_string(), "fire".to_string()].into_iter()
+                                            .chain(scenes.iter().map(|(id, _)| format!("scene:{}", id)));
+                                        for action in actions {
+                                            let label = action_label(&action);
+                                            ui.selectable_value(&mut self.state.audio.trigger_action, action, label);
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text("What a strong audio onset (a drop, a hit after a break) does. Needs an audio input.");
+                                if self.state.audio.trigger_action != before {
+                                    self.mark_state_changed();
+                                }
+                            });
+                            if self.state.audio.trigger_action != "off" {
+                                ui.horizontal(|ui| {
+                                    let mut changed = ui.add(egui::Slider::new(&mut self.state.audio.trigger_threshold, model::TRIGGER_THRESHOLD_RANGE).text("Threshold"))
+                                        .on_hover_text("Onset strength needed to fire; raise it so only the big hits count")
+                                        .changed();
+                                    changed |= ui.add(egui::DragValue::new(&mut self.state.audio.trigger_cooldown_secs).clamp_range(model::TRIGGER_COOLDOWN_SECS_RANGE).suffix(" s cooldown"))
+                                        .on_hover_text("Minimum time between triggers, so one drop fires once")
+                                        .changed();
+                                    if changed {
+                                        self.mark_state_changed();
+                                    }
+                                });
+                            }
+                            ui.label("Tempo source priority").on_hover_text("The first source with a tempo wins. Link needs peers, Audio needs a detected BPM, Manual (Manual BPM × Master Speed) always has one.");
+                            let mut order = self.state.audio.beat_source_order();
+                            let mut move_up = None;
+                            for (i, source) in order.iter().enumerate() {
+                                ui.horizontal(|ui| {
+                                    ui.label(format!("{}.", i + 1));
+                                    ui.label(match source.as_str() { "link" => "Link", "audio" => "Audio", _ => "Manual" });
+                                    if i > 0 && ui.small_button("⬆").clicked() {
+                                        move_up = Some(i);
+                                    }
+                                    if i + 1 < order.len() && ui.small_button("⬇").clicked() {
+                                        move_up = Some(i + 1);
+                                    }
+                                });
+                            }
+                            if let Some(i) = move_up {
+                                order.swap(i - 1, i);
+                                self.state.audio.source_priority = order;
+                                self.mark_state_changed();
+                            }
+                            ui.collapsing("Advanced Sync", |ui| {
+                                // Lower correction values favour stability (busy or swung music),
+                                // higher ones favour responsiveness (tight electronic music)
+                                let audio = &mut self.state.audio;
+                                let mut changed = false;
+                                changed |= ui.add(egui::Slider::new(&mut audio.flywheel_jump_beats, model::FLYWHEEL_JUMP_BEATS_RANGE).text("Jump threshold (beats)"))
+                                    .on_hover_text("A difference from Link larger than this is treated as a jump (e.g. the DJ restarted the transport) instead of drift.")
+                                    .changed();
+                                changed |= ui.add(egui::Slider::new(&mut audio.flywheel_recovery_secs, model::FLYWHEEL_RECOVERY_SECS_RANGE).text("Snap after (s)"))
+                                    .on_hover_text("How long a jump must last before the flywheel snaps to Link. Shorter follows cues faster; longer rides out glitches.")
+                                    .changed();
+                                changed |= ui.add(egui::Slider::new(&mut audio.flywheel_nudge, model::FLYWHEEL_NUDGE_RANGE).text("Link pull"))
+                                    .on_hover_text("Fraction of the remaining drift toward Link corrected each frame. 1 follows Link exactly.")
+                                    .changed();
+                                changed |= ui.add(egui::Slider::new(&mut audio.phase_correction, model::PHASE_CORRECTION_RANGE).text("Audio phase correction"))
+                                    .on_hover_text("Audio Snap: fraction of the phase error corrected on each detected beat.")
+                                    .changed();
+                                changed |= ui.add(egui::Slider::new(&mut audio.phase_decay, model::PHASE_DECAY_RANGE).text("Audio phase release"))
+                                    .on_hover_text("Audio Snap: fraction of the accumulated correction released each frame, so the beat drifts back when audio stops.")
+                                    .changed();
+                                if ui.button("Reset to defaults").clicked() {
+                                    let defaults = model::AudioConfig::default();
+                                    audio.flywheel_jump_beats = defaults.flywheel_jump_beats;
+                                    audio.flywheel_recovery_secs = defaults.flywheel_recovery_secs;
+                                    audio.flywheel_nudge = defaults.flywheel_nudge;
+                                    audio.phase_correction = defaults.phase_correction;
+                                    audio.phase_decay = defaults.phase_decay;
+                                    changed = true;
+                                }
+                                if changed {
+                                    self.mark_state_changed();
+                                }
+                            });
                             ui.separator();
                             ui.horizontal(|ui| {
                                 ui.checkbox(&mut self.state.midi_enabled, "Enable MIDI (Launchpad)");
                             });
+                            ui.horizontal(|ui| {
+                                ui.label("Fire button:");
+                                let mapping = match self.state.fire_midi_btn {
+                                    Some(btn) if self.state.fire_midi_is_cc => format!("CC {}", btn),
+                                    Some(btn) => format!("Note {}", btn),
+                                    None => "None".to_string(),
+                                };
+                                ui.label(if self.fire_midi_learn { "Press a pad...".to_string() } else { mapping });
+                                if ui.selectable_label(self.fire_midi_learn, "Learn").clicked() {
+                                    self.fire_midi_learn = !self.fire_midi_learn;
+                                }
+                                if self.state.fire_midi_btn.is_some() && ui.button("Clear").clicked() {
+                                    self.state.fire_midi_btn = None;
+                                    self.mark_state_changed();
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Freeze button:");
+                                let mapping = match self.state.freeze_midi_btn {
+                                    Some(btn) if self.state.freeze_midi_is_cc => format!("CC {}", btn),
+                                    Some(btn) => format!("Note {}", btn),
+                                    None => "None".to_string(),
+                                };
+                                ui.label(if self.freeze_midi_learn { "Press a pad...".to_string() } else { mapping });
+                                if ui.selectable_label(self.freeze_midi_learn, "Learn").clicked() {
+                                    self.freeze_midi_learn = !self.freeze_midi_learn;
+                                }
+                                if self.state.freeze_midi_btn.is_some() && ui.button("Clear").clicked() {
+                                    self.state.freeze_midi_btn = None;
+                                    self.mark_state_changed();
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Test white button:");
+                                let mapping = match self.state.panic_midi_btn {
+                                    Some(btn) if self.state.panic_midi_is_cc => format!("CC {}", btn),
+                                    Some(btn) => format!("Note {}", btn),
+                                    None => "None".to_string(),
+                                };
+                                ui.label(if self.panic_midi_learn { "Press a pad...".to_string() } else { mapping });
+                                if ui.selectable_label(self.panic_midi_learn, "Learn").clicked() {
+                                    self.panic_midi_learn = !self.panic_midi_learn;
+                                }
+                                if self.state.panic_midi_btn.is_some() && ui.button("Clear").clicked() {
+                                    self.state.panic_midi_btn = None;
+                                    self.panic_midi_held = false;
+                                    self.mark_state_changed();
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Heartbeat pad:")
+                                    .on_hover_text("Pulses green while sACN is sending, red on send errors or lost sync. Pick a pad no scene uses.");
+                                let mapping = match self.state.heartbeat_midi_btn {
+                                    Some(btn) if self.state.heartbeat_midi_is_cc => format!("CC {}", btn),
+                                    Some(btn) => format!("Note {}", btn),
+                                    None => "Off".to_string(),
+                                };
+                                ui.label(if self.heartbeat_midi_learn { "Press a pad...".to_string() } else { mapping });
+                                if ui.selectable_label(self.heartbeat_midi_learn, "Learn").clicked() {
+                                    self.heartbeat_midi_learn = !self.heartbeat_midi_learn;
+                                }
+                                if self.state.heartbeat_midi_btn.is_some() && ui.button("Clear").clicked() {
+                                    self.state.heartbeat_midi_btn = None;
+                                    self.refresh_launchpad_colors();
+                                    self.mark_state_changed();
+                                }
+                            });
+                            ui.collapsing("Fader CCs", |ui| {
+                                ui.label("Fixed CC numbers for a fader bank. A mapped CC only moves its fader, never triggers scenes or buttons.");
+                                let mut changed = false;
+                                let mut cc_row = |ui: &mut egui::Ui, state: &mut AppState, target: &str, label: &str| {
+                                    ui.horizontal(|ui| {
+                                        ui.label(label);
+                                        let current = state.cc_for(target);
+                                        let mut enabled = current.is_some();
+                                        let mut cc = current.unwrap_or(0);
+                                        let mut row_changed = ui.checkbox(&mut enabled, "").changed();
+                                        if enabled {
+                                            row_changed |= ui.add(egui::DragValue::new(&mut cc).prefix("CC ").clamp_range(0..=127)).changed();
+                                        }
+                                        if row_changed {
+                                            state.set_cc(target, enabled.then_some(cc));
+                                            changed = true;
+                                        }
+                                    });
+                                };
+                                for (target, label) in model::CC_TARGETS {
+                                    cc_row(ui, &mut self.state, target, label);
+                                }
+                                ui.label("Scene submasters:");
+                                let scenes: Vec<(u64, String)> = self.state.scenes.iter().map(|s| (s.id, s.name.clone())).collect();
+                                let mut add_scene = None;
+                                for (id, name) in &scenes {
+                                    let target = format!("scene:{}", id);
+                                    if self.state.cc_for(&target).is_some() {
+                                        cc_row(ui, &mut self.state, &target, name);
+                                    }
+                                }
+                                egui::ComboBox::from_id_source("cc_add_scene")
+                                    .selected_text("Map a scene...")
+                                    .show_ui(ui, |ui| {
+                                        for (id, name) in &scenes {
+                                            if self.state.cc_for(&format!("scene:{}", id)).is_none() && ui.selectable_label(false, name).clicked() {
+                                                add_scene = Some(*id);
+                                            }
+                                        }
+                                    });
+                                if let Some(id) = add_scene {
+                                    // Next CC after the highest one in use, so a fader bank fills in order
+                                    let next = self.state.cc_mappings.iter().map(|m| m.cc + 1).max().unwrap_or(1).min(127);
+                                    self.state.set_cc(&format!("scene:{}", id), Some(next));
+                                    changed = true;
+                                }
+                                // Mappings for deleted scenes do nothing; drop them
+                                let before = self.state.cc_mappings.len();
+                                self.state.cc_mappings.retain(|m| {
+                                    let id = m.target.strip_prefix("scene:").and_then(|id| id.parse::<u64>().ok());
+                                    id.is_none() || scenes.iter().any(|(s, _)| Some(*s) == id)
+                                });
+                                changed |= self.state.cc_mappings.len() != before;
+                                if changed {
+                                    self.mark_state_changed();
+                                }
+                            });
+                            ui.collapsing("Feedback", |ui| {
+                                let before = (self.state.feedback_midi, self.state.feedback_osc, self.state.feedback_osc_target.clone());
+                                ui.checkbox(&mut self.state.feedback_midi, "Send fader CCs")
+                                    .on_hover_text("Send each mapped fader CC its control's value whenever it changes, so motorized faders and LED rings follow");
+                                ui.horizontal(|ui| {
+                                    ui.checkbox(&mut self.state.feedback_osc, "Send OSC to");
+                                    // Applied on commit: a new target is resolved (a DNS lookup) once, not per keystroke
+                                    let edit_id = ui.make_persistent_id("osc_target_edit");
+                                    let mut target = ui.data_mut(|d| d.get_temp::<String>(edit_id))
+                                        .unwrap_or_else(|| self.state.feedback_osc_target.clone());
+                                    let resp = ui.add(egui::TextEdit::singleline(&mut target).desired_width(140.0).hint_text("host:port"));
+                                    if resp.changed() {
+                                        ui.data_mut(|d| d.insert_temp(edit_id, target.clone()));
+                                    }
+                                    if resp.lost_focus() {
+                                        self.state.feedback_osc_target = target.trim().to_string();
+                                        ui.data_mut(|d| d.remove::<String>(edit_id));
+                                    }
+                                }).response.on_hover_text("Live scene (/lightspeed/scene, /lightspeed/scene/index), /lightspeed/master, /lightspeed/speed, \
+                                    /lightspeed/submaster and /lightspeed/bpm, sent whenever they change");
+                                if matches!(&self.osc_feedback, Some((_, None))) && self.state.feedback_osc {
+                                    ui.colored_label(egui::Color32::YELLOW, "⚠ Can't send to this target");
+                                }
+                                if before != (self.state.feedback_midi, self.state.feedback_osc, self.state.feedback_osc_target.clone()) {
+                                    self.mark_state_changed();
+                                }
+                            });
+                            ui.separator();
+                            ui.horizontal(|ui| {
+                                ui.label("No scene selected:");
+                                let mode_label = |mode: &str| match mode {
+                                    "last" => "Last scene",
+                                    "scene" => "Specific scene",
+                                    _ => "Black",
+                                };
+                                let before = (self.state.no_selection_mode.clone(), self.state.fallback_scene_id);
+                                egui::ComboBox::from_id_source("no_selection_mode")
+                                    .selected_text(mode_label(&self.state.no_selection_mode))
+                                    .show_ui(ui, |ui| {
+                                        for mode in ["black", "last", "scene"] {
+                                            ui.selectable_value(&mut self.state.no_selection_mode, mode.to_string(), mode_label(mode));
+                                        }
+                                    });
+                                if self.state.no_selection_mode == "scene" {
+                                    let fallback_name = self.state.fallback_scene_id
+                                        .and_then(|id| self.state.scenes.iter().find(|s| s.id == id))
+                                        .map(|s| s.name.clone())
+                                        .unwrap_or_else(|| "None".into());
+                                    egui::ComboBox::from_id_source("fallback_scene")
+                                        .selected_text(fallback_name)
+                                        .show_ui(ui, |ui| {
+                                            for scene in &self.state.scenes {
+                                                if ui.selectable_label(self.state.fallback_scene_id == Some(scene.id), &scene.name).clicked() {
+                                                    self.state.fallback_scene_id = Some(scene.id);
+                                                }
+                                            }
+                                        });
+                                }
+                                if before != (self.state.no_selection_mode.clone(), self.state.fallback_scene_id) {
+                                    self.mark_state_changed();
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("On sync loss:");
+                                let behavior_label = |behavior: &str| match behavior {
+                                    "hold" => "Hold last frame",
+                                    "fade" => "Fade to black",
+                                    _ => "Keep animating",
+                                };
+                                let before = self.state.sync_loss_behavior.clone();
+                                egui::ComboBox::from_id_source("sync_loss_behavior")
+                                    .selected_text(behavior_label(&self.state.sync_loss_behavior))
+                                    .show_ui(ui, |ui| {
+                                        for behavior in ["continue", "hold", "fade"] {
+                                            ui.selectable_value(&mut self.state.sync_loss_behavior, behavior.to_string(), behavior_label(behavior));
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text("What to do when Link peers and audio beats have been gone for a few seconds, or sync keeps failing.");
+                                if before != self.state.sync_loss_behavior {
+                                    self.mark_state_changed();
+                                }
+                                if self.engine.sync_lost() {
+                                    ui.colored_label(egui::Color32::YELLOW, "Sync lost");
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Mask overlap:");
+                                let compositing_label = |mode: &str| match mode {
+                                    "max" => "Highest wins (HTP)",
+                                    "ltp" => "Latest wins (LTP)",
+                                    _ => "Additive",
+                                };
+                                let before = self.state.mask_compositing.clone();
+                                egui::ComboBox::from_id_source("mask_compositing")
+                                    .selected_text(compositing_label(&self.state.mask_compositing))
+                                    .show_ui(ui, |ui| {
+                                        for mode in ["add", "max", "ltp"] {
+                                            ui.selectable_value(&mut self.state.mask_compositing, mode.to_string(), compositing_label(mode));
+                                        }
+                                    })
+                                    .response
+                                    .on_hover_text("How overlapping masks combine on a pixel. Latest wins lets a later mask fully cover earlier ones.");
+                                if before != self.state.mask_compositing {
+                                    self.mark_state_changed();
+                                }
+                            });
+                            ui.separator();
+                            // Fast, high-contrast flashing can trigger seizures in photosensitive people.
+                            // These limits default to the common 3 flashes/second guideline for public spaces.
+                            ui.label("Flash safety limits");
+                            ui.horizontal(|ui| {
+                                if ui.add(egui::Slider::new(&mut self.state.max_flash_hz, 0.5..=20.0).text("Max flashes/s"))
+                                    .on_hover_text("Flashing effects (Flash, Zone Alternate pulse) are slowed on the beat grid to stay under this rate. \
+                                        Keep it at 3 or below for public audiences: faster strobing can trigger photosensitive seizures.")
+                                    .changed()
+                                {
+                                    self.mark_state_changed();
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                if ui.add(egui::Slider::new(&mut self.state.max_flash_contrast, 0.0..=1.0).text("Max contrast"))
+                                    .on_hover_text("Largest brightness swing a flash may use. Below 1.0 the flash never drops fully to black, \
+                                        which makes flashing much less harsh.")
+                                    .changed()
+                                {
+                                    self.mark_state_changed();
+                                }
+                            });
+                            ui.separator();
+                            let mut theme_changed = false;
+                            ui.horizontal(|ui| {
+                                ui.label("Canvas theme:");
+                                let label = |v: &str| match v {
+                                    "light" => "Light",
+                                    "high_contrast" => "High contrast",
+                                    _ => "Dark",
+                                };
+                                egui::ComboBox::from_id_source("canvas_theme")
+                                    .selected_text(label(&self.state.canvas_theme))
+                                    .show_ui(ui, |ui| {
+                                        for v in ["dark", "light", "high_contrast"] {
+                                            if ui.selectable_value(&mut self.state.canvas_theme, v.to_string(), label(v)).changed() {
+                                                theme_changed = true;
+                                            }
+                                        }
+                                    });
+                                if ui.checkbox(&mut self.state.light_ui, "Light panels")
+                                    .on_hover_text("Light window and panel colors, easier to read in daylight")
+                                    .changed()
+                                {
+                                    theme_changed = true;
+                                }
+                            });
+                            if theme_changed {
+                                self.mark_state_changed();
+                            }
+                            if ui.checkbox(&mut self.state.zero_based_channels, "0-based DMX channels")
+                                .on_hover_text("Show and enter start channels as 0..511 instead of 1..512, for consoles and controllers that count from zero. \
+                                    The same physical slot is addressed either way.")
+                                .changed()
+                            {
+                                self.mark_state_changed();
+                            }
                         });
                         
                         ui.collapsing("Network Output", |ui| {
                             ui.horizontal(|ui| {
                                 ui.label("Universe");
                                 ui.add(egui::DragValue::new(&mut self.state.network.universe).speed(1).clamp_range(1..=63999));
+                                let mode = &mut self.state.network.universe_mode;
+                                let before = mode.clone();
+                                egui::ComboBox::from_id_source("universe_mode")
+                                    .selected_text(if mode == "absolute" { "Absolute" } else { "Relative" })
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(mode, "relative".to_string(), "Relative");
+                                        ui.selectable_value(mode, "absolute".to_string(), "Absolute");
+                                    }).response
+                                    .on_hover_text("Relative (default): strip and fixture universes count from this universe, so universe 1 is sent on it and 2 on the next. \
+                                        Absolute: strip and fixture universes are sent as entered and this setting is ignored.");
+                                if *mode != before {
+                                    self.mark_state_changed();
+                                }
                             });
+                            if ui.checkbox(&mut self.state.network.reverse_universe_order, "Reverse universe order")
+                                .on_hover_text("For controllers that number their ports backwards: the strip with the lowest universe is sent on the highest and vice versa. \
+                                    Unlike a strip's data-in end, this doesn't change pixel order within a strip.")
+                                .changed()
+                            {
+                                self.mark_state_changed();
+                            }
                             
                             ui.checkbox(&mut self.state.network.use_multicast, "Multicast (Broadcast)");
                             
@@ -932,66 +2628,678 @@ impl eframe::App for MyApp {
                                     ui.label("IP Address");
                                     ui.text_edit_singleline(&mut self.state.network.unicast_ip);
                                 });
+                            } else {
+                                ui.horizontal(|ui| {
+                                    ui.label("Multicast TTL");
+                                    if ui.add(egui::DragValue::new(&mut self.state.network.multicast_ttl).clamp_range(1..=255))
+                                        .on_hover_text("1 keeps traffic on the local subnet. Raise it only when sACN must cross routers.")
+                                        .changed()
+                                    {
+                                        self.mark_state_changed();
+                                    }
+                                });
+                            }
+                            ui.horizontal(|ui| {
+                                ui.label("Source name");
+                                // Applied on commit: a new name rebuilds the sender
+                                let edit_id = ui.make_persistent_id("source_name_edit");
+                                let mut name = ui.data_mut(|d| d.get_temp::<String>(edit_id))
+                                    .unwrap_or_else(|| self.state.network.source_name.clone());
+                                let resp = ui.add(egui::TextEdit::singleline(&mut name).char_limit(model::SACN_SOURCE_NAME_MAX).desired_width(120.0))
+                                    .on_hover_text(format!("Name receivers and consoles show for this source. CID: {}", self.state.network.source_cid));
+                                if resp.changed() {
+                                    ui.data_mut(|d| d.insert_temp(edit_id, name.clone()));
+                                }
+                                if resp.lost_focus() {
+                                    self.state.network.source_name = name.trim().to_string();
+                                    ui.data_mut(|d| d.remove::<String>(edit_id));
+                                    self.mark_state_changed();
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label("Interface IP");
+                                // Edit a temp buffer and only apply on commit so the sender isn't rebuilt per keystroke
+                                let edit_id = ui.make_persistent_id("multicast_interface_edit");
+                                let mut iface = ui.data_mut(|d| d.get_temp::<String>(edit_id))
+                                    .unwrap_or_else(|| self.state.network.multicast_interface.clone().unwrap_or_default());
+                                let resp = ui.add(egui::TextEdit::singleline(&mut iface).hint_text("OS default").desired_width(120.0))
+                                    .on_hover_text("Local IPv4 address of the lighting network adapter. Leave blank to use the OS default route.");
+                                if resp.changed() {
+                                    ui.data_mut(|d| d.insert_temp(edit_id, iface.clone()));
+                                }
+                                if resp.lost_focus() {
+                                    let iface = iface.trim().to_string();
+                                    self.state.network.multicast_interface = if iface.is_empty() { None } else { Some(iface) };
+                                    ui.data_mut(|d| d.remove::<String>(edit_id));
+                                    self.mark_state_changed();
+                                }
+                            });
+                            // Redundancy: a second source on a separate adapter sends every universe again
+                            if ui.checkbox(&mut self.state.network.redundant_output, "Redundant output (backup network)")
+                                .on_hover_text("For show-critical rigs with two separate data networks. Every universe is also sent from a second adapter. \
+                                    Multicast goes out of both adapters; unicast universes go to the same node IPs on both paths.")
+                                .changed()
+                            {
+                                self.mark_state_changed();
+                            }
+                            if self.state.network.redundant_output {
+                                ui.horizontal(|ui| {
+                                    ui.label("Backup interface IP");
+                                    let edit_id = ui.make_persistent_id("redundant_interface_edit");
+                                    let mut iface = ui.data_mut(|d| d.get_temp::<String>(edit_id))
+                                        .unwrap_or_else(|| self.state.network.redundant_interface.clone().unwrap_or_default());
+                                    let resp = ui.add(egui::TextEdit::singleline(&mut iface).hint_text("Required").desired_width(120.0))
+                                        .on_hover_text("Local IPv4 address of the backup network adapter. Must differ from the primary interface.");
+                                    if resp.changed() {
+                                        ui.data_mut(|d| d.insert_temp(edit_id, iface.clone()));
+                                    }
+                                    if resp.lost_focus() {
+                                        let iface = iface.trim().to_string();
+                                        self.state.network.redundant_interface = if iface.is_empty() { None } else { Some(iface) };
+                                        ui.data_mut(|d| d.remove::<String>(edit_id));
+                                        self.mark_state_changed();
+                                    }
+                                });
+                                if !self.engine.output_stats().redundant_active {
+                                    ui.colored_label(egui::Color32::YELLOW, "⚠ Backup sender not running: check the backup interface IP");
+                                }
+                            }
+                            ui.horizontal(|ui| {
+                                ui.label("Max Universes");
+                                if ui.add(egui::DragValue::new(&mut self.state.network.max_universes).clamp_range(1..=63999))
+                                    .on_hover_text("Each registered universe holds network resources. Output for universes beyond this limit is not sent.")
+                                    .changed()
+                                {
+                                    self.mark_state_changed();
+                                }
+                            });
+                            if ui.button("🔄 Reset Output")
+                                .on_hover_text("Rebuild the sACN senders, re-register every universe and resync the beat clock. Try this before restarting when output stalls or a node shows stale data.")
+                                .clicked()
+                            {
+                                self.engine.reset_output();
+                                self.status = "Output reset: sACN senders rebuilt".into();
+                            }
+                            let registered = self.engine.registered_universe_count();
+                            let max = self.state.network.max_universes as usize;
+                            let dropped = self.engine.over_limit_universe_count();
+                            let text = format!("Registered universes: {} / {}", registered, max);
+                            if dropped > 0 {
+                                ui.colored_label(egui::Color32::RED, format!("{} ({} over limit, not sent)", text, dropped));
+                            } else if registered * 5 >= max * 4 {
+                                ui.colored_label(egui::Color32::YELLOW, format!("{} (approaching limit)", text));
+                            } else {
+                                ui.label(text);
+                            }
+
+                            ui.collapsing("Advanced: Per-universe routing", |ui| {
+                                ui.label("Overrides the multicast/unicast setting above for individual output universes.");
+                                let mut remove_route = None;
+                                let mut routes_changed = false;
+                                for (idx, route) in self.state.network.universe_routes.iter_mut().enumerate() {
+                                    ui.horizontal(|ui| {
+                                        routes_changed |= ui.add(egui::DragValue::new(&mut route.universe).prefix("Uni: ").clamp_range(1..=63999)).changed();
+                                        routes_changed |= ui.checkbox(&mut route.use_multicast, "Multicast").changed();
+                                        if !route.use_multicast {
+                                            routes_changed |= ui.add(egui::TextEdit::singleline(&mut route.unicast_ip).hint_text("Node IP").desired_width(110.0)).changed();
+                                        }
+                                        if ui.small_button("🗑").clicked() {
+                                            remove_route = Some(idx);
+                                        }
+                                    });
+                                }
+                                if let Some(idx) = remove_route {
+                                    self.state.network.universe_routes.remove(idx);
+                                    routes_changed = true;
+                                }
+                                // Two overrides for one universe are ambiguous (and collapse to one on save)
+                                let routes = &self.state.network.universe_routes;
+                                if routes.iter().enumerate().any(|(i, r)| routes[..i].iter().any(|o| o.universe == r.universe)) {
+                                    ui.colored_label(egui::Color32::YELLOW, "⚠ Duplicate universe overrides: keep only one per universe");
+                                }
+                                if ui.button("➕ Add Override").clicked() {
+                                    let next = self.state.network.universe_routes.iter().map(|r| r.universe).max().unwrap_or(0).saturating_add(1).max(1);
+                                    let unicast_ip = self.state.network.unicast_ip.clone();
+                                    self.state.network.universe_routes.push(model::UniverseRoute {
+                                        universe: next,
+                                        use_multicast: false,
+                                        unicast_ip,
+                                    });
+                                    routes_changed = true;
+                                }
+                                if routes_changed {
+                                    self.mark_state_changed();
+                                }
+                            });
+
+                            ui.collapsing("Universe output", |ui| {
+                                ui.label("Untick a universe to stop sending it (one black frame first). Strips stay as they are.");
+                                let mut universes = self.engine.registered_universes();
+                                universes.extend(self.state.network.muted_universes.iter().copied());
+                                universes.sort_unstable();
+                                universes.dedup();
+                                if universes.is_empty() {
+                                    ui.weak("No universes are being sent yet.");
+                                }
+                                let mut mute_changed = false;
+                                ui.horizontal_wrapped(|ui| {
+                                    for u in universes {
+                                        let mut enabled = !self.state.network.muted_universes.contains(&u);
+                                        if ui.checkbox(&mut enabled, format!("Uni {}", u)).changed() {
+                                            if enabled {
+                                                self.state.network.muted_universes.retain(|&m| m != u);
+                                            } else {
+                                                self.state.network.muted_universes.push(u);
+                                            }
+                                            mute_changed = true;
+                                        }
+                                    }
+                                });
+                                if !self.state.network.muted_universes.is_empty() {
+                                    ui.colored_label(egui::Color32::YELLOW, format!("⚠ {} universe(s) muted", self.state.network.muted_universes.len()));
+                                    if ui.button("Unmute all").clicked() {
+                                        self.state.network.muted_universes.clear();
+                                        mute_changed = true;
+                                    }
+                                }
+                                if mute_changed {
+                                    self.mark_state_changed();
+                                }
+                            });
+                        });
+
+                        ui.collapsing("Sync Diagnostics", |ui| {
+                            let diag = self.engine.sync_diagnostics();
+                            egui::Grid::new("sync_diagnostics").num_columns(2).show(ui, |ui| {
+                                ui.label("Link beat (raw)");
+                                ui.label(format!("{:.3}", diag.link_beat));
+                                ui.end_row();
+                                ui.label("Flywheel beat");
+                                ui.label(format!("{:.3}", diag.flywheel_beat));
+                                ui.end_row();
+                                ui.label("Drift (flywheel - Link)");
+                                let drift = diag.flywheel_beat - diag.link_beat;
+                                if diag.link_peers == 0 {
+                                    ui.weak("n/a (no Link peers)");
+                                } else if drift.abs() > 0.1 {
+                                    ui.colored_label(egui::Color32::YELLOW, format!("{:+.3} beats", drift));
+                                } else {
+                                    ui.label(format!("{:+.3} beats", drift));
+                                }
+                                ui.end_row();
+                                ui.label("Flywheel");
+                                if diag.in_sync {
+                                    ui.label("In sync");
+                                } else {
+                                    ui.colored_label(egui::Color32::RED, format!("Out of sync for {:.1}s", diag.sync_error_secs));
+                                }
+                                ui.end_row();
+                                ui.label("Audio phase offset");
+                                ui.label(format!("{:+.3} beats", diag.phase_offset));
+                                ui.end_row();
+                                ui.label("Last audio phase error");
+                                ui.label(format!("{:+.3} beats", diag.last_phase_error));
+                                ui.end_row();
+                            });
+                        });
+
+                        ui.collapsing("Output Stats", |ui| {
+                            let stats = self.engine.output_stats();
+                            egui::Grid::new("output_stats").num_columns(2).show(ui, |ui| {
+                                ui.label("Render FPS");
+                                ui.label(format!("{:.1}", stats.fps));
+                                ui.end_row();
+                                ui.label("Packets/sec");
+                                ui.label(format!("{:.0}", stats.packets_per_sec));
+                                ui.end_row();
+                                ui.label("Bandwidth");
+                                ui.label(format!("{:.1} kB/s", stats.bytes_per_sec / 1000.0));
+                                ui.end_row();
+                                ui.label("Universes");
+                                ui.label(stats.universes.to_string());
+                                ui.end_row();
+                                ui.label("Send errors");
+                                if stats.send_errors > 0 {
+                                    ui.colored_label(egui::Color32::RED, stats.send_errors.to_string());
+                                } else {
+                                    ui.label("0");
+                                }
+                                ui.end_row();
+                                if stats.redundant_active {
+                                    ui.label("Backup send errors");
+                                    if stats.redundant_send_errors > 0 {
+                                        ui.colored_label(egui::Color32::RED, stats.redundant_send_errors.to_string());
+                                    } else {
+                                        ui.label("0");
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                        });
+
+                        ui.collapsing("Test Patterns", |ui| {
+                            ui.label("Overrides every scene while active. Use it to check color order, pixel counts and addressing on the rig.");
+                            ui.horizontal_wrapped(|ui| {
+                                for (kind, label) in engine::TEST_PATTERNS {
+                                    let active = self.engine.test_pattern.as_deref() == Some(kind);
+                                    if ui.selectable_label(active, label).clicked() {
+                                        self.engine.test_pattern = if active { None } else { Some(kind.to_string()) };
+                                    }
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                if ui.add_enabled(self.engine.test_pattern.is_some(), egui::Button::new("Stop")).clicked() {
+                                    self.engine.test_pattern = None;
+                                }
+                                if let Some(step) = self.engine.color_order_step() {
+                                    ui.label(format!("Sending: {} (every strip should show pure {})", step, step.to_lowercase()));
+                                }
+                            });
+                        });
+
+                        ui.collapsing("Record/Playback", |ui| {
+                            ui.label("Record the output exactly as sent, then play it back on its own timing without scenes, audio or Link.");
+                            ui.horizontal(|ui| {
+                                match self.engine.recording_progress() {
+                                    Some((frames, secs)) => {
+                                        ui.colored_label(egui::Color32::RED, format!("⏺ {:.0}s, {} frames", secs, frames));
+                                        if ui.button("⏹ Stop recording").clicked() {
+                                            self.status = match self.engine.stop_recording() {
+                                                Ok(frames) => format!("Recorded {} frames", frames.unwrap_or(0)),
+                                                Err(e) => format!("Failed to finish recording: {}", e),
+                                            };
+                                        }
+                                    }
+                                    None => {
+                                        if ui.button("⏺ Record...").clicked() {
+                                            if let Some(path) = rfd::FileDialog::new()
+                                                .add_filter("Lightspeed recording", &["lsrec"])
+                                                .set_file_name("show.lsrec")
+                                                .save_file()
+                                            {
+                                                self.status = match self.engine.start_recording(&path) {
+                                                    Ok(()) => format!("Recording to {}", path.display()),
+                                                    Err(e) => format!("Failed to start recording: {}", e),
+                                                };
+                                            }
+                                        }
+                                    }
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                match self.engine.playback_elapsed() {
+                                    Some(secs) => {
+                                        ui.colored_label(egui::Color32::LIGHT_GREEN, format!("▶ {:.0}s", secs));
+                                        if ui.button("⏹ Stop playback").clicked() {
+                                            self.engine.stop_playback();
+                                            self.status = "Playback stopped".into();
+                                        }
+                                    }
+                                    None => {
+                                        if ui.button("▶ Play...").clicked() {
+                                            if let Some(path) = rfd::FileDialog::new()
+                                                .add_filter("Lightspeed recording", &["lsrec"])
+                                                .pick_file()
+                                            {
+                                                self.status = match self.engine.start_playback(&path, self.playback_loop) {
+                                                    Ok(()) => format!("Playing {}", path.display()),
+                                                    Err(e) => format!("Failed to play recording: {}", e),
+                                                };
+                                            }
+                                        }
+                                    }
+                                }
+                                if ui.checkbox(&mut self.playback_loop, "Loop").changed() {
+                                    self.engine.set_playback_looping(self.playback_loop);
+                                }
+                            });
+                            if self.engine.playback_elapsed().is_some() {
+                                ui.label("Playback replaces the live render on the wire; the canvas still shows the scene.");
+                            }
+                        });
+                        
+                        ui.separator();
+
+                        // Scenes UI will be shown after Strips to keep Strips on top
+
+                        // STRIPS
+                        ui.horizontal(|ui| {
+                            ui.heading("Strips");
+                            if ui.button("➕ Add Strip").clicked() {
+                                let mut s = PixelStrip::default();
+                                s.id = rand::random();
+                                self.state.strips.push(s);
+                                self.mark_state_changed();
+                            }
+                            if ui.add_enabled(!self.state.strips.is_empty(), egui::Button::new("📐 Auto-pack"))
+                                .on_hover_text("Re-patch every strip back to back, spilling into the next universe when one is full")
+                                .clicked()
+                            {
+                                self.auto_pack_preview = Some(model::auto_pack_strips(&self.state.strips));
+                            }
+                            if self.auto_pack_undo.is_some() && ui.button("↺ Undo auto-pack").clicked() {
+                                if let Some(patch) = self.auto_pack_undo.take() {
+                                    apply_strip_patch(&mut self.state.strips, &patch);
+                                    self.status = "Restored the patch from before auto-pack".into();
+                                    self.mark_state_changed();
+                                }
+                            }
+                        });
+                        
+                        let mut delete_strip_idx = None;
+                        let mut lut_status: Option<String> = None;
+                        let mut preset_applied = false;
+                        let mut save_preset: Option<StripPreset> = None;
+                        let mut check_strip = None;
+                        let mut delete_preset: Option<String> = None;
+                        let builtin_presets = StripPreset::builtins();
+                        let strip_universe_range = self.state.strip_universe_range();
+                        let network = self.state.network.clone();
+                        let channel_base = self.state.channel_base();
+                        let unit_meters = self.state.canvas_unit_meters;
+                        for (idx, s) in self.state.strips.iter_mut().enumerate() {
+                            ui.push_id(s.id, |ui| {
+                                ui.collapsing(format!("Strip::{}", s.id), |ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Position:");
+                                        ui.add(egui::DragValue::new(&mut s.x).speed(0.01).prefix("X: "));
+                                        ui.add(egui::DragValue::new(&mut s.y).speed(0.01).prefix("Y: "));
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Data in:");
+                                        ui.radio_value(&mut s.flipped, false, "Left end");
+                                        ui.radio_value(&mut s.flipped, true, "Right end");
+                                    }).response.on_hover_text("Which physical end the controller feeds (pixel 0). Marked with the cyan square on the canvas.");
+                                    ui.horizontal(|ui| {
+                                        ui.label("Config:");
+                                        ui.add(egui::DragValue::new(&mut s.universe).prefix("Uni: ").clamp_range(1..=63999));
+                                        channel_drag(ui, &mut s.start_channel, channel_base);
+                                        let sent_on = network.effective_universe(s.universe, strip_universe_range);
+                                        if sent_on != s.universe {
+                                            ui.weak(format!("→ sent on {}", sent_on))
+                                                .on_hover_text("After reverse universe order and the relative universe offset (Network Output)");
+                                        }
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Prefix:")
+                                            .on_hover_text("Static channel values sent at the start channel, before the pixel data. For bars that expect a leading control block such as a master dimmer.");
+                                        let mut remove = None;
+                                        for (i, value) in s.channel_prefix.iter_mut().enumerate() {
+                                            ui.add(egui::DragValue::new(value).clamp_range(0..=255));
+                                            if ui.small_button("✖").on_hover_text("Remove channel").clicked() {
+                                                remove = Some(i);
+                                            }
+                                        }
+                                        if let Some(i) = remove {
+                                            s.channel_prefix.remove(i);
+                                        }
+                                        if s.channel_prefix.len() < model::MAX_CHANNEL_PREFIX && ui.small_button("➕").on_hover_text("Add a prefix channel").clicked() {
+                                            s.channel_prefix.push(255);
+                                        }
+                                        let first = s.start_channel - 1 + channel_base;
+                                        ui.weak(format!("ch {}-{}", first, first as usize + s.channel_span() - 1));
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Run offset:");
+                                        ui.add(egui::DragValue::new(&mut s.pixel_offset).suffix(" px").clamp_range(0..=65535));
+                                    }).response.on_hover_text("Where this strip starts within a longer run split across controllers. Chases and patterns continue from here, and Pulse Wave spans every offset strip it targets as one run.");
+                                    ui.horizontal(|ui| {
+                                        ui.label("Strip type:");
+                                        egui::ComboBox::from_id_source(format!("strip_preset_{}", s.id))
+                                            .selected_text("Apply preset...")
+                                            .show_ui(ui, |ui| {
+                                                for preset in &builtin_presets {
+                                                    if ui.selectable_label(false, &preset.name).clicked() {
+                                                        preset.apply(s);
+                                                        preset_applied = true;
+                                                    }
+                                                }
+                                                if !self.strip_presets.is_empty() {
+                                                    ui.separator();
+                                                }
+                                                for preset in &self.strip_presets {
+                                                    ui.horizontal(|ui| {
+                                                        if ui.selectable_label(false, &preset.name).clicked() {
+                                                            preset.apply(s);
+                                                            preset_applied = true;
+                                                        }
+                                                        if ui.small_button("🗑").on_hover_text("Delete this preset").clicked() {
+                                                            delete_preset = Some(preset.name.clone());
+                                                        }
+                                                    });
+                                                }
+                                            });
+                                        ui.add(egui::TextEdit::singleline(&mut self.strip_preset_name).hint_text("Preset name").desired_width(100.0));
+                                        let name = self.strip_preset_name.trim();
+                                        if ui.add_enabled(!name.is_empty(), egui::Button::new("Save type"))
+                                            .on_hover_text("Save this strip's count, spacing and color order as a reusable preset")
+                                            .clicked()
+                                        {
+                                            save_preset = Some(StripPreset::from_strip(name, s));
+                                        }
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Layout:");
+                                        if ui.add(egui::DragValue::new(&mut s.pixel_count).prefix("Count: ").clamp_range(1..=model::MAX_STRIP_PIXELS)).changed() {
+                                            // Resizes `data` now, so the canvas doesn't draw the old length until the next frame
+                                            s.sanitize();
+                                        }
+                                        ui.add(egui::Slider::new(&mut s.spacing, 0.001..=0.05).text("Spacing"));
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Physical:");
+                                        if unit_meters <= 0.0 {
+                                            ui.weak("Set meters per canvas unit (⚙) to size strips physically");
+                                            return;
+                                        }
+                                        let solve = self.strip_layout_solve.clone();
+                                        let mut count = s.pixel_count;
+                                        let mut length_m = s.pixel_count as f32 * s.spacing * unit_meters;
+                                        let mut pitch_mm = s.spacing * unit_meters * 1000.0;
+                                        // The solved quantity shows its result but can't be edited
+                                        let mut edited = ui.add_enabled(solve != "count", egui::DragValue::new(&mut count)
+                                            .prefix("Count: ").clamp_range(1..=model::MAX_STRIP_PIXELS)).changed();
+                                        edited |= ui.add_enabled(solve != "length", egui::DragValue::new(&mut length_m)
+                                            .suffix(" m").speed(0.01).clamp_range(0.001..=1000.0)).changed();
+                                        edited |= ui.add_enabled(solve != "pitch", egui::DragValue::new(&mut pitch_mm)
+                                            .suffix(" mm pitch").speed(0.1).clamp_range(0.1..=10_000.0)).changed();
+                                        if edited {
+                                            let (count, pitch_m) = solve_strip_layout(&solve, count, length_m, pitch_mm / 1000.0);
+                                            s.pixel_count = count;
+                                            s.spacing = pitch_m / unit_meters;
+                                            s.sanitize();
+                                        }
+                                        ui.weak(format!("{:.0} LEDs/m", 1000.0 / pitch_mm));
+                                        egui::ComboBox::from_id_source(format!("layout_solve_{}", s.id))
+                                            .selected_text(format!("Solve {}", self.strip_layout_solve))
+                                            .show_ui(ui, |ui| {
+                                                for q in ["count", "length", "pitch"] {
+                                                    ui.selectable_value(&mut self.strip_layout_solve, q.to_string(), q);
+                                                }
+                                            })
+                                            .response
+                                            .on_hover_text("Which one follows from the other two: count × pitch = length");
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Protocol:");
+                                        ui.selectable_value(&mut s.channels_per_pixel, 3, "Color")
+                                            .on_hover_text("Three channels per pixel");
+                                        ui.selectable_value(&mut s.channels_per_pixel, 1, "Mono")
+                                            .on_hover_text("One brightness channel per pixel, for dimmers and white-only fixtures. The rendered color is sent as its luma.");
+                                        if s.channels_per_pixel == 3 {
+                                            egui::ComboBox::from_id_source(format!("proto_{}", s.id))
+                                                .selected_text(&s.color_order)
+                                                .show_ui(ui, |ui| {
+                                                    for order in model::COLOR_ORDERS {
+                                                        ui.selectable_value(&mut s.color_order, order.to_string(), order);
+                                                    }
+                                                });
+                                            if ui.button("🔍 Check").on_hover_text("Send pure red, green and blue to this strip and confirm what it shows").clicked() {
+                                                check_strip = Some(s.id);
+                                            }
+                                        }
+                                    });
+                                    if s.channels_per_pixel == 1 {
+                                        ui.horizontal(|ui| {
+                                            ui.label("Luma:").on_hover_text("How much red, green and blue each count toward brightness. Only the ratio matters; white is always full.");
+                                            for (weight, prefix) in s.luma_weights.iter_mut().zip(["R ", "G ", "B "]) {
+                                                ui.add(egui::DragValue::new(weight).speed(0.01).clamp_range(0.0..=1.0).prefix(prefix));
+                                            }
+                                            if ui.small_button("Reset").on_hover_text("Rec. 709 weights").clicked() {
+                                                s.luma_weights = model::default_luma_weights();
+                                            }
+                                        });
+                                    }
+                                    ui.horizontal(|ui| {
+                                        ui.label("Dimmer curve:");
+                                        let curve_label = |curve: &str| model::DIMMER_CURVES.iter()
+                                            .find(|(name, _)| *name == curve)
+                                            .map_or("Linear", |(_, label)| *label);
+                                        egui::ComboBox::from_id_source(format!("dimmer_curve_{}", s.id))
+                                            .selected_text(curve_label(&s.dimmer_curve))
+                                            .show_ui(ui, |ui| {
+                                                for (name, label) in model::DIMMER_CURVES {
+                                                    ui.selectable_value(&mut s.dimmer_curve, name.to_string(), label);
+                                                }
+                                            })
+                                            .response
+                                            .on_hover_text("How this strip's driver responds to level. Pick the curve that makes fades look even next to other fixtures.");
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label("Calibration:");
+                                        ui.label(if s.lut.is_some() { "Custom LUT" } else { "Identity" });
+                                        if ui.button("📂 Load LUT...").on_hover_text("Text file with 256 lines of \"R G B\" output values (0-255)").clicked() {
+                                            if let Some(path) = rfd::FileDialog::new()
+                                                .add_filter("LUT", &["txt", "csv", "lut"])
+                                                .pick_file()
+                                            {
+                                                let parsed = fs::read_to_string(&path)
+                                                    .map_err(anyhow::Error::from)
+                                                    .and_then(|text| model::ColorLut::parse(&text));
+                                                match parsed {
+                                                    Ok(lut) => {
+                                                        s.lut = Some(lut);
+                                                        lut_status = Some(format!("Loaded LUT from {}", path.display()));
+                                                    }
+                                                    Err(e) => {
+                                                        // Keep the previous LUT on a bad file
+                                                        lut_status = Some(format!("Invalid LUT file: {}", e));
+                                                    }
+                                                }
+                                            }
+                                        }
+                                        if s.lut.is_some() && ui.button("Reset").clicked() {
+                                            s.lut = None;
+                                            lut_status = Some("LUT reset to identity".into());
+                                        }
+                                    });
+                                    
+                                    if ui.button("🗑 Delete Strip").clicked() {
+                                        delete_strip_idx = Some(idx);
+                                    }
+                                });
+                            });
+                        }
+                        if let Some(idx) = delete_strip_idx {
+                            self.state.remove_strip(idx);
+                        }
+                        if let Some(msg) = lut_status {
+                            self.status = msg;
+                            self.mark_state_changed();
+                        }
+                        if preset_applied {
+                            self.mark_state_changed();
+                        }
+                        if let Some(id) = check_strip {
+                            self.color_check = Some((id, Vec::new()));
+                        }
+                        if let Some(preset) = save_preset {
+                            match self.db.save_strip_preset(&preset) {
+                                Ok(()) => {
+                                    self.status = format!("Saved strip type '{}'", preset.name);
+                                    self.strip_presets.retain(|p| p.name != preset.name);
+                                    self.strip_presets.push(preset);
+                                    self.strip_presets.sort_by(|a, b| a.name.cmp(&b.name));
+                                    self.strip_preset_name.clear();
+                                }
+                                Err(e) => self.status = format!("Failed to save strip type: {}", e),
                             }
-                        });
-                        
-                        ui.separator();
+                        }
+                        if let Some(name) = delete_preset {
+                            match self.db.delete_strip_preset(&name) {
+                                Ok(()) => self.strip_presets.retain(|p| p.name != name),
+                                Err(e) => self.status = format!("Failed to delete strip type: {}", e),
+                            }
+                        }
 
-                        // Scenes UI will be shown after Strips to keep Strips on top
+                        ui.separator();
 
-                        // STRIPS
+                        // FIXTURES (conventional DMX, rendered as a single pixel)
                         ui.horizontal(|ui| {
-                            ui.heading("Strips");
-                            if ui.button("➕ Add Strip").clicked() {
-                                let mut s = PixelStrip::default();
-                                s.id = rand::random();
-                                self.state.strips.push(s);
+                            ui.heading("Fixtures");
+                            if ui.button("➕ Add Fixture").clicked() {
+                                self.state.fixtures.push(model::Fixture { id: rand::random(), ..Default::default() });
                                 self.mark_state_changed();
                             }
                         });
-                        
-                        let mut delete_strip_idx = None;
-                        for (idx, s) in self.state.strips.iter_mut().enumerate() {
-                            ui.push_id(s.id, |ui| {
-                                ui.collapsing(format!("Strip::{}", s.id), |ui| {
+
+                        let mut delete_fixture_idx = None;
+                        let mut fixture_changed = false;
+                        for (idx, f) in self.state.fixtures.iter_mut().enumerate() {
+                            ui.push_id(f.id, |ui| {
+                                let [r, g, b] = f.color;
+                                ui.collapsing(format!("Fixture::{}", f.name), |ui| {
                                     ui.horizontal(|ui| {
-                                        ui.label("Position:");
-                                        ui.add(egui::DragValue::new(&mut s.x).speed(0.01).prefix("X: "));
-                                        ui.add(egui::DragValue::new(&mut s.y).speed(0.01).prefix("Y: "));
+                                        ui.label("Name:");
+                                        fixture_changed |= ui.text_edit_singleline(&mut f.name).changed();
                                     });
                                     ui.horizontal(|ui| {
-                                        ui.label("Direction:");
-                                        ui.checkbox(&mut s.flipped, "Flip 180°");
+                                        ui.label("Position:");
+                                        fixture_changed |= ui.add(egui::DragValue::new(&mut f.x).speed(0.01).prefix("X: ")).changed();
+                                        fixture_changed |= ui.add(egui::DragValue::new(&mut f.y).speed(0.01).prefix("Y: ")).changed();
                                     });
                                     ui.horizontal(|ui| {
                                         ui.label("Config:");
-                                        ui.add(egui::DragValue::new(&mut s.universe).prefix("Uni: ").clamp_range(1..=63999));
-                                        ui.add(egui::DragValue::new(&mut s.start_channel).prefix("Ch: "));
-                                    });
-                                    ui.horizontal(|ui| {
-                                        ui.label("Layout:");
-                                        ui.add(egui::DragValue::new(&mut s.pixel_count).prefix("Count: "));
-                                        ui.add(egui::Slider::new(&mut s.spacing, 0.001..=0.05).text("Spacing"));
+                                        fixture_changed |= ui.add(egui::DragValue::new(&mut f.universe).prefix("Uni: ").clamp_range(1..=63999)).changed();
+                                        fixture_changed |= channel_drag(ui, &mut f.start_channel, channel_base).changed();
                                     });
                                     ui.horizontal(|ui| {
-                                        ui.label("Protocol:");
-                                        egui::ComboBox::from_id_source(format!("proto_{}", s.id))
-                                            .selected_text(&s.color_order)
+                                        ui.label("Channels:");
+                                        let current = model::FIXTURE_TEMPLATES.iter()
+                                            .find(|(_, layout)| f.channels.iter().map(String::as_str).eq(layout.iter().copied()))
+                                            .map(|(name, _)| *name)
+                                            .unwrap_or("Custom");
+                                        egui::ComboBox::from_id_source(format!("fixture_layout_{}", f.id))
+                                            .selected_text(current)
                                             .show_ui(ui, |ui| {
-                                                ui.selectable_value(&mut s.color_order, "RGB".to_string(), "RGB");
-                                                ui.selectable_value(&mut s.color_order, "GRB".to_string(), "GRB");
-                                                ui.selectable_value(&mut s.color_order, "BGR".to_string(), "BGR");
+                                                for (name, layout) in model::FIXTURE_TEMPLATES {
+                                                    if ui.selectable_label(current == name, name).clicked() {
+                                                        f.channels = layout.iter().map(|c| c.to_string()).collect();
+                                                        fixture_changed = true;
+                                                    }
+                                                }
                                             });
+                                        ui.label(format!("{} ch", f.channels.len()));
                                     });
-                                    
-                                    if ui.button("🗑 Delete Strip").clicked() {
-                                        delete_strip_idx = Some(idx);
+                                    ui.horizontal(|ui| {
+                                        ui.label("Output:");
+                                        let (swatch, _) = ui.allocate_exact_size(egui::vec2(16.0, 16.0), egui::Sense::hover());
+                                        ui.painter().rect_filled(swatch, 2.0, egui::Color32::from_rgb(r, g, b));
+                                        ui.label(format!("{:?}", f.channel_values()));
+                                    });
+
+                                    if ui.button("🗑 Delete Fixture").clicked() {
+                                        delete_fixture_idx = Some(idx);
                                     }
                                 });
                             });
                         }
-                        if let Some(idx) = delete_strip_idx {
-                            self.state.strips.remove(idx);
+                        if let Some(idx) = delete_fixture_idx {
+                            self.state.fixtures.remove(idx);
+                            fixture_changed = true;
+                        }
+                        if fixture_changed {
+                            self.mark_state_changed();
                         }
 
                         ui.separator();
@@ -1026,6 +3334,18 @@ impl eframe::App for MyApp {
                             vec![]
                         };
 
+                        // Scene pages: the list and the Launchpad grid show one page at a time
+                        ui.horizontal_wrapped(|ui| {
+                            ui.label("Page:");
+                            for page in 0..model::SCENE_PAGES {
+                                let count = self.state.scenes.iter().filter(|s| s.page == page).count();
+                                let label = if count > 0 { format!("{} ({})", page + 1, count) } else { (page + 1).to_string() };
+                                if ui.selectable_label(self.state.launchpad_page == page, label).clicked() {
+                                    self.set_launchpad_page(page);
+                                }
+                            }
+                        }).response.on_hover_text("The Launchpad's up/down arrows also switch pages");
+
                         // Filters for main panel
                         if !self.state.scenes.is_empty() {
 
@@ -1076,22 +3396,27 @@ impl eframe::App for MyApp {
                                 ui.horizontal(|ui| {
                                     ui.selectable_value(&mut self.new_scene_kind, "Masks".into(), "Masks");
                                     ui.selectable_value(&mut self.new_scene_kind, "Global".into(), "Global effect");
+                                    ui.selectable_value(&mut self.new_scene_kind, "Blackout".into(), "Blackout");
                                 });
                                 ui.horizontal(|ui| {
                                     if ui.button("Create").clicked() {
                                         let id = rand::random();
-                                        let scene = if self.new_scene_kind == "Masks" {
+                                        let scene = if self.new_scene_kind == "Masks" || self.new_scene_kind == "Blackout" {
                                             model::Scene {
                                                 id,
                                                 name: self.new_scene_name.clone(),
-                                                kind: "Masks".into(),
+                                                kind: self.new_scene_kind.clone(),
                                                 category: self.new_scene_category.clone(),
                                                 masks: vec![],
                                                 global: None,
                                                 global_effects: vec![],
                                                 launchpad_btn: None,
                                                 launchpad_color: None,
-                                                launchpad_is_cc: false
+                                                launchpad_is_cc: false,
+                                                master_intensity: 1.0,
+                                                compositing: "add".into(),
+                                                page: self.state.launchpad_page,
+                                                audio_reactive: true,
                                             }
                                         } else {
                                             let mut ge = model::GlobalEffect::default();
@@ -1109,7 +3434,11 @@ impl eframe::App for MyApp {
                                                  }],
                                                  launchpad_btn: None,
                                                  launchpad_color: None,
-                                                 launchpad_is_cc: false
+                                                 launchpad_is_cc: false,
+                                                master_intensity: 1.0,
+                                                compositing: "add".into(),
+                                                page: self.state.launchpad_page,
+                                                audio_reactive: true,
                                             }
                                         };
                                         self.state.scenes.push(scene);
@@ -1127,19 +3456,28 @@ impl eframe::App for MyApp {
                         let mut swap_request: Option<(usize, usize)> = None;
                         let mut floating_scene: Option<model::Scene> = None;
                         let mut needs_save = false;
+                        let mut refresh_pads = false;
                         let sender = self.midi_sender.clone();
+                        let current_page = self.state.launchpad_page;
+                        let live_locked = self.live_scene_locked();
+                        let life_clock = (self.engine.get_time() as f64, self.engine.get_beat());
+                        let submaster_cc = self.state.cc_for("submaster");
+                        let scene_ccs: std::collections::HashMap<u64, u8> = self.state.cc_mappings.iter()
+                            .filter_map(|m| Some((m.target.strip_prefix("scene:")?.parse().ok()?, m.cc)))
+                            .collect();
+                        let mut unlock_live = None;
                         
                         // Pre-calculate dragged index to avoid borrow issues
                         let dragged_scene_index = self.dragged_scene_id.and_then(|id| {
                             self.state.scenes.iter().position(|s| s.id == id)
                         });
 
-                        // Collect used IDs to prevent duplicates
+                        // Collect used IDs to prevent duplicates (buttons are per page)
                         let mut used_ids = std::collections::HashMap::new();
                         for s in &self.state.scenes {
                             if let Some(btn) = s.launchpad_btn {
                                 if btn != 0 {
-                                    used_ids.insert((s.launchpad_is_cc, btn), s.id);
+                                    used_ids.insert((s.page, s.launchpad_is_cc, btn), s.id);
                                 }
                             }
                         }
@@ -1148,6 +3486,10 @@ impl eframe::App for MyApp {
                         let available_strips: Vec<(u64, usize)> = self.state.strips.iter().enumerate().map(|(i, s)| (s.id, i)).collect();
 
                         for (si, scene) in self.state.scenes.iter_mut().enumerate() {
+                            if scene.page != current_page {
+                                continue;
+                            }
+
                             // Apply category filter
                             if let Some(ref filter) = self.main_scenes_category_filter {
                                 if &scene.category != filter {
@@ -1200,9 +3542,10 @@ impl eframe::App for MyApp {
                                         if ui.selectable_label(selected, &scene.name).clicked() {
                                             self.state.selected_scene_id = Some(scene.id);
                                         }
-                                        ui.text_edit_singleline(&mut scene.name);
+                                        let locked = selected && live_locked;
+                                        ui.add_enabled(!locked, egui::TextEdit::singleline(&mut scene.name));
                                         if ui.button("📋").on_hover_text("Duplicate").clicked() { duplicate_scene_idx = Some(si); }
-                                        if ui.button("X").clicked() { delete_scene_idx = Some(si); }
+                                        if ui.add_enabled(!locked, egui::Button::new("X")).clicked() { delete_scene_idx = Some(si); }
                                     });
                                     Some(inner_resp.response.rect)
                                 };
@@ -1219,6 +3562,62 @@ impl eframe::App for MyApp {
                                 if !is_being_dragged {
                                     let selected = self.state.selected_scene_id == Some(scene.id);
                                     if selected {
+                                // Scene submaster
+                                ui.horizontal(|ui| {
+                                    ui.label("Intensity:");
+                                    if ui.add(egui::Slider::new(&mut scene.master_intensity, 0.0..=1.0)).changed() {
+                                        needs_save = true;
+                                    }
+                                    let ccs: Vec<String> = scene_ccs.get(&scene.id).into_iter().chain(submaster_cc.as_ref())
+                                        .map(|cc| format!("CC {}", cc))
+                                        .collect();
+                                    if !ccs.is_empty() {
+                                        ui.weak(ccs.join(", "));
+                                    }
+                                });
+                                // Performance mode: everything below edits the live output
+                                if live_locked {
+                                    ui.horizontal(|ui| {
+                                        ui.colored_label(egui::Color32::from_rgb(255, 160, 0), "🔒 Live scene locked");
+                                        if ui.button("Edit anyway").on_hover_text("Unlock this scene until you switch scenes").clicked() {
+                                            unlock_live = Some(Some(scene.id));
+                                        }
+                                    });
+                                    ui.set_enabled(false);
+                                } else if self.state.performance_mode {
+                                    ui.horizontal(|ui| {
+                                        ui.colored_label(egui::Color32::YELLOW, "⚠ Editing the live scene");
+                                        if ui.button("🔒 Relock").clicked() {
+                                            unlock_live = Some(None);
+                                        }
+                                    });
+                                }
+                                // How masks layer against the scene's global effects
+                                if scene.kind != "Blackout" {
+                                    ui.horizontal(|ui| {
+                                        ui.label("Layering:");
+                                        let label = |v: &str| match v {
+                                            "replace" => "Masks replace base",
+                                            "base_over" => "Base over masks",
+                                            _ => "Masks add on top",
+                                        };
+                                        egui::ComboBox::from_id_source(format!("scene_comp_{}", scene.id))
+                                            .selected_text(label(&scene.compositing))
+                                            .show_ui(ui, |ui| {
+                                                for v in ["add", "replace", "base_over"] {
+                                                    if ui.selectable_value(&mut scene.compositing, v.to_string(), label(v)).changed() {
+                                                        needs_save = true;
+                                                    }
+                                                }
+                                            });
+                                    }).response.on_hover_text("Only matters when the scene has both global effects and masks.");
+                                    if ui.checkbox(&mut scene.audio_reactive, "🎵 Audio reactive")
+                                        .on_hover_text("Off: burst, audio color and audio-modulated masks ignore the input and render their static look")
+                                        .changed()
+                                    {
+                                        needs_save = true;
+                                    }
+                                }
                                 // Category Editor
                                 ui.horizontal(|ui| {
                                     ui.label("Category:");
@@ -1276,7 +3675,7 @@ impl eframe::App for MyApp {
                                                     for col in 0..8 {
                                                         let note = ((8 - row) * 10 + 1 + col) as u8;
                                                         // Check if already used by another scene
-                                                        let is_used = if let Some(&owner) = used_ids.get(&(false, note)) {
+                                                        let is_used = if let Some(&owner) = used_ids.get(&(scene.page, false, note)) {
                                                             owner != scene.id
                                                         } else {
                                                             false
@@ -1323,8 +3722,29 @@ impl eframe::App for MyApp {
                                         }
                                         needs_save = true;
                                     }
+
+                                    let mut page = scene.page;
+                                    egui::ComboBox::from_id_source(format!("scene_page_{}", scene.id))
+                                        .selected_text(format!("Page {}", page + 1))
+                                        .show_ui(ui, |ui| {
+                                            for p in 0..model::SCENE_PAGES {
+                                                ui.selectable_value(&mut page, p, format!("Page {}", p + 1));
+                                            }
+                                        });
+                                    if page != scene.page {
+                                        // Keep the pad only if it's free on the new page
+                                        if let Some(btn) = scene.launchpad_btn {
+                                            if used_ids.contains_key(&(page, scene.launchpad_is_cc, btn)) {
+                                                scene.launchpad_btn = None;
+                                            }
+                                        }
+                                        scene.page = page;
+                                        refresh_pads = true;
+                                        needs_save = true;
+                                    }
                                 });
-                                if scene.kind == "Global" {
+                                if scene.kind != "Blackout" {
+                                    // Base layer: global effects render first, masks draw on top
                                     ui.horizontal(|ui| {
                                         ui.label("Global Effects:");
                                         if ui.button("➕ Add Effect").clicked() {
@@ -1401,6 +3821,7 @@ impl eframe::App for MyApp {
                                                             ui.selectable_value(&mut config.effect.kind, "GlitchSparkle".into(), "Glitch Sparkle");
                                                             ui.selectable_value(&mut config.effect.kind, "PulseWave".into(), "Pulse Wave");
                                                             ui.selectable_value(&mut config.effect.kind, "ZoneAlternate".into(), "Zone Alternate");
+                                                            ui.selectable_value(&mut config.effect.kind, "Comet".into(), "Comet");
                                                         });
                                                         
                                                     if ui.button("🗑").clicked() {
@@ -1422,6 +3843,8 @@ impl eframe::App for MyApp {
                                                     if color_picker(ui, &mut color, format!("ge_sol_{}_{}", scene.id, eff_idx)) {
                                                         ge.params.insert("color".into(), serde_json::json!([color[0], color[1], color[2]]));
                                                     }
+                                                    ui.label("Breathing:").on_hover_text("Modulate brightness; unipolar mode dips from full, bipolar is clipped at full");
+                                                    lfo_controls(ui, &mut ge.params, "intensity", format!("sol_lfo_{}_{}", scene.id, eff_idx));
                                                 } else if ge.kind == "Flash" {
                                                     ui.horizontal(|ui| {
                                                         ui.label("Color:");
@@ -1433,15 +3856,7 @@ impl eframe::App for MyApp {
                                                     ui.horizontal(|ui| {
                                                         ui.label("Rate:");
                                                         let mut rate = ge.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1 Bar").to_string();
-                                                        egui::ComboBox::from_id_source("rate")
-                                                            .selected_text(rate.clone())
-                                                            .show_ui(ui, |ui| {
-                                                                ui.selectable_value(&mut rate, "4 Bar".into(), "4 Bar");
-                                                                ui.selectable_value(&mut rate, "1 Bar".into(), "1 Bar");
-                                                                ui.selectable_value(&mut rate, "1/2".into(), "1/2");
-                                                                ui.selectable_value(&mut rate, "1/4".into(), "1/4");
-                                                                ui.selectable_value(&mut rate, "1/8".into(), "1/8");
-                                                            });
+                                                        rate_combo(ui, &mut rate, "rate");
                                                         if rate != ge.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1 Bar") {
                                                             ge.params.insert("rate".into(), serde_json::json!(rate));
                                                         }
@@ -1450,6 +3865,22 @@ impl eframe::App for MyApp {
                                                     if ui.add(egui::Slider::new(&mut decay, 0.1..=20.0).text("Decay")).changed() {
                                                         ge.params.insert("decay".into(), decay.into());
                                                     }
+                                                    ui.horizontal(|ui| {
+                                                        ui.label("Trigger:");
+                                                        let mut trigger = ge.params.get("trigger").and_then(|v| v.as_str()).unwrap_or("continuous").to_string();
+                                                        egui::ComboBox::from_id_source(format!("ge_fl_trig_{}_{}", scene.id, eff_idx))
+                                                            .selected_text(if trigger == "oneshot" { "One-shot" } else { "Continuous" })
+                                                            .show_ui(ui, |ui| {
+                                                                ui.selectable_value(&mut trigger, "continuous".into(), "Continuous");
+                                                                ui.selectable_value(&mut trigger, "oneshot".into(), "One-shot");
+                                                            });
+                                                        if trigger != ge.params.get("trigger").and_then(|v| v.as_str()).unwrap_or("continuous") {
+                                                            ge.params.insert("trigger".into(), serde_json::json!(trigger));
+                                                        }
+                                                        if trigger == "oneshot" {
+                                                            ui.label("(fires once per ⚡ Fire / F key)");
+                                                        }
+                                                    });
                                                 } else if ge.kind == "Sparkle" {
                                                     ui.horizontal(|ui| {
                                                         ui.label("Color:");
@@ -1458,13 +3889,46 @@ impl eframe::App for MyApp {
                                                             ge.params.insert("color".into(), serde_json::json!([color[0], color[1], color[2]]));
                                                         }
                                                     });
-                                                    let mut density = ge.params.get("density").and_then(|v| v.as_f64()).unwrap_or(0.05);
-                                                    if ui.add(egui::Slider::new(&mut density, 0.001..=0.2).text("Density")).changed() {
-                                                        ge.params.insert("density".into(), density.into());
+                                                    let mut pattern = ge.params.get("pattern").and_then(|v| v.as_str()).unwrap_or("random").to_string();
+                                                    ui.horizontal(|ui| {
+                                                        ui.label("Pattern:");
+                                                        egui::ComboBox::from_id_source(format!("ge_spk_pat_{}_{}", scene.id, eff_idx))
+                                                            .selected_text(match pattern.as_str() {
+                                                                "interval" => "Every Nth",
+                                                                "moving" => "Moving",
+                                                                _ => "Random",
+                                                            })
+                                                            .show_ui(ui, |ui| {
+                                                                ui.selectable_value(&mut pattern, "random".to_string(), "Random");
+                                                                ui.selectable_value(&mut pattern, "interval".to_string(), "Every Nth");
+                                                                ui.selectable_value(&mut pattern, "moving".to_string(), "Moving");
+                                                            });
+                                                    });
+                                                    if pattern != ge.params.get("pattern").and_then(|v| v.as_str()).unwrap_or("random") {
+                                                        ge.params.insert("pattern".into(), serde_json::json!(pattern));
+                                                    }
+                                                    let structured = pattern != "random";
+                                                    if structured {
+                                                        let mut interval = ge.params.get("interval").and_then(|v| v.as_u64()).unwrap_or(4);
+                                                        if ui.add(egui::Slider::new(&mut interval, 2..=64).text("Every Nth pixel")).changed() {
+                                                            ge.params.insert("interval".into(), interval.into());
+                                                        }
+                                                        let mut sync_to_beat = ge.params.get("sync_to_beat").and_then(|v| v.as_bool()).unwrap_or(false);
+                                                        if ui.checkbox(&mut sync_to_beat, "Flash on every beat").changed() {
+                                                            ge.params.insert("sync_to_beat".into(), sync_to_beat.into());
+                                                        }
+                                                    } else {
+                                                        let mut density = ge.params.get("density").and_then(|v| v.as_f64()).unwrap_or(0.05);
+                                                        if ui.add(egui::Slider::new(&mut density, 0.001..=0.2).text("Density")).changed() {
+                                                            ge.params.insert("density".into(), density.into());
+                                                        }
                                                     }
-                                                    let mut life = ge.params.get("life").and_then(|v| v.as_f64()).unwrap_or(0.2);
-                                                    if ui.add(egui::Slider::new(&mut life, 0.05..=2.0).text("Life")).changed() {
-                                                        ge.params.insert("life".into(), life.into());
+                                                    let beat_synced = structured && ge.params.get("sync_to_beat").and_then(|v| v.as_bool()).unwrap_or(false);
+                                                    if !beat_synced {
+                                                        let mut life = ge.params.get("life").and_then(|v| v.as_f64()).unwrap_or(0.2);
+                                                        if ui.add(egui::Slider::new(&mut life, 0.05..=2.0).text("Life")).changed() {
+                                                            ge.params.insert("life".into(), life.into());
+                                                        }
                                                     }
                                                     let mut decay = ge.params.get("decay").and_then(|v| v.as_f64()).unwrap_or(5.0);
                                                     if ui.add(egui::Slider::new(&mut decay, 0.1..=20.0).text("Decay")).changed() {
@@ -1493,16 +3957,7 @@ impl eframe::App for MyApp {
                                                         ui.horizontal(|ui| {
                                                             ui.label("Rate:");
                                                             let mut rate = ge.params.get("rate").and_then(|v| v.as_str().map(String::from)).unwrap_or("1 Bar".into());
-                                                            egui::ComboBox::from_id_source(format!("cw_rate_{}_{}", scene.id, eff_idx))
-                                                                .selected_text(&rate)
-                                                                .show_ui(ui, |ui| {
-                                                                    ui.selectable_value(&mut rate, "4 Bar".into(), "4 Bar");
-                                                                    ui.selectable_value(&mut rate, "2 Bar".into(), "2 Bar");
-                                                                    ui.selectable_value(&mut rate, "1 Bar".into(), "1 Bar");
-                                                                    ui.selectable_value(&mut rate, "1/2".into(), "1/2");
-                                                                    ui.selectable_value(&mut rate, "1/4".into(), "1/4");
-                                                                    ui.selectable_value(&mut rate, "1/8".into(), "1/8");
-                                                                });
+                                                            rate_combo(ui, &mut rate, format!("cw_rate_{}_{}", scene.id, eff_idx));
                                                             ge.params.insert("rate".into(), serde_json::json!(rate));
                                                         });
                                                     } else {
@@ -1511,6 +3966,44 @@ impl eframe::App for MyApp {
                                                             ge.params.insert("period".into(), period.into());
                                                         }
                                                     }
+                                                    stagger_controls(ui, &mut ge.params, format!("cw_stagger_{}_{}", scene.id, eff_idx));
+                                                } else if ge.kind == "Comet" {
+                                                    ui.horizontal(|ui| {
+                                                        ui.label("Color:");
+                                                        let mut color = ge.params.get("color").and_then(|v| serde_json::from_value(v.clone()).ok()).unwrap_or([255u8,255,255]);
+                                                        if color_picker(ui, &mut color, format!("ge_comet_{}_{}", scene.id, eff_idx)) {
+                                                            ge.params.insert("color".into(), serde_json::json!([color[0], color[1], color[2]]));
+                                                        }
+                                                    });
+                                                    let mut angle = ge.params.get("angle").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                                                    if ui.add(egui::Slider::new(&mut angle, 0.0..=360.0).text("Angle (°)")).changed() {
+                                                        ge.params.insert("angle".into(), angle.into());
+                                                    }
+                                                    let mut trail = ge.params.get("trail").and_then(|v| v.as_f64()).unwrap_or(0.3);
+                                                    if ui.add(egui::Slider::new(&mut trail, 0.01..=2.0).text("Trail")).changed() {
+                                                        ge.params.insert("trail".into(), trail.into());
+                                                    }
+                                                    let mut width = ge.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.1);
+                                                    if ui.add(egui::Slider::new(&mut width, 0.01..=2.0).text("Width")).changed() {
+                                                        ge.params.insert("width".into(), width.into());
+                                                    }
+                                                    let mut sync_to_beat = ge.params.get("sync_to_beat").and_then(|v| v.as_bool()).unwrap_or(false);
+                                                    if ui.checkbox(&mut sync_to_beat, "Sync to Beat").changed() {
+                                                        ge.params.insert("sync_to_beat".into(), sync_to_beat.into());
+                                                    }
+                                                    if sync_to_beat {
+                                                        ui.horizontal(|ui| {
+                                                            ui.label("Rate:");
+                                                            let mut rate = ge.params.get("rate").and_then(|v| v.as_str().map(String::from)).unwrap_or("1 Bar".into());
+                                                            rate_combo(ui, &mut rate, format!("comet_rate_{}_{}", scene.id, eff_idx));
+                                                            ge.params.insert("rate".into(), serde_json::json!(rate));
+                                                        });
+                                                    } else {
+                                                        let mut speed = ge.params.get("speed").and_then(|v| v.as_f64()).unwrap_or(0.5);
+                                                        if ui.add(egui::Slider::new(&mut speed, 0.05..=5.0).text("Speed (sweeps/s)")).changed() {
+                                                            ge.params.insert("speed".into(), speed.into());
+                                                        }
+                                                    }
                                                 } else if ge.kind == "GlitchSparkle" {
                                                     ui.horizontal(|ui| {
                                                         ui.label("Background:");
@@ -1554,16 +4047,7 @@ impl eframe::App for MyApp {
                                                         ui.horizontal(|ui| {
                                                             ui.label("Rate:");
                                                             let mut rate = ge.params.get("rate").and_then(|v| v.as_str().map(String::from)).unwrap_or("1/4".into());
-                                                            egui::ComboBox::from_id_source(format!("pw_rate_{}_{}", scene.id, eff_idx))
-                                                                .selected_text(&rate)
-                                                                .show_ui(ui, |ui| {
-                                                                    ui.selectable_value(&mut rate, "4 Bar".into(), "4 Bar");
-                                                                    ui.selectable_value(&mut rate, "2 Bar".into(), "2 Bar");
-                                                                    ui.selectable_value(&mut rate, "1 Bar".into(), "1 Bar");
-                                                                    ui.selectable_value(&mut rate, "1/2".into(), "1/2");
-                                                                    ui.selectable_value(&mut rate, "1/4".into(), "1/4");
-                                                                    ui.selectable_value(&mut rate, "1/8".into(), "1/8");
-                                                                });
+                                                            rate_combo(ui, &mut rate, format!("pw_rate_{}_{}", scene.id, eff_idx));
                                                             ge.params.insert("rate".into(), serde_json::json!(rate));
                                                         });
                                                     } else {
@@ -1592,6 +4076,7 @@ impl eframe::App for MyApp {
                                                             });
                                                         ge.params.insert("direction".into(), serde_json::json!(direction));
                                                     });
+                                                    stagger_controls(ui, &mut ge.params, format!("pw_stagger_{}_{}", scene.id, eff_idx));
                                                 } else if ge.kind == "ZoneAlternate" {
                                                     ui.horizontal(|ui| {
                                                         ui.label("Group A:");
@@ -1610,16 +4095,7 @@ impl eframe::App for MyApp {
                                                     ui.horizontal(|ui| {
                                                         ui.label("Rate:");
                                                         let mut rate = ge.params.get("rate").and_then(|v| v.as_str().map(String::from)).unwrap_or("1/4".into());
-                                                        egui::ComboBox::from_id_source(format!("za_rate_{}_{}", scene.id, eff_idx))
-                                                            .selected_text(&rate)
-                                                            .show_ui(ui, |ui| {
-                                                                ui.selectable_value(&mut rate, "4 Bar".into(), "4 Bar");
-                                                                ui.selectable_value(&mut rate, "2 Bar".into(), "2 Bar");
-                                                                ui.selectable_value(&mut rate, "1 Bar".into(), "1 Bar");
-                                                                ui.selectable_value(&mut rate, "1/2".into(), "1/2");
-                                                                ui.selectable_value(&mut rate, "1/4".into(), "1/4");
-                                                                ui.selectable_value(&mut rate, "1/8".into(), "1/8");
-                                                            });
+                                                        rate_combo(ui, &mut rate, format!("za_rate_{}_{}", scene.id, eff_idx));
                                                         ge.params.insert("rate".into(), serde_json::json!(rate));
                                                     });
                                                     ui.horizontal(|ui| {
@@ -1692,7 +4168,11 @@ impl eframe::App for MyApp {
                                     if let Some(idx) = delete_effect_idx {
                                         scene.global_effects.remove(idx);
                                     }
+                                }
+                                if scene.kind == "Blackout" {
+                                    ui.label("Blackout: outputs all channels at zero.");
                                 } else {
+                                    ui.separator();
                                     // Embedded Masks editor for this scene
                                     ui.horizontal(|ui| {
                                         ui.label("Masks:");
@@ -1700,7 +4180,7 @@ impl eframe::App for MyApp {
                                             .selected_text("Add Mask...")
                                             .show_ui(ui, |ui| {
                                                 if ui.selectable_label(false, "Scanner").clicked() {
-                                                    let mut m = Mask { id: rand::random(), mask_type: "scanner".into(), x: 0.5, y: 0.5, params: std::collections::HashMap::new() };
+                                                    let mut m = Mask { id: rand::random(), mask_type: "scanner".into(), x: 0.5, y: 0.5, params: std::collections::HashMap::new(), anchor_strip: None, target_strips: Vec::new() };
                                                     m.params.insert("width".into(), 0.3.into());
                                                     m.params.insert("height".into(), 0.3.into());
                                                     m.params.insert("speed".into(), 1.0.into());
@@ -1708,13 +4188,13 @@ impl eframe::App for MyApp {
                                                     scene.masks.push(m);
                                                 }
                                                 if ui.selectable_label(false, "Radial").clicked() {
-                                                    let mut m = Mask { id: rand::random(), mask_type: "radial".into(), x: 0.5, y: 0.5, params: std::collections::HashMap::new() };
+                                                    let mut m = Mask { id: rand::random(), mask_type: "radial".into(), x: 0.5, y: 0.5, params: std::collections::HashMap::new(), anchor_strip: None, target_strips: Vec::new() };
                                                     m.params.insert("radius".into(), 0.2.into());
                                                     m.params.insert("color".into(), serde_json::json!([255, 0, 0]));
                                                     scene.masks.push(m);
                                                 }
                                                 if ui.selectable_label(false, "Burst").clicked() {
-                                                    let mut m = Mask { id: rand::random(), mask_type: "burst".into(), x: 0.5, y: 0.5, params: std::collections::HashMap::new() };
+                                                    let mut m = Mask { id: rand::random(), mask_type: "burst".into(), x: 0.5, y: 0.5, params: std::collections::HashMap::new(), anchor_strip: None, target_strips: Vec::new() };
                                                     m.params.insert("base_radius".into(), 0.1.into());
                                                     m.params.insert("max_radius".into(), 0.5.into());
                                                     m.params.insert("sensitivity".into(), 0.5.into());
@@ -1723,7 +4203,7 @@ impl eframe::App for MyApp {
                                                     scene.masks.push(m);
                                                 }
                                                 if ui.selectable_label(false, "Orbit").clicked() {
-                                                    let mut m = Mask { id: rand::random(), mask_type: "orbit".into(), x: 0.5, y: 0.5, params: std::collections::HashMap::new() };
+                                                    let mut m = Mask { id: rand::random(), mask_type: "orbit".into(), x: 0.5, y: 0.5, params: std::collections::HashMap::new(), anchor_strip: None, target_strips: Vec::new() };
                                                     m.params.insert("width".into(), 0.3.into());
                                                     m.params.insert("height".into(), 0.3.into());
                                                     m.params.insert("bar_width".into(), 0.1.into());
@@ -1731,18 +4211,91 @@ impl eframe::App for MyApp {
                                                     m.params.insert("color".into(), serde_json::json!([255, 0, 255]));
                                                     scene.masks.push(m);
                                                 }
+                                                if ui.selectable_label(false, "Image").clicked() {
+                                                    let mut m = Mask { id: rand::random(), mask_type: "image".into(), x: 0.5, y: 0.5, params: std::collections::HashMap::new(), anchor_strip: None, target_strips: Vec::new() };
+                                                    m.params.insert("width".into(), 0.5.into());
+                                                    m.params.insert("height".into(), 0.5.into());
+                                                    m.params.insert("color".into(), serde_json::json!([255, 255, 255]));
+                                                    scene.masks.push(m);
+                                                }
                                             });
                                     });
 
                                     let mut delete_mask_idx = None;
+                                    let mut spawn_mask_idx = None;
                                     for (idx, m) in scene.masks.iter_mut().enumerate() {
                                         ui.push_id(m.id, |ui| {
-                                            ui.collapsing(format!("{} Mask::{}", m.mask_type, m.id), |ui| {
+                                            let is_mask_selected = self.selected_mask_ids.contains(&m.id);
+                                            let header_text = format!("{} Mask::{}", m.mask_type, m.id);
+                                            let (_, mask_header, _) = egui::collapsing_header::CollapsingState::load_with_default_open(
+                                                ui.ctx(), ui.make_persistent_id("mask_header"), false,
+                                            )
+                                            .show_header(ui, |ui| {
+                                                ui.selectable_label(is_mask_selected, header_text)
+                                                    .on_hover_text("Click to select, Ctrl/Cmd-click to add to selection")
+                                            })
+                                            .body(|ui| {
                                                 ui.horizontal(|ui| {
                                                     if ui.button("🗑 Delete").clicked() {
                                                         delete_mask_idx = Some(idx);
                                                     }
+                                                    if m.params.contains_key("lifetime") && !m.params.contains_key("spawn_time")
+                                                        && ui.button("✨ Spawn").on_hover_text("Add a copy that fades out and removes itself after the lifetime").clicked()
+                                                    {
+                                                        spawn_mask_idx = Some(idx);
+                                                    }
                                                 });
+
+                                    // Anchor: position becomes an offset from the chosen strip's origin
+                                    let mut anchor = m.anchor_strip;
+                                    ui.horizontal(|ui| {
+                                        ui.label("Anchor:");
+                                        let anchor_text = match anchor {
+                                            Some(id) => format!("Strip::{}", id),
+                                            None => "None (absolute)".to_string(),
+                                        };
+                                        egui::ComboBox::from_id_source(format!("anchor_{}_{}", scene.id, m.id))
+                                            .selected_text(anchor_text)
+                                            .show_ui(ui, |ui| {
+                                                ui.selectable_value(&mut anchor, None, "None (absolute)");
+                                                for strip in &self.state.strips {
+                                                    ui.selectable_value(&mut anchor, Some(strip.id), format!("Strip::{}", strip.id));
+                                                }
+                                            });
+                                    });
+                                    if anchor != m.anchor_strip {
+                                        m.set_anchor(anchor, &self.state.strips);
+                                        needs_save = true;
+                                    }
+
+                                    // Targets: empty means the mask draws on every strip
+                                    let target_text = if m.target_strips.is_empty() {
+                                        "Targets: All Strips".to_string()
+                                    } else if !m.targets_any(&self.state.strips) {
+                                        "Targets: None (strips deleted)".to_string()
+                                    } else {
+                                        format!("Targets: {} Strips", m.target_strips.len())
+                                    };
+                                    egui::CollapsingHeader::new(target_text)
+                                        .id_source(format!("mask_targets_{}_{}", scene.id, m.id))
+                                        .show(ui, |ui| {
+                                            if ui.selectable_label(m.target_strips.is_empty(), "All Strips").clicked() {
+                                                m.target_strips.clear();
+                                                needs_save = true;
+                                            }
+                                            ui.separator();
+                                            for strip in &self.state.strips {
+                                                let mut is_selected = m.target_strips.contains(&strip.id);
+                                                if ui.checkbox(&mut is_selected, format!("Strip::{}", strip.id)).changed() {
+                                                    if is_selected {
+                                                        m.target_strips.push(strip.id);
+                                                    } else {
+                                                        m.target_strips.retain(|id| *id != strip.id);
+                                                    }
+                                                    needs_save = true;
+                                                }
+                                            }
+                                        });
                                     
                                     // DYNAMIC PARAMS
                                     if m.mask_type == "scanner" {
@@ -1765,6 +4318,26 @@ impl eframe::App for MyApp {
                                             m.params.insert("rotation".into(), rotation.into());
                                             needs_save = true;
                                         }
+                                        if edge_softness_control(ui, &mut m.params) {
+                                            needs_save = true;
+                                        }
+                                        ui.horizontal(|ui| {
+                                            let mut jitter = m.params.get("jitter").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+                                            if ui.add(egui::Slider::new(&mut jitter, 0.0..=1.0).text("Jitter"))
+                                                .on_hover_text("Offset each pixel's sweep and color phase by a fixed random amount for a more organic shimmer")
+                                                .changed()
+                                            {
+                                                m.params.insert("jitter".into(), jitter.into());
+                                                if !m.params.contains_key("jitter_seed") {
+                                                    m.params.insert("jitter_seed".into(), rand::random::<u32>().into());
+                                                }
+                                                needs_save = true;
+                                            }
+                                            if jitter > 0.0 && ui.small_button("🎲 Reseed").on_hover_text("Pick a new random pattern").clicked() {
+                                                m.params.insert("jitter_seed".into(), rand::random::<u32>().into());
+                                                needs_save = true;
+                                            }
+                                        });
                                     } else if m.mask_type == "radial" {
                                         let mut r = m.params.get("radius").and_then(|v| v.as_f64()).unwrap_or(0.2) as f32;
                                         if ui.add(egui::Slider::new(&mut r, 0.0..=5.0).text("Radius")).changed() {
@@ -1774,7 +4347,13 @@ impl eframe::App for MyApp {
                                         if lfo_controls(ui, &mut m.params, "radius", format!("radius_lfo_{}", m.id)) {
                                             needs_save = true;
                                         }
+                                        if edge_softness_control(ui, &mut m.params) {
+                                            needs_save = true;
+                                        }
                                     } else if m.mask_type == "burst" {
+                                        if !self.engine.has_audio_input() {
+                                            ui.colored_label(egui::Color32::YELLOW, "⚠ No audio input: burst holds its base radius");
+                                        }
                                         let mut base_r = m.params.get("base_radius").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
                                         if ui.add(egui::Slider::new(&mut base_r, 0.0..=2.0).text("Base Radius")).changed() {
                                             m.params.insert("base_radius".into(), base_r.into());
@@ -1793,6 +4372,19 @@ impl eframe::App for MyApp {
                                             needs_save = true;
                                         }
 
+                                        let mut envelope = m.params.get("envelope").and_then(|v| v.as_str()).unwrap_or("level").to_string();
+                                        ui.horizontal(|ui| {
+                                            ui.label("Follow:");
+                                            ui.selectable_value(&mut envelope, "level".to_string(), "Level")
+                                                .on_hover_text("Smoothed loudness: rises and falls gently, like the meter");
+                                            ui.selectable_value(&mut envelope, "transient".to_string(), "Transients")
+                                                .on_hover_text("Jumps on each hit and falls away slowly: punchy bursts on kicks and snares");
+                                        });
+                                        if m.params.get("envelope").and_then(|v| v.as_str()).unwrap_or("level") != envelope {
+                                            m.params.insert("envelope".into(), envelope.into());
+                                            needs_save = true;
+                                        }
+
                                         let mut decay = m.params.get("decay").and_then(|v| v.as_f64()).unwrap_or(0.05) as f32;
                                         if ui.add(egui::Slider::new(&mut decay, 0.001..=0.5).text("Decay Speed")).changed() {
                                             m.params.insert("decay".into(), decay.into());
@@ -1819,6 +4411,52 @@ impl eframe::App for MyApp {
                                             m.params.insert("bar_width".into(), bw.into());
                                             needs_save = true;
                                         }
+                                    } else if m.mask_type == "image" {
+                                        let loaded = m.params.get("image")
+                                            .and_then(|v| serde_json::from_value::<model::MaskImage>(v.clone()).ok());
+                                        ui.horizontal(|ui| {
+                                            ui.label("Image:");
+                                            match &loaded {
+                                                Some(image) => ui.label(format!("{} x {} px", image.width, image.height)),
+                                                None => ui.weak("None"),
+                                            };
+                                            let hint = format!("PNG, JPEG, GIF or BMP. Scaled down to at most {} px per side and saved with the mask.", model::MASK_IMAGE_MAX_SIZE);
+                                            if ui.button("📂 Load Image...").on_hover_text(hint).clicked() {
+                                                if let Some(path) = rfd::FileDialog::new()
+                                                    .add_filter("Image", &["png", "jpg", "jpeg", "gif", "bmp"])
+                                                    .pick_file()
+                                                {
+                                                    match model::MaskImage::load(&path) {
+                                                        Ok(image) => {
+                                                            // Keep the width, fit the height to the picture's aspect ratio
+                                                            let w = m.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.5);
+                                                            let h = (w * image.height as f64 / image.width as f64).clamp(0.01, 2.0);
+                                                            m.params.insert("height".into(), h.into());
+                                                            m.params.insert("image".into(), serde_json::json!(image));
+                                                            self.status = format!("Loaded image from {}", path.display());
+                                                            needs_save = true;
+                                                        }
+                                                        Err(e) => self.status = format!("Image load failed: {}", e),
+                                                    }
+                                                }
+                                            }
+                                        });
+                                        let mut scale = m.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.5) as f32;
+                                        let before = scale;
+                                        if ui.add(egui::Slider::new(&mut scale, 0.01..=2.0).text("Scale"))
+                                            .on_hover_text("Width on the canvas; the height follows so the picture isn't stretched")
+                                            .changed()
+                                        {
+                                            let h = m.params.get("height").and_then(|v| v.as_f64()).unwrap_or(0.5) as f32;
+                                            m.params.insert("width".into(), scale.into());
+                                            m.params.insert("height".into(), (h * scale / before).clamp(0.01, 2.0).into());
+                                            needs_save = true;
+                                        }
+                                        let mut rotation = m.params.get("rotation").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+                                        if ui.add(egui::Slider::new(&mut rotation, 0.0..=360.0).text("Rotation")).changed() {
+                                            m.params.insert("rotation".into(), rotation.into());
+                                            needs_save = true;
+                                        }
                                     }
 
                                     // Color
@@ -1834,6 +4472,13 @@ impl eframe::App for MyApp {
                                         }
                                     });
                                     
+                                    if audio_mod_controls(ui, &mut m.params, m.id) {
+                                        needs_save = true;
+                                    }
+                                    if lifetime_controls(ui, &mut m.params, m.id, life_clock) {
+                                        needs_save = true;
+                                    }
+
                                     // Color Mode
                                     ui.horizontal(|ui| {
                                         ui.label("Gradient:");
@@ -1841,10 +4486,12 @@ impl eframe::App for MyApp {
                                         // Auto-migrate "rainbow" or "pulse" to "gradient" or "static" if needed? 
                                         // For now just offer Static/Gradient.
                                         egui::ComboBox::from_id_source(m.id)
-                                            .selected_text(if mode == "gradient" { "Gradient" } else { "Static" })
+                                            .selected_text(match mode.as_str() { "gradient" => "Gradient", "audio" => "Audio", _ => "Static" })
                                             .show_ui(ui, |ui| {
                                                 ui.selectable_value(&mut mode, "static".into(), "Static");
                                                 ui.selectable_value(&mut mode, "gradient".into(), "Gradient");
+                                                ui.selectable_value(&mut mode, "audio".into(), "Audio")
+                                                    .on_hover_text("Hue and brightness follow the audio input");
                                             });
                                         
                                         if mode != m.params.get("color_mode").and_then(|v| v.as_str()).unwrap_or("static") {
@@ -1853,6 +4500,12 @@ impl eframe::App for MyApp {
                                         }
                                     });
 
+                                    if m.params.get("color_mode").and_then(|v| v.as_str()) == Some("audio")
+                                        && audio_color_controls(ui, &mut m.params, m.id, self.engine.has_audio_input())
+                                    {
+                                        needs_save = true;
+                                    }
+
                                     // Multi-Color Gradient Colors
                                     let mode_ref = m.params.get("color_mode").and_then(|v| v.as_str()).unwrap_or("static");
                                     if mode_ref == "gradient" {
@@ -1922,6 +4575,43 @@ impl eframe::App for MyApp {
                                             }
                                             needs_save = true;
                                         }
+
+                                        ui.horizontal(|ui| {
+                                            ui.label("Blend:");
+                                            let space_label = |space: &str| match space {
+                                                "hsv" => "HSV",
+                                                "oklab" => "Oklab",
+                                                _ => "RGB",
+                                            };
+                                            let current = m.params.get("gradient_space").and_then(|v| v.as_str()).unwrap_or("rgb").to_string();
+                                            let mut space = current.clone();
+                                            egui::ComboBox::from_id_source(format!("grad_space_{}", m.id))
+                                                .selected_text(space_label(&space))
+                                                .show_ui(ui, |ui| {
+                                                    ui.selectable_value(&mut space, "rgb".to_string(), "RGB").on_hover_text("Straight mix; complementary colors pass through gray");
+                                                    ui.selectable_value(&mut space, "hsv".to_string(), "HSV").on_hover_text("Travel around the hue wheel, stays saturated");
+                                                    ui.selectable_value(&mut space, "oklab".to_string(), "Oklab").on_hover_text("Perceptually even brightness between stops");
+                                                });
+                                            if space != current {
+                                                m.params.insert("gradient_space".into(), space.into());
+                                                needs_save = true;
+                                            }
+                                        });
+
+                                        // Color cycle rate, independent from the motion rate
+                                        if m.params.get("sync").and_then(|v| v.as_bool()).unwrap_or(false) {
+                                            ui.horizontal(|ui| {
+                                                ui.label("Color Rate:");
+                                                let motion_rate = m.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1/4").to_string();
+                                                let current = m.params.get("color_rate").and_then(|v| v.as_str()).unwrap_or(&motion_rate).to_string();
+                                                let mut color_rate = current.clone();
+                                                rate_combo(ui, &mut color_rate, format!("color_rate_{}", m.id));
+                                                if color_rate != current {
+                                                    m.params.insert("color_rate".into(), serde_json::json!(color_rate));
+                                                    needs_save = true;
+                                                }
+                                            });
+                                        }
                                     }
                                     
                                     // Speed / Sync
@@ -1946,7 +4636,12 @@ impl eframe::App for MyApp {
                                                         .selected_text(motion.clone())
                                                         .show_ui(ui, |ui| {
                                                             ui.selectable_value(&mut motion, "Smooth".into(), "Smooth");
-                                                            ui.selectable_value(&mut motion, "Linear".into(), "Linear");
+                                                            ui.selectable_value(&mut motion, "Linear".into(), "Linear")
+                                                                .on_hover_text("Constant speed, turning sharply at the edges");
+                                                            ui.selectable_value(&mut motion, "Ease In-Out".into(), "Ease In-Out")
+                                                                .on_hover_text("Slower than Smooth into and out of the edges, faster through the middle");
+                                                            ui.selectable_value(&mut motion, "Hold".into(), "Hold")
+                                                                .on_hover_text("Constant speed, then a short rest at each edge");
                                                         });
                                                     if motion != m.params.get("motion").and_then(|v| v.as_str()).unwrap_or("Smooth") {
                                                         m.params.insert("motion".into(), serde_json::json!(motion));
@@ -1958,15 +4653,7 @@ impl eframe::App for MyApp {
                                                     ui.horizontal(|ui| {
                                                         ui.label("Rate:");
                                                         let mut rate = m.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1/4").to_string();
-                                                        egui::ComboBox::from_id_source(format!("rate_{}", m.id))
-                                                            .selected_text(rate.clone())
-                                                            .show_ui(ui, |ui| {
-                                                                ui.selectable_value(&mut rate, "4 Bar".into(), "4 Bar");
-                                                                ui.selectable_value(&mut rate, "1 Bar".into(), "1 Bar");
-                                                                ui.selectable_value(&mut rate, "1/2".into(), "1/2");
-                                                                ui.selectable_value(&mut rate, "1/4".into(), "1/4");
-                                                                ui.selectable_value(&mut rate, "1/8".into(), "1/8");
-                                                            });
+                                                        rate_combo(ui, &mut rate, format!("rate_{}", m.id));
                                                         if rate != m.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1/4") {
                                                             m.params.insert("rate".into(), serde_json::json!(rate));
                                                             needs_save = true;
@@ -2020,16 +4707,7 @@ impl eframe::App for MyApp {
                                                     ui.horizontal(|ui| {
                                                         ui.label("Rate:");
                                                         let mut rate = m.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1/4").to_string();
-                                                        egui::ComboBox::from_id_source(format!("orbit_rate_{}", m.id))
-                                                            .selected_text(rate.clone())
-                                                            .show_ui(ui, |ui| {
-                                                                ui.selectable_value(&mut rate, "4 Bar".into(), "4 Bar");
-                                                                ui.selectable_value(&mut rate, "2 Bar".into(), "2 Bar");
-                                                                ui.selectable_value(&mut rate, "1 Bar".into(), "1 Bar");
-                                                                ui.selectable_value(&mut rate, "1/2".into(), "1/2");
-                                                                ui.selectable_value(&mut rate, "1/4".into(), "1/4");
-                                                                ui.selectable_value(&mut rate, "1/8".into(), "1/8");
-                                                            });
+                                                        rate_combo(ui, &mut rate, format!("orbit_rate_{}", m.id));
                                                         if rate != m.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1/4") {
                                                             m.params.insert("rate".into(), serde_json::json!(rate));
                                                             needs_save = true;
@@ -2045,19 +4723,41 @@ impl eframe::App for MyApp {
                                             });
                                         }
                                         });
+                                    let has_cycle = m.mask_type == "scanner" || m.mask_type == "orbit"
+                                        || m.params.get("color_mode").and_then(|v| v.as_str()) == Some("gradient");
+                                    if has_cycle {
+                                        phase_preview_controls(ui, &mut self.mask_phase_preview, m.id);
+                                        phase_preview_shown |= self.mask_phase_preview.is_some_and(|(id, _)| id == m.id);
+                                    }
                                     // Close collapsing and push_id blocks, then the for-loop
                                     });
+                                    if mask_header.inner.clicked() {
+                                        // Selection is scoped to the scene being edited
+                                        if self.state.selected_scene_id != Some(scene.id) {
+                                            self.state.selected_scene_id = Some(scene.id);
+                                            self.selected_mask_ids.clear();
+                                        }
+                                        toggle_mask_selection(&mut self.selected_mask_ids, m.id, ui.input(|i| i.modifiers.command));
+                                    }
                                 });
                             }
                             if let Some(idx) = delete_mask_idx {
                                 scene.masks.remove(idx);
                                 needs_save = true;
                             }
+                            if let Some(idx) = spawn_mask_idx.filter(|_| delete_mask_idx.is_none()) {
+                                let copy = spawn_temporary(&scene.masks[idx], life_clock);
+                                scene.masks.push(copy);
+                                needs_save = true;
+                            }
                         }
                         } // End of !is_being_dragged
                         } // End of push_id
                             });
                         }
+                        if let Some(unlocked) = unlock_live {
+                            self.performance_unlocked = unlocked;
+                        }
                         if let Some(i) = duplicate_scene_idx {
                             let mut new_s = self.state.scenes[i].clone();
                             new_s.id = rand::random();
@@ -2072,24 +4772,18 @@ impl eframe::App for MyApp {
                             self.mark_state_changed();
                         }
 
-                        if let Some(i) = delete_scene_idx {
-                            // Clear MIDI button before deleting scene
-                            if let Some(scene) = self.state.scenes.get(i) {
-                                if let Some(btn) = scene.launchpad_btn {
-                                    if btn > 0 {
-                                        let cmd = if scene.launchpad_is_cc {
-                                            midi::MidiCommand::SetButtonColor { cc: btn, color: 0 }
-                                        } else {
-                                            midi::MidiCommand::SetPadColor { note: btn, color: 0 }
-                                        };
-                                        let _ = self.midi_sender.send(cmd);
-                                    }
-                                }
+                        if let Some(scene) = delete_scene_idx.and_then(|i| self.state.scenes.get(i)) {
+                            // Scenes with masks or a pad mapping are worth a second look
+                            if scene.masks.is_empty() && scene.launchpad_btn.is_none() {
+                                self.delete_scene(scene.id);
+                            } else {
+                                self.scene_delete_confirm = Some(scene.id);
                             }
-                            self.state.scenes.remove(i);
-                            self.mark_state_changed();
                         }
 
+                        if refresh_pads {
+                            self.refresh_launchpad_colors();
+                        }
                         if needs_save {
                             self.mark_state_changed();
                         }
@@ -2119,8 +4813,58 @@ impl eframe::App for MyApp {
                 // RIGHT PANEL: CANVAS
                 let canvas_ui = &mut columns[1];
                 
+                let mut fit_all = false;
+                let mut fit_selection = false;
                 canvas_ui.horizontal(|ui| {
                     ui.checkbox(&mut self.state.layout_locked, "🔒 Lock Layout");
+                    ui.checkbox(&mut self.view.pixel_lines, "〰 Connect Pixels")
+                        .on_hover_text("Draw strips as colored lines instead of dots (easier to read for dense strips)");
+                    ui.checkbox(&mut self.view.fast_render, "⚡ Fast Draw")
+                        .on_hover_text("Performance rendering: when zoomed out, draw one dot every few pixels so very large rigs stay responsive");
+                    fit_all = ui.button("⛶ Fit").on_hover_text("Fit every strip and fixture in view").clicked();
+                    fit_selection = ui.add_enabled(!self.selected_mask_ids.is_empty(), egui::Button::new("Fit selection"))
+                        .on_hover_text("Fit the selected masks in view")
+                        .clicked();
+                    let overlay_label = |mode: &str| match mode {
+                        "show" => "Show",
+                        "hide" => "Hide",
+                        _ => "Auto",
+                    };
+                    let mut overlays = self.state.mask_overlays.clone();
+                    egui::ComboBox::from_id_source("mask_overlays")
+                        .selected_text(format!("Overlays: {}", overlay_label(&overlays)))
+                        .show_ui(ui, |ui| {
+                            for mode in ["auto", "show", "hide"] {
+                                ui.selectable_value(&mut overlays, mode.to_string(), overlay_label(mode));
+                            }
+                        })
+                        .response
+                        .on_hover_text("Mask outlines, handles, grid and labels. Hidden, the canvas shows only the output pixels. Auto hides them in performance mode.");
+                    if overlays != self.state.mask_overlays {
+                        self.state.mask_overlays = overlays;
+                        self.mark_state_changed();
+                    }
+                    if ui.selectable_label(self.view.measuring, "📏 Measure")
+                        .on_hover_text("Click two points on the canvas to measure the distance between them")
+                        .clicked()
+                    {
+                        self.view.measuring = !self.view.measuring;
+                        self.view.measure_points.clear();
+                    }
+                    ui.menu_button("⚙", |ui| {
+                        let padding = ui.add(egui::Slider::new(&mut self.state.fit_padding, model::FIT_PADDING_RANGE).text("Fit padding"))
+                            .on_hover_text("Canvas units of space around the content");
+                        let margin = ui.add(egui::Slider::new(&mut self.state.fit_margin, model::FIT_MARGIN_RANGE).text("Fit fill"))
+                            .on_hover_text("How much of the canvas the fitted content fills");
+                        let scale = ui.horizontal(|ui| {
+                            let scale = ui.add(egui::DragValue::new(&mut self.state.canvas_unit_meters).clamp_range(0.0..=1000.0).speed(0.01).suffix(" m"));
+                            ui.label("per canvas unit");
+                            scale
+                        }).inner.on_hover_text("Physical size of one canvas unit, to show readouts in meters. 0 = canvas units only");
+                        if padding.changed() || margin.changed() || scale.changed() {
+                            self.mark_state_changed();
+                        }
+                    });
                 });
 
                 let (response, painter) = canvas_ui.allocate_painter(
@@ -2130,79 +4874,48 @@ impl eframe::App for MyApp {
                 
                 let rect = response.rect;
                 
-                // AUTO-FIT ON LOAD
+                // First frame: restore the view from the last session, or fit a layout that has never been viewed
                 if self.is_first_frame {
                     self.is_first_frame = false;
-                    
-                    let mut min_x: f32 = 1.0;
-                    let mut min_y: f32 = 1.0;
-                    let mut max_x: f32 = 0.0;
-                    let mut max_y: f32 = 0.0;
-                    let mut found = false;
-                    
-                    for s in &self.state.strips {
-                        // Start point
-                        min_x = min_x.min(s.x);
-                        min_y = min_y.min(s.y);
-                        max_x = max_x.max(s.x);
-                        max_y = max_y.max(s.y);
-
-                        // End point
-                        if s.pixel_count > 1 {
-                            let len = (s.pixel_count - 1) as f32 * s.spacing;
-                            // Strip always extends to Right
-                            let tail_x = s.x + len;
-                            let tail_y = s.y;
-                            min_x = min_x.min(tail_x);
-                            min_y = min_y.min(tail_y);
-                            max_x = max_x.max(tail_x);
-                            max_y = max_y.max(tail_y);
-                        }
-                        found = true;
-                    }
-
-
-                    
-                    if found {
-                        // Pad slightly
-                        min_x -= 0.1;
-                        min_y -= 0.1;
-                        max_x += 0.1;
-                        max_y += 0.1;
-                        
-                        let w = max_x - min_x;
-                        let h = max_y - min_y;
-                        
-                        // Fit w/h into 1.0/1.0 (since normalized coords 0..1 are standard)
-                        // Scale = Pixels / NormUnit
-                        // Available: rect.width(), rect.height()
-                        
-                        let scale_x = 1.0 / w;
-                        let scale_y = 1.0 / h;
-                        let fit_scale = scale_x.min(scale_y) * 0.9; 
-                        
-                        self.view.scale = fit_scale.clamp(0.1, 100.0);
-                        
-                        // Center Logic
-                        let cx = (min_x + max_x) / 2.0;
-                        let cy = (min_y + max_y) / 2.0;
-
-                        let w_px = rect.width();
-                        let h_px = rect.height();
-                        
-                        self.view.offset.x = -(cx - 0.5) * w_px * self.view.scale;
-                        self.view.offset.y = -(cy - 0.5) * h_px * self.view.scale;
+                    if let Some(b) = self.view_bookmarks.iter().find(|b| b.slot == db::LAST_VIEW_SLOT) {
+                        self.view.offset = egui::vec2(b.offset[0], b.offset[1]);
+                        self.view.scale = b.scale;
+                    } else {
+                        fit_all = true;
                     }
                 }
+                let fit_bounds = if fit_selection {
+                    let selected: Vec<Mask> = self.state.active_masks().iter()
+                        .filter(|m| self.selected_mask_ids.contains(&m.id))
+                        .cloned()
+                        .collect();
+                    content_bounds(&[], &[], &selected, &self.state.strips)
+                } else if fit_all {
+                    content_bounds(&self.state.strips, &self.state.fixtures, &[], &self.state.strips)
+                } else {
+                    None
+                };
+                // Empty rig or empty selection: leave the view alone
+                if let Some(bounds) = fit_bounds {
+                    let (scale, offset) = fit_view(bounds, self.state.fit_padding, self.state.fit_margin, rect.size());
+                    self.view.scale = scale;
+                    self.view.offset = offset;
+                }
                 
                 // HELPER CLOSURES (Moved up for scope visibility)
                 let to_screen = |x: f32, y: f32, view: &ViewState| -> egui::Pos2 {
-                    egui::pos2(
-                        rect.center().x + (x - 0.5) * rect.width() * view.scale + view.offset.x,
-                        rect.center().y + (y - 0.5) * rect.height() * view.scale + view.offset.y
-                    )
+                    canvas_to_screen(rect, view, x, y)
                 };
                 
+                if let Some(preview) = &self.stage_preview {
+                    let mut preview = preview.lock().unwrap();
+                    preview.strips.clone_from(&self.state.strips);
+                    preview.scale = self.view.scale;
+                    preview.offset = self.view.offset / rect.size();
+                    preview.pixel_lines = self.view.pixel_lines;
+                    preview.fast_render = self.view.fast_render;
+                }
+
                 let from_screen = |pos: egui::Pos2, view: &ViewState| -> (f32, f32) {
                      let dx = pos.x - (rect.center().x + view.offset.x);
                      let dy = pos.y - (rect.center().y + view.offset.y);
@@ -2213,12 +4926,16 @@ impl eframe::App for MyApp {
 
                 // INPUT TRANSFORMS (Keep existing input logic)
                 let input = ctx.input(|i| i.clone());
-                // Determine which masks are active for viewing/editing on canvas
-                let active_masks: Vec<model::Mask> = if let Some(sel) = self.state.selected_scene_id {
-                    if let Some(scene) = self.state.scenes.iter().find(|s| s.id == sel) {
-                        if scene.kind == "Masks" { scene.masks.clone() } else { self.state.masks.clone() }
-                    } else { self.state.masks.clone() }
-                } else { self.state.masks.clone() };
+                // Determine which masks are active for viewing/editing on canvas; none while overlays are hidden
+                let overlays = self.state.mask_overlays_visible();
+                let active_masks: Vec<model::Mask> = if overlays { self.state.active_masks().to_vec() } else { Vec::new() };
+                // Resolve strip-anchored masks to absolute canvas coordinates for hit-testing and drawing
+                let active_masks: Vec<model::Mask> = active_masks.into_iter().map(|mut m| {
+                    let (x, y) = m.resolved_position(&self.state.strips);
+                    m.x = x;
+                    m.y = y;
+                    m
+                }).collect();
                 
                 if response.hovered() {
                     let mut zoom_factor = 1.0;
@@ -2237,6 +4954,35 @@ impl eframe::App for MyApp {
                         }
                     }
 
+                    // View bookmarks: Ctrl/Cmd+1..9 saves the current pan/zoom, 1..9 recalls it
+                    if ctx.memory(|m| m.focus().is_none()) {
+                        const SLOT_KEYS: [egui::Key; 9] = [
+                            egui::Key::Num1, egui::Key::Num2, egui::Key::Num3,
+                            egui::Key::Num4, egui::Key::Num5, egui::Key::Num6,
+                            egui::Key::Num7, egui::Key::Num8, egui::Key::Num9,
+                        ];
+                        for (idx, key) in SLOT_KEYS.iter().enumerate() {
+                            if !input.key_pressed(*key) { continue; }
+                            let slot = idx as u8 + 1;
+                            if input.modifiers.command {
+                                let bookmark = ViewBookmark { slot, offset: [self.view.offset.x, self.view.offset.y], scale: self.view.scale };
+                                match self.db.save_view_bookmark(&bookmark) {
+                                    Ok(()) => {
+                                        self.view_bookmarks.retain(|b| b.slot != slot);
+                                        self.view_bookmarks.push(bookmark);
+                                        self.status = format!("Saved view {}", slot);
+                                    }
+                                    Err(e) => self.status = format!("Failed to save view bookmark: {}", e),
+                                }
+                            } else if input.modifiers.is_none() {
+                                if let Some(b) = self.view_bookmarks.iter().find(|b| b.slot == slot) {
+                                    self.view.offset = egui::vec2(b.offset[0], b.offset[1]);
+                                    self.view.scale = b.scale;
+                                }
+                            }
+                        }
+                    }
+
                     // HOVER CURSOR LOGIC
                     if let Some(pos) = response.hover_pos() {
                        // Use Screen Pixels directly!
@@ -2244,7 +4990,7 @@ impl eframe::App for MyApp {
                            let handle_size = 15.0; // Pixels
                            
                            match m.mask_type.as_str() {
-                               "scanner" => {
+                               "scanner" | "image" => {
                                    let w = m.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
                                    let h = m.params.get("height").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
                                    let rot_deg = m.params.get("rotation").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
@@ -2358,7 +5104,15 @@ impl eframe::App for MyApp {
                     }
                 }
 
-                if response.clicked() || response.drag_started() {
+                if self.view.measuring {
+                    // Measurement clicks never select or drag; dragging still pans
+                    if let Some(pos) = response.interact_pointer_pos().filter(|_| response.clicked()) {
+                        if self.view.measure_points.len() >= 2 {
+                            self.view.measure_points.clear();
+                        }
+                        self.view.measure_points.push(from_screen(pos, &self.view));
+                    }
+                } else if response.clicked() || response.drag_started() {
                    if let Some(pos) = response.interact_pointer_pos() {
                        let (wx, wy) = from_screen(pos, &self.view);
                        let mut hit = false;
@@ -2369,7 +5123,7 @@ impl eframe::App for MyApp {
                            let handle_size = 15.0; // Pixels
                            
                            match m.mask_type.as_str() {
-                               "scanner" => {
+                               "scanner" | "image" => {
                                    let w = m.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
                                    let h = m.params.get("height").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
                                    let rot_deg = m.params.get("rotation").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
@@ -2502,7 +5256,7 @@ impl eframe::App for MyApp {
                        if !hit {
                            for m in &active_masks {
                                match m.mask_type.as_str() {
-                                   "scanner" => {
+                                   "scanner" | "image" => {
                                        let w = m.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
                                        let h = m.params.get("height").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
                                        let rot_deg = m.params.get("rotation").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
@@ -2578,6 +5332,17 @@ impl eframe::App for MyApp {
                            self.view.drag_id = None; 
                            self.view.drag_type = DragType::None;
                        }
+
+                       // Mask selection for batch editing
+                       if response.clicked() {
+                           if self.view.drag_type == DragType::Mask {
+                               if let Some(id) = self.view.drag_id {
+                                   toggle_mask_selection(&mut self.selected_mask_ids, id, input.modifiers.command);
+                               }
+                           } else if !input.modifiers.command {
+                               self.selected_mask_ids.clear();
+                           }
+                       }
                    }
                 }
                 
@@ -2658,227 +5423,22 @@ impl eframe::App for MyApp {
                                   s.x += dx;
                                   s.y += dy;
                              }
-                         } else if self.view.drag_type == DragType::Mask {
-                             // Move mask (snapping happens on release)
-                             let dx = delta.x / (rect.width() * self.view.scale);
-                             let dy = delta.y / (rect.height() * self.view.scale);
-                             if let Some(sel) = self.state.selected_scene_id {
-                                 if let Some(scene_index) = self.state.scenes.iter().position(|s| s.id == sel && s.kind == "Masks") {
-                                     if let Some(m) = self.state.scenes[scene_index].masks.iter_mut().find(|m| Some(m.id) == self.view.drag_id) {
-                                         m.x += dx; m.y += dy;
+                         } else if matches!(self.view.drag_type, DragType::Mask | DragType::ResizeMask(_)) && !self.live_scene_locked() {
+                             let px_w = rect.width() * self.view.scale;
+                             let px_h = rect.height() * self.view.scale;
+                             let drag_type = self.view.drag_type;
+                             let drag_id = self.view.drag_id;
+                             if let Some(m) = self.state.active_masks_mut().and_then(|masks| masks.iter_mut().find(|m| Some(m.id) == drag_id)) {
+                                 match drag_type {
+                                     // Move mask (snapping happens on release)
+                                     DragType::Mask => {
+                                         m.x += delta.x / px_w;
+                                         m.y += delta.y / px_h;
                                      }
-                                 } else if let Some(m) = self.state.masks.iter_mut().find(|m| Some(m.id) == self.view.drag_id) {
-                                     m.x += dx; m.y += dy;
+                                     DragType::ResizeMask(edge_idx) => resize_mask(m, edge_idx, delta, px_w, px_h),
+                                     _ => {}
                                  }
-                             } else if let Some(m) = self.state.masks.iter_mut().find(|m| Some(m.id) == self.view.drag_id) {
-                                 m.x += dx; m.y += dy;
                              }
-                         } else if let DragType::ResizeMask(edge_idx) = self.view.drag_type {
-                              // Fetch target mask mutably depending on scene selection
-                              // We'll duplicate the resize logic for whichever collection contains the mask
-                              // Scene masks first
-                              if let Some(sel) = self.state.selected_scene_id {
-                                  if let Some(scene_index) = self.state.scenes.iter().position(|s| s.id == sel && s.kind == "Masks") {
-                                      if let Some(m) = self.state.scenes[scene_index].masks.iter_mut().find(|m| Some(m.id) == self.view.drag_id) {
-                                          match m.mask_type.as_str() {
-                                              "scanner" => {
-                                                  let w = m.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
-                                                  let h = m.params.get("height").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
-                                                  let rot_deg = m.params.get("rotation").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
-                                                  let rot = rot_deg.to_radians();
-                                                  let cos_r = rot.cos();
-                                                  let sin_r = rot.sin();
-                                                  let ldx_scr = delta.x * cos_r + delta.y * sin_r;
-                                                  let ldy_scr = -delta.x * sin_r + delta.y * cos_r;
-                                                  let w_scr = w * rect.width() * self.view.scale;
-                                                  let h_scr = h * rect.height() * self.view.scale;
-                                                  let mut new_w_scr = w_scr;
-                                                  let mut new_h_scr = h_scr;
-                                                  let mut shift_lx_scr = 0.0;
-                                                  let mut shift_ly_scr = 0.0;
-                                                  match edge_idx { 0 => { new_h_scr = (h_scr - ldy_scr).max(1.0); shift_ly_scr = ldy_scr / 2.0; },
-                                                                   1 => { new_w_scr = (w_scr + ldx_scr).max(1.0); shift_lx_scr = ldx_scr / 2.0; },
-                                                                   2 => { new_h_scr = (h_scr + ldy_scr).max(1.0); shift_ly_scr = ldy_scr / 2.0; },
-                                                                   3 => { new_w_scr = (w_scr - ldx_scr).max(1.0); shift_lx_scr = ldx_scr / 2.0; },
-                                                                   _ => {} }
-                                                  let new_w = new_w_scr / (rect.width() * self.view.scale);
-                                                  let new_h = new_h_scr / (rect.height() * self.view.scale);
-                                                  let wx_shift_scr = shift_lx_scr * cos_r - shift_ly_scr * sin_r;
-                                                  let wy_shift_scr = shift_lx_scr * sin_r + shift_ly_scr * cos_r;
-                                                  let wx_shift_norm = wx_shift_scr / (rect.width() * self.view.scale);
-                                                  let wy_shift_norm = wy_shift_scr / (rect.height() * self.view.scale);
-                                                  m.x += wx_shift_norm; m.y += wy_shift_norm;
-                                                  m.params.insert("width".to_string(), new_w.max(0.01).into());
-                                                  m.params.insert("height".to_string(), new_h.max(0.01).into());
-                                              },
-                                              "radial" => {
-                                                  let r = m.params.get("radius").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
-                                                  let dr_scr = delta.x;
-                                                  let dr_norm = dr_scr / (rect.width() * self.view.scale);
-                                                  m.params.insert("radius".to_string(), (r + dr_norm).max(0.01).into());
-                                              },
-                                              "orbit" => {
-                                                  // Orbit has no rotation, simpler resize logic
-                                                  let w = m.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.3) as f32;
-                                                  let h = m.params.get("height").and_then(|v| v.as_f64()).unwrap_or(0.3) as f32;
-                                                  let w_scr = w * rect.width() * self.view.scale;
-                                                  let h_scr = h * rect.height() * self.view.scale;
-                                                  let mut new_w_scr = w_scr;
-                                                  let mut new_h_scr = h_scr;
-                                                  let mut shift_x_scr = 0.0f32;
-                                                  let mut shift_y_scr = 0.0f32;
-                                                  match edge_idx {
-                                                      0 => { new_h_scr = (h_scr - delta.y).max(1.0); shift_y_scr = delta.y / 2.0; },
-                                                      1 => { new_w_scr = (w_scr + delta.x).max(1.0); shift_x_scr = delta.x / 2.0; },
-                                                      2 => { new_h_scr = (h_scr + delta.y).max(1.0); shift_y_scr = delta.y / 2.0; },
-                                                      3 => { new_w_scr = (w_scr - delta.x).max(1.0); shift_x_scr = delta.x / 2.0; },
-                                                      _ => {}
-                                                  }
-                                                  let new_w = new_w_scr / (rect.width() * self.view.scale);
-                                                  let new_h = new_h_scr / (rect.height() * self.view.scale);
-                                                  m.x += shift_x_scr / (rect.width() * self.view.scale);
-                                                  m.y += shift_y_scr / (rect.height() * self.view.scale);
-                                                  m.params.insert("width".to_string(), new_w.max(0.01).into());
-                                                  m.params.insert("height".to_string(), new_h.max(0.01).into());
-                                              },
-                                              _ => {}
-                                          }
-                                          // End scene mask branch
-                                      } else if let Some(m) = self.state.masks.iter_mut().find(|m| Some(m.id) == self.view.drag_id) {
-                                          // Fall back to global masks if not found
-                                          match m.mask_type.as_str() {
-                                              "scanner" => {
-                                                  let w = m.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
-                                                  let h = m.params.get("height").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
-                                                  let rot_deg = m.params.get("rotation").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
-                                                  let rot = rot_deg.to_radians();
-                                                  let cos_r = rot.cos();
-                                                  let sin_r = rot.sin();
-                                                  let ldx_scr = delta.x * cos_r + delta.y * sin_r;
-                                                  let ldy_scr = -delta.x * sin_r + delta.y * cos_r;
-                                                  let w_scr = w * rect.width() * self.view.scale;
-                                                  let h_scr = h * rect.height() * self.view.scale;
-                                                  let mut new_w_scr = w_scr;
-                                                  let mut new_h_scr = h_scr;
-                                                  let mut shift_lx_scr = 0.0;
-                                                  let mut shift_ly_scr = 0.0;
-                                                  match edge_idx {
-                                                      0 => { new_h_scr = (h_scr - ldy_scr).max(1.0); shift_ly_scr = -(new_h_scr - h_scr) / 2.0; },
-                                                      1 => { new_w_scr = (w_scr + ldx_scr).max(1.0); shift_lx_scr = (new_w_scr - w_scr) / 2.0; },
-                                                      2 => { new_h_scr = (h_scr + ldy_scr).max(1.0); shift_ly_scr = (new_h_scr - h_scr) / 2.0; },
-                                                      3 => { new_w_scr = (w_scr - ldx_scr).max(1.0); shift_lx_scr = -(new_w_scr - w_scr) / 2.0; },
-                                                      _ => {}
-                                                  }
-                                                  let new_w = new_w_scr / (rect.width() * self.view.scale);
-                                                  let new_h = new_h_scr / (rect.height() * self.view.scale);
-                                                  let wx_shift_scr = shift_lx_scr * cos_r - shift_ly_scr * sin_r;
-                                                  let wy_shift_scr = shift_lx_scr * sin_r + shift_ly_scr * cos_r;
-                                                  let wx_shift_norm = wx_shift_scr / (rect.width() * self.view.scale);
-                                                  let wy_shift_norm = wy_shift_scr / (rect.height() * self.view.scale);
-                                                  m.x += wx_shift_norm; m.y += wy_shift_norm;
-                                                  m.params.insert("width".to_string(), new_w.max(0.01).into());
-                                                  m.params.insert("height".to_string(), new_h.max(0.01).into());
-                                              },
-                                              "radial" => {
-                                                  let r = m.params.get("radius").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
-                                                  let dr_scr = delta.x;
-                                                  let dr_norm = dr_scr / (rect.width() * self.view.scale);
-                                                  m.params.insert("radius".to_string(), (r + dr_norm).max(0.01).into());
-                                              },
-                                              "orbit" => {
-                                                  let w = m.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.3) as f32;
-                                                  let h = m.params.get("height").and_then(|v| v.as_f64()).unwrap_or(0.3) as f32;
-                                                  let w_scr = w * rect.width() * self.view.scale;
-                                                  let h_scr = h * rect.height() * self.view.scale;
-                                                  let mut new_w_scr = w_scr;
-                                                  let mut new_h_scr = h_scr;
-                                                  let mut shift_x_scr = 0.0f32;
-                                                  let mut shift_y_scr = 0.0f32;
-                                                  match edge_idx {
-                                                      0 => { new_h_scr = (h_scr - delta.y).max(1.0); shift_y_scr = delta.y / 2.0; },
-                                                      1 => { new_w_scr = (w_scr + delta.x).max(1.0); shift_x_scr = delta.x / 2.0; },
-                                                      2 => { new_h_scr = (h_scr + delta.y).max(1.0); shift_y_scr = delta.y / 2.0; },
-                                                      3 => { new_w_scr = (w_scr - delta.x).max(1.0); shift_x_scr = delta.x / 2.0; },
-                                                      _ => {}
-                                                  }
-                                                  let new_w = new_w_scr / (rect.width() * self.view.scale);
-                                                  let new_h = new_h_scr / (rect.height() * self.view.scale);
-                                                  m.x += shift_x_scr / (rect.width() * self.view.scale);
-                                                  m.y += shift_y_scr / (rect.height() * self.view.scale);
-                                                  m.params.insert("width".to_string(), new_w.max(0.01).into());
-                                                  m.params.insert("height".to_string(), new_h.max(0.01).into());
-                                              },
-                                              _ => {}
-                                          }
-                                      }
-                                  } else if let Some(m) = self.state.masks.iter_mut().find(|m| Some(m.id) == self.view.drag_id) {
-                                      // No scene selected; operate on global masks
-                                      match m.mask_type.as_str() {
-                                          "scanner" => {
-                                              let w = m.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
-                                              let h = m.params.get("height").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
-                                              let rot_deg = m.params.get("rotation").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
-                                              let rot = rot_deg.to_radians();
-                                              let cos_r = rot.cos();
-                                              let sin_r = rot.sin();
-                                              let ldx_scr = delta.x * cos_r + delta.y * sin_r;
-                                              let ldy_scr = -delta.x * sin_r + delta.y * cos_r;
-                                              let w_scr = w * rect.width() * self.view.scale;
-                                              let h_scr = h * rect.height() * self.view.scale;
-                                              let mut new_w_scr = w_scr;
-                                              let mut new_h_scr = h_scr;
-                                              let mut shift_lx_scr = 0.0;
-                                              let mut shift_ly_scr = 0.0;
-                                              match edge_idx {
-                                                  0 => { new_h_scr = (h_scr - ldy_scr).max(1.0); shift_ly_scr = -(new_h_scr - h_scr) / 2.0; },
-                                                  1 => { new_w_scr = (w_scr + ldx_scr).max(1.0); shift_lx_scr = (new_w_scr - w_scr) / 2.0; },
-                                                  2 => { new_h_scr = (h_scr + ldy_scr).max(1.0); shift_ly_scr = (new_h_scr - h_scr) / 2.0; },
-                                                  3 => { new_w_scr = (w_scr - ldx_scr).max(1.0); shift_lx_scr = -(new_w_scr - w_scr) / 2.0; },
-                                                  _ => {}
-                                              }
-                                              let new_w = new_w_scr / (rect.width() * self.view.scale);
-                                              let new_h = new_h_scr / (rect.height() * self.view.scale);
-                                              let wx_shift_scr = shift_lx_scr * cos_r - shift_ly_scr * sin_r;
-                                              let wy_shift_scr = shift_lx_scr * sin_r + shift_ly_scr * cos_r;
-                                              let wx_shift_norm = wx_shift_scr / (rect.width() * self.view.scale);
-                                              let wy_shift_norm = wy_shift_scr / (rect.height() * self.view.scale);
-                                              m.x += wx_shift_norm; m.y += wy_shift_norm;
-                                              m.params.insert("width".to_string(), new_w.max(0.01).into());
-                                              m.params.insert("height".to_string(), new_h.max(0.01).into());
-                                          },
-                                          "radial" => {
-                                              let r = m.params.get("radius").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
-                                              let dr_scr = delta.x;
-                                              let dr_norm = dr_scr / (rect.width() * self.view.scale);
-                                              m.params.insert("radius".to_string(), (r + dr_norm).max(0.01).into());
-                                          },
-                                          "orbit" => {
-                                              let w = m.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.3) as f32;
-                                              let h = m.params.get("height").and_then(|v| v.as_f64()).unwrap_or(0.3) as f32;
-                                              let w_scr = w * rect.width() * self.view.scale;
-                                              let h_scr = h * rect.height() * self.view.scale;
-                                              let mut new_w_scr = w_scr;
-                                              let mut new_h_scr = h_scr;
-                                              let mut shift_x_scr = 0.0f32;
-                                              let mut shift_y_scr = 0.0f32;
-                                              match edge_idx {
-                                                  0 => { new_h_scr = (h_scr - delta.y).max(1.0); shift_y_scr = delta.y / 2.0; },
-                                                  1 => { new_w_scr = (w_scr + delta.x).max(1.0); shift_x_scr = delta.x / 2.0; },
-                                                  2 => { new_h_scr = (h_scr + delta.y).max(1.0); shift_y_scr = delta.y / 2.0; },
-                                                  3 => { new_w_scr = (w_scr - delta.x).max(1.0); shift_x_scr = delta.x / 2.0; },
-                                                  _ => {}
-                                              }
-                                              let new_w = new_w_scr / (rect.width() * self.view.scale);
-                                              let new_h = new_h_scr / (rect.height() * self.view.scale);
-                                              m.x += shift_x_scr / (rect.width() * self.view.scale);
-                                              m.y += shift_y_scr / (rect.height() * self.view.scale);
-                                              m.params.insert("width".to_string(), new_w.max(0.01).into());
-                                              m.params.insert("height".to_string(), new_h.max(0.01).into());
-                                          },
-                                          _ => {}
-                                      }
-                                  }
-                              }
                          }
                     } else {
                         // Pan View - offset is in Pixels
@@ -2889,7 +5449,8 @@ impl eframe::App for MyApp {
                 
                 if response.drag_released() {
                     // Apply snapping on release (if shift not held)
-                    if !shift_held && self.view.drag_id.is_some() {
+                    let mask_drag = matches!(self.view.drag_type, DragType::Mask | DragType::ResizeMask(_));
+                    if !shift_held && self.view.drag_id.is_some() && !(mask_drag && self.live_scene_locked()) {
                         let drag_id = self.view.drag_id;
                         let drag_type = self.view.drag_type;
 
@@ -2939,24 +5500,23 @@ impl eframe::App for MyApp {
                         };
 
                         // Find the mask and apply appropriate snapping
-                        let apply_snap = |m: &mut crate::model::Mask| {
+                        let apply_snap = |m: &mut crate::model::Mask, strips: &[PixelStrip]| {
+                            // Snap in absolute canvas space so anchored masks land on the grid too
+                            let (ox, oy) = m.anchor_origin(strips);
+                            m.x += ox;
+                            m.y += oy;
                             match drag_type {
                                 DragType::Mask => snap_mask_position(m),
                                 DragType::ResizeMask(edge_idx) => snap_mask_edge(m, edge_idx),
                                 _ => {}
                             }
+                            m.x -= ox;
+                            m.y -= oy;
                         };
 
-                        if let Some(sel) = self.state.selected_scene_id {
-                            if let Some(scene_index) = self.state.scenes.iter().position(|s| s.id == sel && s.kind == "Masks") {
-                                if let Some(m) = self.state.scenes[scene_index].masks.iter_mut().find(|m| Some(m.id) == drag_id) {
-                                    apply_snap(m);
-                                }
-                            } else if let Some(m) = self.state.masks.iter_mut().find(|m| Some(m.id) == drag_id) {
-                                apply_snap(m);
-                            }
-                        } else if let Some(m) = self.state.masks.iter_mut().find(|m| Some(m.id) == drag_id) {
-                            apply_snap(m);
+                        let strips = self.state.strips.clone();
+                        if let Some(m) = self.state.active_masks_mut().and_then(|masks| masks.iter_mut().find(|m| Some(m.id) == drag_id)) {
+                            apply_snap(m, &strips);
                         }
                     }
 
@@ -2966,8 +5526,9 @@ impl eframe::App for MyApp {
                 }
 
                 // RENDERING
+                let theme = CanvasTheme::named(&self.state.canvas_theme);
                 // Background
-                painter.rect_filled(rect, 0.0, egui::Color32::from_rgb(15, 15, 18));
+                painter.rect_filled(rect, 0.0, theme.background);
                 
                 
                 // Draw bounds (Fit to strips)
@@ -2999,16 +5560,13 @@ impl eframe::App for MyApp {
                 let bounds_height = b_max_y - b_min_y;
 
                 // Only draw grid if we have strips
-                if !self.state.strips.is_empty() && bounds_width > 0.0 {
+                if overlays && !self.state.strips.is_empty() && bounds_width > 0.0 {
                     // Get visible world coordinates (canvas corners)
                     let (visible_min_x, visible_min_y) = from_screen(rect.left_top(), &self.view);
                     let (visible_max_x, visible_max_y) = from_screen(rect.right_bottom(), &self.view);
 
-                    // Grid colors - visible but subtle
-                    let grid_color_major = egui::Color32::from_rgba_unmultiplied(70, 70, 70, 180);    // Full units
-                    let grid_color_half = egui::Color32::from_rgba_unmultiplied(55, 55, 55, 140);     // Half units
-                    let grid_color_quarter = egui::Color32::from_rgba_unmultiplied(45, 45, 45, 110);  // Quarter units
-                    let grid_color_minor = egui::Color32::from_rgba_unmultiplied(38, 38, 38, 80);     // Smaller
+                    // Grid colors: full, half, quarter and smaller units
+                    let [grid_color_major, grid_color_half, grid_color_quarter, grid_color_minor] = theme.grid;
 
                     let target_min_pixels = 25.0;
                     let target_max_pixels = 80.0;
@@ -3076,7 +5634,7 @@ impl eframe::App for MyApp {
                                     egui::Align2::LEFT_TOP,
                                     text,
                                     egui::FontId::proportional(10.0),
-                                    egui::Color32::from_rgba_unmultiplied(140, 140, 140, 180),
+                                    theme.grid_label,
                                 );
                             }
                         }
@@ -3131,76 +5689,91 @@ impl eframe::App for MyApp {
                 }
 
                 // Strips
+                let channel_base = self.state.channel_base();
                 for s in &self.state.strips {
-                    let pos = to_screen(s.x, s.y, &self.view);
-                    
-                    // Draw Head (Start)
-                    painter.rect_filled(
-                        egui::Rect::from_center_size(pos, egui::vec2(8.0, 8.0)), 
-                        1.0, 
-                        egui::Color32::from_rgb(0, 255, 255) // Cyan
-                    );
-                    painter.rect_stroke(
-                         egui::Rect::from_center_size(pos, egui::vec2(8.0, 8.0)),
-                         1.0,
-                         egui::Stroke::new(1.0, egui::Color32::BLACK)
-                    );
-                    
-                    // Draw Label "U:C"
-                    painter.text(
-                        pos + egui::vec2(8.0, -8.0),
-                        egui::Align2::LEFT_BOTTOM,
-                        format!("{}:{}", s.universe, s.start_channel),
-                        egui::FontId::proportional(12.0),
-                        egui::Color32::WHITE,
-                    );
-
-                    // Draw Line of Pixels representation
-                    if s.pixel_count > 0 {
-                        let _spacing = s.spacing;
-                        // let angle = s.rotation.to_radians(); -> Removed
-                        // let _dir = egui::vec2(angle.cos(), angle.sin());
-                        
-                        // We actually draw the pixels in the Engine loop usually, 
-                        // but here we can draw a "ghost" line or the pixels themselves if we have data.
-                        // The previous code drew pixels. Let's keep that logic but assume it's below.
+                    // Head marker sits on the data-in end (pixel 0), which is the right end when flipped
+                    let (head_x, head_y) = s.pixel_position(0);
+                    let pos = to_screen(head_x, head_y, &self.view);
+
+                    // Cull strips entirely off-canvas (labels hang up to ~80 px off the head)
+                    let (tail_x, tail_y) = s.pixel_position(s.pixel_count.saturating_sub(1));
+                    let extent = egui::Rect::from_two_pos(pos, to_screen(tail_x, tail_y, &self.view));
+                    if !extent.expand(80.0).intersects(rect) {
+                        continue;
                     }
                     
-                    // Draw pixels based on simulation data...
-                    for i in 0..s.pixel_count {
-                        // Calculate world pos of pixel i
-                        // Calculate world pos of pixel i
-                        // Reverse in place
-                        let effective_offset = if s.flipped {
-                             ((s.pixel_count - 1).saturating_sub(i)) as f32 * s.spacing
-                        } else {
-                             i as f32 * s.spacing
-                        };
-                        let px_world = s.x + effective_offset;
-                        let py_world = s.y;
-
-                        let px_screen = to_screen(px_world, py_world, &self.view);
-
-                        // Color from data (rgb_data is Vec<[u8; 3]>, so length is pixel count)
-                        let rgb_data = &s.data;
-                        let color = if i < rgb_data.len() {
-                            let p = rgb_data[i];
-                            egui::Color32::from_rgb(p[0], p[1], p[2])
-                        } else {
-                            egui::Color32::GRAY
-                        };
-                        
+                    // Draw Head (Data In)
+                    if overlays && rect.expand(80.0).contains(pos) {
                         painter.rect_filled(
-                            egui::Rect::from_center_size(px_screen, egui::vec2(4.0, 4.0)),
+                            egui::Rect::from_center_size(pos, egui::vec2(8.0, 8.0)), 
                             1.0, 
-                            color
+                            theme.head
+                        );
+                        painter.rect_stroke(
+                             egui::Rect::from_center_size(pos, egui::vec2(8.0, 8.0)),
+                             1.0,
+                             egui::Stroke::new(1.0, theme.head_outline)
+                        );
+                    
+                        // Draw Label "U:C"
+                        painter.text(
+                            pos + egui::vec2(8.0, -8.0),
+                            egui::Align2::LEFT_BOTTOM,
+                            format!("{}:{}", s.universe, s.start_channel - 1 + channel_base),
+                            egui::FontId::proportional(12.0),
+                            theme.text,
                         );
                     }
+
+                    draw_strip_pixels(&painter, s, rect, &self.view, theme.outline);
+                }
+
+                // Fixtures: a square showing the rendered color, labelled with name and address
+                for f in &self.state.fixtures {
+                    let pos = to_screen(f.x, f.y, &self.view);
+                    if !rect.expand(80.0).contains(pos) {
+                        continue;
+                    }
+                    let [r, g, b] = f.color;
+                    let fixture_rect = egui::Rect::from_center_size(pos, egui::vec2(14.0, 14.0));
+                    painter.rect_filled(fixture_rect, 2.0, egui::Color32::from_rgb(r, g, b));
+                    painter.rect_stroke(fixture_rect, 2.0, egui::Stroke::new(1.0, theme.outline));
+                    if !overlays {
+                        continue;
+                    }
+                    painter.text(
+                        pos + egui::vec2(10.0, -8.0),
+                        egui::Align2::LEFT_BOTTOM,
+                        format!("{} {}:{}", f.name, f.universe, f.start_channel - 1 + channel_base),
+                        egui::FontId::proportional(12.0),
+                        theme.text,
+                    );
                 }
                 
+                // Comet head preview
+                for &(cx, cy) in self.engine.comet_preview().iter().filter(|_| overlays) {
+                    let pos = to_screen(cx, cy, &self.view);
+                    painter.circle_filled(pos, 5.0, theme.head);
+                    painter.circle_stroke(pos, 5.0, egui::Stroke::new(1.0, theme.head_outline));
+                }
+
                 // Masks
                 for m in &active_masks {
                     let pos = to_screen(m.x, m.y, &self.view);
+
+                    if self.selected_mask_ids.contains(&m.id) {
+                        painter.circle_stroke(pos, 9.0, egui::Stroke::new(2.0, theme.selection));
+                    }
+                    // Temporary masks: a thin ring that winds down as their life runs out
+                    if let Some(life) = m.life_remaining(self.engine.get_time() as f64, self.engine.get_beat()) {
+                        let points: Vec<egui::Pos2> = (0..=32)
+                            .map(|i| {
+                                let a = -std::f32::consts::FRAC_PI_2 + std::f32::consts::TAU * life * i as f32 / 32.0;
+                                pos + 12.0 * egui::vec2(a.cos(), a.sin())
+                            })
+                            .collect();
+                        painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, theme.text.gamma_multiply(0.6))));
+                    }
                     
                     let mut rgb = m.params.get("color").and_then(|v| {
                         serde_json::from_value::<Vec<u8>>(serde_json::json!(v)).ok() // Hacky conversion
@@ -3287,10 +5860,8 @@ impl eframe::App for MyApp {
                                   let norm_phase = (phase / (std::f64::consts::PI * 2.0)).fract();
                                   let p = if norm_phase < 0.0 { norm_phase + 1.0 } else { norm_phase };
                                   p * 2.0 - 1.0
-                             } else if motion == "Linear" {
-                                 (2.0 / std::f64::consts::PI) * (phase.sin().asin())
                              } else {
-                                 phase.sin()
+                                 motion_curve(phase, motion)
                              };
 
                              // Offset of bar center in NORMALIZED units
@@ -3313,7 +5884,9 @@ impl eframe::App for MyApp {
                                       // Calc progress
                                        let progress = if is_sync {
                                              let beat = self.engine.get_beat();
-                                             let rate_str = m.params.get("rate").and_then(|v| v.as_str()).unwrap_or("1/4");
+                                             let rate_str = m.params.get("color_rate")
+                                                 .or_else(|| m.params.get("rate"))
+                                                 .and_then(|v| v.as_str()).unwrap_or("1/4");
                                              let divisor = match rate_str {
                                                  "4 Bar" => 16.0, "2 Bar" => 8.0, "1 Bar" => 4.0, "1/2" => 2.0, "1/4" => 1.0, "1/8" => 0.5, _ => 1.0,
                                              };
@@ -3335,10 +5908,9 @@ impl eframe::App for MyApp {
                                       
                                       let c_start = colors[idx % n];
                                       let c_end = colors[(idx + 1) % n];
+                                      let space = m.params.get("gradient_space").and_then(|v| v.as_str()).unwrap_or("rgb");
                                       
-                                      let r = (c_start[0] as f32 * (1.0 - sub_t) + c_end[0] as f32 * sub_t) as u8;
-                                      let g = (c_start[1] as f32 * (1.0 - sub_t) + c_end[1] as f32 * sub_t) as u8;
-                                      let b = (c_start[2] as f32 * (1.0 - sub_t) + c_end[2] as f32 * sub_t) as u8;
+                                      let [r, g, b] = interpolate_color(c_start, c_end, sub_t, space);
                                       
                                       egui::Color32::from_rgb(r, g, b)
                                   }
@@ -3375,6 +5947,22 @@ impl eframe::App for MyApp {
                              ));
                              
                           },
+                         "image" => {
+                             let w = m.params.get("width").and_then(|v| v.as_f64()).unwrap_or(0.5) as f32;
+                             let h = m.params.get("height").and_then(|v| v.as_f64()).unwrap_or(0.5) as f32;
+                             let rot = (m.params.get("rotation").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32).to_radians();
+                             let (sin_r, cos_r) = rot.sin_cos();
+                             let corners: Vec<egui::Pos2> = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)].iter()
+                                 .map(|(sx, sy)| {
+                                     let (lx, ly) = (sx * w / 2.0, sy * h / 2.0);
+                                     to_screen(m.x + lx * cos_r - ly * sin_r, m.y + lx * sin_r + ly * cos_r, &self.view)
+                                 })
+                                 .collect();
+                             painter.add(egui::Shape::convex_polygon(corners, color, egui::Stroke::new(2.0, base_color)));
+                             if !m.params.contains_key("image") {
+                                 painter.text(pos, egui::Align2::CENTER_CENTER, "🖼", egui::FontId::proportional(16.0), base_color);
+                             }
+                         },
                          "radial" => {
                              let r = m.params.get("radius").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
                              let radius_screen = r * rect.width() * self.view.scale; // Width as basis
@@ -3501,8 +6089,56 @@ impl eframe::App for MyApp {
                          _ => {}
                     }
                 }
+
+                // Measurement: line between the two points (or to the cursor while placing the second)
+                let hover_world = response.hover_pos().map(|pos| from_screen(pos, &self.view));
+                if self.view.measuring {
+                    let end = self.view.measure_points.get(1).copied().or(hover_world);
+                    if let (Some(&a), Some(b)) = (self.view.measure_points.first(), end) {
+                        let (pa, pb) = (to_screen(a.0, a.1, &self.view), to_screen(b.0, b.1, &self.view));
+                        let stroke = egui::Stroke::new(1.5, theme.selection);
+                        painter.line_segment([pa, pb], stroke);
+                        for p in [pa, pb] {
+                            painter.circle_stroke(p, 4.0, stroke);
+                        }
+                        let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+                        painter.text(
+                            pa + (pb - pa) * 0.5 + egui::vec2(6.0, -6.0),
+                            egui::Align2::LEFT_BOTTOM,
+                            format!("{}  (Δx {}, Δy {})",
+                                format_canvas_length(dx.hypot(dy), self.state.canvas_unit_meters),
+                                format_canvas_length(dx, self.state.canvas_unit_meters),
+                                format_canvas_length(dy, self.state.canvas_unit_meters)),
+                            egui::FontId::proportional(12.0),
+                            theme.text,
+                        );
+                    }
+                }
+
+                // Cursor readout in canvas units (and meters when a physical scale is set)
+                if let Some((x, y)) = hover_world {
+                    let meters = self.state.canvas_unit_meters;
+                    let readout = if meters > 0.0 {
+                        format!("x {:.3}  y {:.3}   ({:.2} m, {:.2} m)", x, y, x * meters, y * meters)
+                    } else {
+                        format!("x {:.3}  y {:.3}", x, y)
+                    };
+                    painter.text(
+                        rect.left_bottom() + egui::vec2(6.0, -6.0),
+                        egui::Align2::LEFT_BOTTOM,
+                        readout,
+                        egui::FontId::monospace(11.0),
+                        theme.grid_label,
+                    );
+                }
             });
         });
+
+        // A frozen phase only lasts while its mask's editor is open
+        if !phase_preview_shown {
+            self.mask_phase_preview = None;
+        }
+        self.engine.mask_phase_preview = self.mask_phase_preview.map(|(id, phase)| (id, phase as f64));
         
         // Debounced auto-save (saves 5 seconds after last change)
         if let Some(last_change) = self.last_change_time {
@@ -3517,8 +6153,519 @@ impl eframe::App for MyApp {
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
         // Save state when app is closing
         self.save_state();
+        if let Err(e) = self.engine.stop_recording() {
+            eprintln!("Failed to finish recording: {}", e);
+        }
+        self.save_window_geometry();
+        let last_view = ViewBookmark { slot: db::LAST_VIEW_SLOT, offset: [self.view.offset.x, self.view.offset.y], scale: self.view.scale };
+        if let Err(e) = self.db.save_view_bookmark(&last_view) {
+            eprintln!("Failed to save view: {}", e);
+        }
+    }
+}
+
+/// Edge softness slider for scanner and radial masks; true when it changed
+fn edge_softness_control(ui: &mut egui::Ui, params: &mut std::collections::HashMap<String, serde_json::Value>) -> bool {
+    let max = model::mask_param_range("edge_softness").map_or(0.2, |(_, max)| max as f32);
+    let mut softness = params.get("edge_softness").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32;
+    let changed = ui.add(egui::Slider::new(&mut softness, 0.0..=max).text("Edge softness"))
+        .on_hover_text("Fade the mask's outline over this distance instead of cutting it hard")
+        .changed();
+    if changed {
+        params.insert("edge_softness".into(), softness.into());
+    }
+    changed
+}
+
+/// A strip's physical layout: `count` pixels at `pitch_m` meters make it `count × pitch_m` long.
+/// Solves `solve_for` ("count" | "length" | "pitch") from the other two; returns (count, pitch).
+fn solve_strip_layout(solve_for: &str, count: usize, length_m: f32, pitch_m: f32) -> (usize, f32) {
+    match solve_for {
+        "count" => (((length_m / pitch_m).round() as usize).clamp(1, model::MAX_STRIP_PIXELS), pitch_m),
+        "pitch" => (count, length_m / count.max(1) as f32),
+        _ => (count, pitch_m), // "length" follows from count and pitch
+    }
+}
+
+/// Screen position of canvas point (x, y) in `rect` under the view's zoom and pan
+fn canvas_to_screen(rect: egui::Rect, view: &ViewState, x: f32, y: f32) -> egui::Pos2 {
+    egui::pos2(
+        rect.center().x + (x - 0.5) * rect.width() * view.scale + view.offset.x,
+        rect.center().y + (y - 0.5) * rect.height() * view.scale + view.offset.y
+    )
+}
+
+/// Draw a strip's pixels in their output colors, as dots or a polyline per the view.
+/// Pixels outside `rect` are skipped and overlapping dots are thinned out; pixels without
+/// data yet are drawn in `no_data`.
+fn draw_strip_pixels(painter: &egui::Painter, s: &PixelStrip, rect: egui::Rect, view: &ViewState, no_data: egui::Color32) {
+    // Dot size follows on-screen pixel pitch so dense rigs don't blob and sparse rigs stay visible
+    let pitch_screen = s.spacing * rect.width() * view.scale;
+    let dot_size = (pitch_screen * 0.8).clamp(1.5, 12.0);
+    let clip = rect.expand(dot_size);
+    let step = pixel_draw_step(pitch_screen, view.fast_render);
+    let mut prev_screen: Option<egui::Pos2> = None;
+    for i in (0..s.pixel_count).step_by(step) {
+        let (px_world, py_world) = s.pixel_position(i);
+        let px_screen = canvas_to_screen(rect, view, px_world, py_world);
+        let color = s.data.get(i).map_or(no_data, |p| egui::Color32::from_rgb(p[0], p[1], p[2]));
+
+        if view.pixel_lines && s.pixel_count > 1 {
+            // Polyline mode: each segment takes the color of the pixel it leads into
+            if let Some(prev) = prev_screen.filter(|prev| clip.contains(*prev) || clip.contains(px_screen)) {
+                painter.line_segment([prev, px_screen], egui::Stroke::new(dot_size.min(6.0), color));
+            }
+            prev_screen = Some(px_screen);
+        } else if clip.contains(px_screen) {
+            painter.rect_filled(
+                egui::Rect::from_center_size(px_screen, egui::vec2(dot_size, dot_size)),
+                dot_size * 0.25,
+                color
+            );
+        }
+    }
+}
+
+/// Draw every `step`th pixel of a strip whose pixels are `pitch_screen` screen pixels apart.
+/// Dots closer than a pixel are thinned to about one per screen pixel; performance rendering
+/// keeps them at least 4 screen pixels apart.
+fn pixel_draw_step(pitch_screen: f32, fast: bool) -> usize {
+    let min_gap = if fast { 4.0 } else { 1.0 };
+    if pitch_screen <= 0.0 || !pitch_screen.is_finite() {
+        return 1;
+    }
+    ((min_gap / pitch_screen).ceil() as usize).max(1)
+}
+
+/// A canvas length for display: canvas units, plus meters when a physical scale is set
+fn format_canvas_length(units: f32, meters_per_unit: f32) -> String {
+    if meters_per_unit > 0.0 {
+        format!("{:.3} ({:.2} m)", units, units * meters_per_unit)
+    } else {
+        format!("{:.3}", units)
+    }
+}
+
+/// Colors for the layout canvas chrome. LEDs and mask colors are drawn as-is on top.
+struct CanvasTheme {
+    background: egui::Color32,
+    grid: [egui::Color32; 4], // Full, half, quarter and smaller units
+    grid_label: egui::Color32,
+    text: egui::Color32,
+    head: egui::Color32,
+    head_outline: egui::Color32,
+    outline: egui::Color32,
+    selection: egui::Color32,
+}
+
+impl CanvasTheme {
+    /// Theme for an `AppState::canvas_theme` name; unknown names fall back to dark
+    fn named(name: &str) -> Self {
+        use egui::Color32;
+        match name {
+            "light" => Self {
+                background: Color32::from_rgb(235, 235, 238),
+                grid: [
+                    Color32::from_rgba_unmultiplied(120, 120, 120, 180),
+                    Color32::from_rgba_unmultiplied(150, 150, 150, 140),
+                    Color32::from_rgba_unmultiplied(175, 175, 175, 110),
+                    Color32::from_rgba_unmultiplied(195, 195, 195, 80),
+                ],
+                grid_label: Color32::from_rgb(90, 90, 90),
+                text: Color32::BLACK,
+                head: Color32::from_rgb(0, 140, 160),
+                head_outline: Color32::BLACK,
+                outline: Color32::DARK_GRAY,
+                selection: Color32::from_rgb(200, 120, 0),
+            },
+            // Pure black and white with saturated accents, readable on washed-out screens
+            "high_contrast" => Self {
+                background: Color32::BLACK,
+                grid: [
+                    Color32::from_gray(170),
+                    Color32::from_gray(120),
+                    Color32::from_gray(85),
+                    Color32::from_gray(60),
+                ],
+                grid_label: Color32::WHITE,
+                text: Color32::WHITE,
+                head: Color32::from_rgb(0, 255, 255),
+                head_outline: Color32::WHITE,
+                outline: Color32::WHITE,
+                selection: Color32::from_rgb(255, 255, 0),
+            },
+            _ => Self {
+                background: Color32::from_rgb(15, 15, 18),
+                grid: [
+                    Color32::from_rgba_unmultiplied(70, 70, 70, 180),
+                    Color32::from_rgba_unmultiplied(55, 55, 55, 140),
+                    Color32::from_rgba_unmultiplied(45, 45, 45, 110),
+                    Color32::from_rgba_unmultiplied(38, 38, 38, 80),
+                ],
+                grid_label: Color32::from_rgba_unmultiplied(140, 140, 140, 180),
+                text: Color32::WHITE,
+                head: Color32::from_rgb(0, 255, 255),
+                head_outline: Color32::BLACK,
+                outline: Color32::GRAY,
+                selection: Color32::YELLOW,
+            },
+        }
+    }
+}
+
+/// Set universe and start channel on the strips a patch names; strips since removed are skipped
+fn apply_strip_patch(strips: &mut [PixelStrip], patch: &[model::StripPatch]) {
+    for &(id, universe, start) in patch {
+        if let Some(s) = strips.iter_mut().find(|s| s.id == id) {
+            s.universe = universe;
+            s.start_channel = start;
+        }
+    }
+}
+
+/// DMX start channel editor. `channel` is stored 1-based; `base` (0 or 1) only changes
+/// how it is displayed and entered.
+fn channel_drag(ui: &mut egui::Ui, channel: &mut u16, base: u16) -> egui::Response {
+    let mut shown = (*channel).clamp(1, 512) - 1 + base;
+    let response = ui.add(egui::DragValue::new(&mut shown).prefix("Ch: ").clamp_range(base..=511 + base))
+        .on_hover_text(if base == 0 { "DMX start channel, 0-based (0..511)" } else { "DMX start channel, 1-based (1..512)" });
+    *channel = shown + 1 - base;
+    response
+}
+
+/// Per-strip stagger controls shared by effects that run along strips
+fn stagger_controls(ui: &mut egui::Ui, params: &mut std::collections::HashMap<String, serde_json::Value>, id: String) {
+    let mut stagger = params.get("stagger").and_then(|v| v.as_f64()).unwrap_or(0.0);
+    if ui.add(egui::Slider::new(&mut stagger, -1.0..=1.0).text("Stagger (cycles)"))
+        .on_hover_text("Phase offset between parallel strips; 0 keeps them in unison")
+        .changed() {
+        params.insert("stagger".into(), stagger.into());
+    }
+    if stagger != 0.0 {
+        ui.horizontal(|ui| {
+            ui.label("Stagger by:");
+            let mut by = params.get("stagger_by").and_then(|v| v.as_str().map(String::from)).unwrap_or("index".into());
+            egui::ComboBox::from_id_source(id)
+                .selected_text(if by == "position" { "Position" } else { "Strip order" })
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut by, "index".into(), "Strip order");
+                    ui.selectable_value(&mut by, "position".into(), "Position");
+                });
+            params.insert("stagger_by".into(), serde_json::json!(by));
+        });
+    }
+}
+
+/// Canvas-space bounding box [min_x, min_y, max_x, max_y] of every pixel of `strips`, the
+/// `fixtures` and the extent of `masks` (resolved against `all_strips`). None if there's nothing.
+fn content_bounds(strips: &[PixelStrip], fixtures: &[model::Fixture], masks: &[Mask], all_strips: &[PixelStrip]) -> Option<[f32; 4]> {
+    let mut bounds: Option<[f32; 4]> = None;
+    let mut add = |x0: f32, y0: f32, x1: f32, y1: f32| {
+        if !(x0.is_finite() && y0.is_finite() && x1.is_finite() && y1.is_finite()) {
+            return;
+        }
+        let b = bounds.get_or_insert([x0, y0, x1, y1]);
+        *b = [b[0].min(x0), b[1].min(y0), b[2].max(x1), b[3].max(y1)];
+    };
+    for s in strips {
+        for i in 0..s.pixel_count.max(1) {
+            let (x, y) = s.pixel_position(i);
+            add(x, y, x, y);
+        }
+    }
+    for f in fixtures {
+        add(f.x, f.y, f.x, f.y);
+    }
+    for m in masks {
+        let (x, y) = m.resolved_position(all_strips);
+        let param = |key: &str, default: f64| m.params.get(key).and_then(|v| v.as_f64()).unwrap_or(default) as f32;
+        let (hw, hh) = match m.mask_type.as_str() {
+            "radial" => (param("radius", 0.2), param("radius", 0.2)),
+            "burst" => (param("max_radius", 0.5), param("max_radius", 0.5)),
+            _ => (param("width", 0.3) / 2.0, param("height", 0.3) / 2.0),
+        };
+        add(x - hw, y - hh, x + hw, y + hh);
+    }
+    bounds
+}
+
+/// View scale and offset that fit `bounds` (plus `padding` on every side) into `margin` of the canvas
+fn fit_view(bounds: [f32; 4], padding: f32, margin: f32, canvas: egui::Vec2) -> (f32, egui::Vec2) {
+    let [min_x, min_y, max_x, max_y] = bounds;
+    // A single point or a straight line still needs some extent to fit
+    let w = (max_x - min_x + 2.0 * padding).max(0.05);
+    let h = (max_y - min_y + 2.0 * padding).max(0.05);
+    // Normalized 0..1 fills the canvas at scale 1
+    let scale = ((1.0 / w).min(1.0 / h) * margin).clamp(0.1, 100.0);
+    let cx = (min_x + max_x) / 2.0;
+    let cy = (min_y + max_y) / 2.0;
+    (scale, egui::vec2(-(cx - 0.5) * canvas.x * scale, -(cy - 0.5) * canvas.y * scale))
+}
+
+/// Audio band selectors for each mask property an audio band can drive. Returns true on change.
+fn audio_mod_controls(ui: &mut egui::Ui, params: &mut std::collections::HashMap<String, serde_json::Value>, mask_id: u64) -> bool {
+    let mut changed = false;
+    let band_label = |band: &str| match band {
+        "volume" => "Volume",
+        "transient" => "Transients",
+        "bass" => "Bass",
+        "mid" => "Mid",
+        "treble" => "Treble",
+        _ => "Off",
+    };
+    egui::CollapsingHeader::new("Audio modulation")
+        .id_source(format!("audio_mod_{}", mask_id))
+        .show(ui, |ui| {
+            for target in model::AUDIO_MOD_TARGETS {
+                let key = format!("audio_{}_band", target);
+                let mut band = params.get(&key).and_then(|v| v.as_str()).unwrap_or("off").to_string();
+                ui.horizontal(|ui| {
+                    ui.label(format!("{}:", target[..1].to_uppercase() + &target[1..]));
+                    egui::ComboBox::from_id_source(format!("{}_{}", key, mask_id))
+                        .selected_text(band_label(&band))
+                        .show_ui(ui, |ui| {
+                            for b in ["off", "volume", "transient"].into_iter().chain(audio::BAND_NAMES) {
+                                ui.selectable_value(&mut band, b.to_string(), band_label(b));
+                            }
+                        });
+                });
+                if params.get(&key).and_then(|v| v.as_str()).unwrap_or("off") != band {
+                    params.insert(key, serde_json::json!(band));
+                    changed = true;
+                }
+            }
+            let mut gain = params.get("audio_gain").and_then(|v| v.as_f64()).unwrap_or(4.0) as f32;
+            if ui.add(egui::Slider::new(&mut gain, 0.5..=20.0).logarithmic(true).text("Gain"))
+                .on_hover_text("How hard the band drives its targets; raise it for quiet inputs")
+                .changed()
+            {
+                params.insert("audio_gain".into(), gain.into());
+                changed = true;
+            }
+        });
+    changed
+}
+
+/// Feature pickers for the "audio" color mode. Returns true on change.
+fn audio_color_controls(ui: &mut egui::Ui, params: &mut std::collections::HashMap<String, serde_json::Value>, mask_id: u64, has_audio: bool) -> bool {
+    let mut changed = false;
+    let source_label = |source: &str| match source {
+        "centroid" => "Dominant frequency",
+        "volume" => "Volume",
+        "transient" => "Transients",
+        "bass" => "Bass",
+        "mid" => "Mid",
+        "treble" => "Treble",
+        _ => "Full",
+    };
+    let rows = [
+        ("audio_hue_source", "Hue from:", "centroid", ["centroid", "volume", "transient"]),
+        ("audio_value_source", "Brightness from:", "volume", ["off", "volume", "transient"]),
+    ];
+    for (key, label, default, extra) in rows {
+        let mut source = params.get(key).and_then(|v| v.as_str()).unwrap_or(default).to_string();
+        ui.horizontal(|ui| {
+            ui.label(label);
+            egui::ComboBox::from_id_source(format!("{}_{}", key, mask_id))
+                .selected_text(source_label(&source))
+                .show_ui(ui, |ui| {
+                    for s in extra.into_iter().chain(audio::BAND_NAMES) {
+                        ui.selectable_value(&mut source, s.to_string(), source_label(s));
+                    }
+                });
+        });
+        if params.get(key).and_then(|v| v.as_str()).unwrap_or(default) != source {
+            params.insert(key.into(), serde_json::json!(source));
+            changed = true;
+        }
+    }
+    let mut gain = params.get("audio_gain").and_then(|v| v.as_f64()).unwrap_or(4.0) as f32;
+    if ui.add(egui::Slider::new(&mut gain, 0.5..=20.0).logarithmic(true).text("Gain"))
+        .on_hover_text("Scales band levels; dominant frequency is used as is")
+        .changed()
+    {
+        params.insert("audio_gain".into(), gain.into());
+        changed = true;
+    }
+    if !has_audio {
+        ui.colored_label(egui::Color32::YELLOW, "No audio input: the mask shows its static color");
+    }
+    changed
+}
+
+/// Design aid: hold a mask's motion and color cycle at a chosen point instead of the live clock
+fn phase_preview_controls(ui: &mut egui::Ui, preview: &mut Option<(u64, f32)>, mask_id: u64) {
+    ui.horizontal(|ui| {
+        let mut frozen = preview.is_some_and(|(id, _)| id == mask_id);
+        if ui.checkbox(&mut frozen, "Freeze phase")
+            .on_hover_text("Scrub the animation by hand. Live motion resumes when you close this mask.")
+            .changed()
+        {
+            *preview = frozen.then_some((mask_id, 0.0));
+        }
+        if let Some((_, phase)) = preview.as_mut().filter(|(id, _)| *id == mask_id) {
+            ui.add(egui::Slider::new(phase, 0.0..=1.0).text("Phase"));
+        }
+    });
+}
+
+/// A copy of a template mask that starts its lifetime now. `clock` is (animation seconds, beat).
+fn spawn_temporary(template: &Mask, clock: (f64, f64)) -> Mask {
+    let mut m = template.clone();
+    m.id = rand::random();
+    let now = if m.lifetime_beats() { clock.1 } else { clock.0 };
+    m.params.insert("spawn_time".into(), now.into());
+    m
+}
+
+/// Lifetime for temporary masks. Without a spawn time the mask is a template that stays put;
+/// spawned copies fade out and remove themselves. Returns true on change.
+fn lifetime_controls(ui: &mut egui::Ui, params: &mut std::collections::HashMap<String, serde_json::Value>, mask_id: u64, clock: (f64, f64)) -> bool {
+    let mut changed = false;
+    egui::CollapsingHeader::new("Lifetime")
+        .id_source(format!("lifetime_{}", mask_id))
+        .show(ui, |ui| {
+            let mut temporary = params.contains_key("lifetime");
+            if ui.checkbox(&mut temporary, "Expires").on_hover_text("Fade out and remove the mask after a set time").changed() {
+                if temporary {
+                    params.insert("lifetime".into(), 4.0.into());
+                } else {
+                    params.remove("lifetime");
+                    params.remove("spawn_time");
+                }
+                changed = true;
+            }
+            if !temporary {
+                return;
+            }
+            let mut beats = params.get("lifetime_unit").and_then(|v| v.as_str()) == Some("beats");
+            let mut lifetime = params.get("lifetime").and_then(|v| v.as_f64()).unwrap_or(4.0);
+            ui.horizontal(|ui| {
+                if ui.add(egui::DragValue::new(&mut lifetime).clamp_range(0.1..=64.0).speed(0.1)).changed() {
+                    params.insert("lifetime".into(), lifetime.into());
+                    changed = true;
+                }
+                let was_beats = beats;
+                ui.selectable_value(&mut beats, false, "Seconds");
+                ui.selectable_value(&mut beats, true, "Beats");
+                if beats != was_beats {
+                    params.insert("lifetime_unit".into(), serde_json::json!(if beats { "beats" } else { "seconds" }));
+                    // Spawn times don't convert between units, so stop any running countdown
+                    params.remove("spawn_time");
+                    changed = true;
+                }
+            });
+            let running = params.contains_key("spawn_time");
+            ui.horizontal(|ui| {
+                if ui.button("▶ Start").on_hover_text("Start this mask's own countdown now").clicked() {
+                    params.insert("spawn_time".into(), (if beats { clock.1 } else { clock.0 }).into());
+                    changed = true;
+                }
+                if running && ui.button("⏹ Keep").on_hover_text("Stop the countdown; the mask becomes a template again").clicked() {
+                    params.remove("spawn_time");
+                    changed = true;
+                }
+            });
+            if !running {
+                ui.weak("Template: use ✨ Spawn to add copies that expire");
+            }
+        });
+    changed
+}
+
+/// Drag one edge of a mask by `delta` screen pixels, keeping the opposite edge fixed.
+/// `px_w`/`px_h` are the screen size of one normalized canvas unit. Sizes stay within the
+/// ranges loading clamps to, so a resized mask comes back the same size.
+fn resize_mask(m: &mut Mask, edge_idx: usize, delta: egui::Vec2, px_w: f32, px_h: f32) {
+    let (min_size, max_size) = model::mask_param_range("width").unwrap_or((0.01, 2.0));
+    let (min_size, max_size) = (min_size as f32, max_size as f32);
+    match m.mask_type.as_str() {
+        "scanner" | "orbit" | "image" => {
+            let default_size = if m.mask_type == "scanner" { 0.1 } else { 0.3 };
+            let w = m.params.get("width").and_then(|v| v.as_f64()).unwrap_or(default_size) as f32;
+            let h = m.params.get("height").and_then(|v| v.as_f64()).unwrap_or(default_size) as f32;
+            // Orbit has no rotation; scanners and images resize along their local axes
+            let rot = if m.mask_type != "orbit" {
+                (m.params.get("rotation").and_then(|v| v.as_f64()).unwrap_or(0.0) as f32).to_radians()
+            } else {
+                0.0
+            };
+            let cos_r = rot.cos();
+            let sin_r = rot.sin();
+            let ldx_scr = delta.x * cos_r + delta.y * sin_r;
+            let ldy_scr = -delta.x * sin_r + delta.y * cos_r;
+            let w_scr = w * px_w;
+            let h_scr = h * px_h;
+            let mut new_w_scr = w_scr;
+            let mut new_h_scr = h_scr;
+            let mut shift_lx_scr = 0.0;
+            let mut shift_ly_scr = 0.0;
+            // Shift by half the actual size change so the clamped minimum doesn't drift the mask
+            let max_w_scr = max_size * px_w;
+            let max_h_scr = max_size * px_h;
+            match edge_idx {
+                0 => { new_h_scr = (h_scr - ldy_scr).clamp(1.0, max_h_scr); shift_ly_scr = -(new_h_scr - h_scr) / 2.0; },
+                1 => { new_w_scr = (w_scr + ldx_scr).clamp(1.0, max_w_scr); shift_lx_scr = (new_w_scr - w_scr) / 2.0; },
+                2 => { new_h_scr = (h_scr + ldy_scr).clamp(1.0, max_h_scr); shift_ly_scr = (new_h_scr - h_scr) / 2.0; },
+                3 => { new_w_scr = (w_scr - ldx_scr).clamp(1.0, max_w_scr); shift_lx_scr = -(new_w_scr - w_scr) / 2.0; },
+                _ => {}
+            }
+            let wx_shift_scr = shift_lx_scr * cos_r - shift_ly_scr * sin_r;
+            let wy_shift_scr = shift_lx_scr * sin_r + shift_ly_scr * cos_r;
+            m.x += wx_shift_scr / px_w;
+            m.y += wy_shift_scr / px_h;
+            m.params.insert("width".to_string(), (new_w_scr / px_w).clamp(min_size, max_size).into());
+            m.params.insert("height".to_string(), (new_h_scr / px_h).clamp(min_size, max_size).into());
+        },
+        "radial" => {
+            let max_radius = model::mask_param_range("radius").map_or(5.0, |(_, max)| max as f32);
+            let r = m.params.get("radius").and_then(|v| v.as_f64()).unwrap_or(0.1) as f32;
+            m.params.insert("radius".to_string(), (r + delta.x / px_w).clamp(0.01, max_radius).into());
+        },
+        _ => {}
     }
 }
+
+/// Numeric mask params exposed in the batch edit window: (key, label). Ranges come from
+/// `model::MASK_NUMERIC_PARAMS`.
+const BATCH_EDIT_PARAMS: [(&str, &str); 10] = [
+    ("speed", "Speed"),
+    ("rotation", "Rotation"),
+    ("width", "Width"),
+    ("height", "Height"),
+    ("bar_width", "Bar Width"),
+    ("radius", "Radius"),
+    ("base_radius", "Base Radius"),
+    ("max_radius", "Max Radius"),
+    ("sensitivity", "Sensitivity"),
+    ("decay", "Decay Speed"),
+];
+
+// Ctrl/Cmd-click toggles a mask in the selection, plain click selects only that mask
+fn toggle_mask_selection(selection: &mut std::collections::HashSet<u64>, id: u64, additive: bool) {
+    if additive {
+        if !selection.remove(&id) {
+            selection.insert(id);
+        }
+    } else {
+        selection.clear();
+        selection.insert(id);
+    }
+}
+
+/// Beat-synced rate picker over `SYNC_RATES`; true when a new rate was picked
+fn rate_combo(ui: &mut egui::Ui, rate: &mut String, id_source: impl std::hash::Hash) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_source(id_source)
+        .selected_text(rate.clone())
+        .show_ui(ui, |ui| {
+            for r in model::SYNC_RATES {
+                changed |= ui.selectable_value(rate, r.to_string(), r).changed();
+            }
+        });
+    changed
+}
+
 // Simple RGB color picker helper with Hex Input
 fn color_picker(ui: &mut egui::Ui, rgb: &mut [u8; 3], id_source: impl std::hash::Hash) -> bool {
     let mut changed = false;
@@ -3561,7 +6708,7 @@ fn color_picker(ui: &mut egui::Ui, rgb: &mut [u8; 3], id_source: impl std::hash:
     changed
 }
 
-/// Renders LFO controls for a given parameter
+/// Renders LFO controls for a given parameter, plus an optional second LFO once the first is on
 /// Returns true if any value changed
 fn lfo_controls(
     ui: &mut egui::Ui,
@@ -3569,7 +6716,29 @@ fn lfo_controls(
     param_name: &str,
     id_source: impl std::hash::Hash + std::fmt::Debug,
 ) -> bool {
-    let lfo_key = |suffix: &str| format!("{}_lfo_{}", param_name, suffix);
+    let prefix = format!("{}_lfo", param_name);
+    let mut changed = lfo_slot_controls(ui, params, &prefix, "LFO", format!("{:?}", id_source));
+
+    let first_enabled = params.get(&format!("{}_enabled", prefix))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if first_enabled {
+        let prefix2 = format!("{}_lfo2", param_name);
+        changed |= lfo_slot_controls(ui, params, &prefix2, "LFO 2", format!("{:?}_2", id_source));
+    }
+
+    changed
+}
+
+/// Renders the controls of a single LFO whose keys start with `prefix`
+fn lfo_slot_controls(
+    ui: &mut egui::Ui,
+    params: &mut std::collections::HashMap<String, serde_json::Value>,
+    prefix: &str,
+    label: &str,
+    id_source: String,
+) -> bool {
+    let lfo_key = |suffix: &str| format!("{}_{}", prefix, suffix);
     let mut changed = false;
 
     let mut enabled = params.get(&lfo_key("enabled"))
@@ -3577,7 +6746,7 @@ fn lfo_controls(
         .unwrap_or(false);
 
     ui.horizontal(|ui| {
-        if ui.checkbox(&mut enabled, "LFO").changed() {
+        if ui.checkbox(&mut enabled, label).changed() {
             params.insert(lfo_key("enabled"), enabled.into());
             changed = true;
         }
@@ -3599,7 +6768,7 @@ fn lfo_controls(
             .unwrap_or("sine")
             .to_string();
 
-        egui::ComboBox::from_id_source(format!("{:?}_wave", id_source))
+        egui::ComboBox::from_id_source(format!("{}_wave", id_source))
             .selected_text(&waveform)
             .show_ui(ui, |ui| {
                 if ui.selectable_label(waveform == "sine", "Sine").clicked() {
@@ -3614,11 +6783,43 @@ fn lfo_controls(
                     waveform = "sawtooth".into();
                     changed = true;
                 }
+                if ui.selectable_label(waveform == "ease", "Ease In-Out").on_hover_text("Lingers at the peaks longer than a sine").clicked() {
+                    waveform = "ease".into();
+                    changed = true;
+                }
+                if ui.selectable_label(waveform == "hold", "Hold").on_hover_text("Ramps at constant speed, then rests at each peak").clicked() {
+                    waveform = "hold".into();
+                    changed = true;
+                }
             });
 
         if changed {
             params.insert(lfo_key("waveform"), serde_json::json!(waveform));
         }
+
+        let mut mode = params.get(&lfo_key("mode"))
+            .and_then(|v| v.as_str())
+            .unwrap_or("bipolar")
+            .to_string();
+        let mut mode_changed = false;
+
+        egui::ComboBox::from_id_source(format!("{}_mode", id_source))
+            .selected_text(if mode == "unipolar" { "Unipolar" } else { "Bipolar" })
+            .show_ui(ui, |ui| {
+                if ui.selectable_label(mode != "unipolar", "Bipolar").on_hover_text("Swing above and below the base value").clicked() {
+                    mode = "bipolar".into();
+                    mode_changed = true;
+                }
+                if ui.selectable_label(mode == "unipolar", "Unipolar").on_hover_text("Only dip below the base value").clicked() {
+                    mode = "unipolar".into();
+                    mode_changed = true;
+                }
+            });
+
+        if mode_changed {
+            params.insert(lfo_key("mode"), serde_json::json!(mode));
+            changed = true;
+        }
     });
 
     if enabled {
@@ -3638,29 +6839,53 @@ fn lfo_controls(
                     .unwrap_or("1/4")
                     .to_string();
 
-                egui::ComboBox::from_id_source(format!("{:?}_rate", id_source))
-                    .selected_text(&rate)
-                    .show_ui(ui, |ui| {
-                        for r in ["4 Bar", "2 Bar", "1 Bar", "1/2", "1/4", "1/8"] {
-                            if ui.selectable_label(rate == r, r).clicked() {
-                                rate = r.into();
-                                changed = true;
-                            }
-                        }
-                    });
+                changed |= rate_combo(ui, &mut rate, format!("{}_rate", id_source));
 
                 if changed {
                     params.insert(lfo_key("rate"), serde_json::json!(rate));
                 }
             } else {
+                // Rate is always stored as Hz; "period" only changes how it's entered
                 let mut hz = params.get(&lfo_key("hz"))
                     .and_then(|v| v.as_f64())
                     .unwrap_or(1.0);
-                if ui.add(egui::Slider::new(&mut hz, 0.1..=10.0).text("Hz")).changed() {
+                let mut unit = params.get(&lfo_key("rate_unit"))
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("hz")
+                    .to_string();
+
+                egui::ComboBox::from_id_source(format!("{}_unit", id_source))
+                    .selected_text(if unit == "period" { "Period" } else { "Hz" })
+                    .width(60.0)
+                    .show_ui(ui, |ui| {
+                        for (value, text) in [("hz", "Hz"), ("period", "Period")] {
+                            if ui.selectable_label(unit == value, text).clicked() && unit != value {
+                                unit = value.into();
+                                params.insert(lfo_key("rate_unit"), serde_json::json!(unit));
+                            }
+                        }
+                    });
+
+                if unit == "period" {
+                    let (min_hz, max_hz) = model::LFO_HZ_RANGE;
+                    let mut period = 1.0 / hz.clamp(min_hz, max_hz);
+                    if ui.add(egui::Slider::new(&mut period, 1.0 / max_hz..=1.0 / min_hz).logarithmic(true).suffix(" s").text("per cycle")).changed() {
+                        params.insert(lfo_key("hz"), (1.0 / period).into());
+                        changed = true;
+                    }
+                } else if ui.add(egui::Slider::new(&mut hz, 0.1..=10.0).text("Hz")).changed() {
                     params.insert(lfo_key("hz"), hz.into());
                     changed = true;
                 }
             }
+
+            let mut phase = params.get(&lfo_key("phase"))
+                .and_then(|v| v.as_f64())
+                .unwrap_or(0.0);
+            if ui.add(egui::Slider::new(&mut phase, 0.0..=1.0).text("Phase")).changed() {
+                params.insert(lfo_key("phase"), phase.into());
+                changed = true;
+            }
         });
     }
 
@@ -3737,6 +6962,34 @@ const LAUNCHPAD_PALETTE: [(u8, u8, u8); 128] = [
     (156, 0, 0), (52, 0, 0), (24, 204, 0), (4, 64, 0), (180, 172, 0), (60, 48, 0), (176, 80, 0), (72, 20, 0),
 ];
 
+/// Top-row arrow buttons (programmer mode) that step through scene pages
+const LAUNCHPAD_PAGE_UP_CC: u8 = 91;
+const LAUNCHPAD_PAGE_DOWN_CC: u8 = 92;
+
+/// Clear the Launchpad, light the current page's scene buttons and the page arrows that lead somewhere
+fn send_launchpad_colors(sender: &Sender<midi::MidiCommand>, state: &model::AppState) {
+    let _ = sender.send(midi::MidiCommand::ClearAll);
+    for s in state.scenes.iter().filter(|s| s.page == state.launchpad_page) {
+        if let (Some(btn), Some(col)) = (s.launchpad_btn, s.launchpad_color) {
+            let cmd = if s.launchpad_is_cc {
+                midi::MidiCommand::SetButtonColor { cc: btn, color: col }
+            } else {
+                midi::MidiCommand::SetPadColor { note: btn, color: col }
+            };
+            let _ = sender.send(cmd);
+        }
+    }
+    let arrow = |lit: bool| if lit { 3 } else { 0 }; // 3 = white
+    for (cc, lit) in [
+        (LAUNCHPAD_PAGE_UP_CC, state.launchpad_page > 0),
+        (LAUNCHPAD_PAGE_DOWN_CC, state.launchpad_page + 1 < model::SCENE_PAGES),
+    ] {
+        if state.launchpad_scene(cc, true).is_none() {
+            let _ = sender.send(midi::MidiCommand::SetButtonColor { cc, color: arrow(lit) });
+        }
+    }
+}
+
 fn launchpad_color_to_egui(code: u8) -> egui::Color32 {
     let idx = code as usize;
     if idx < LAUNCHPAD_PALETTE.len() {
@@ -7,6 +7,7 @@ use log::{info, debug, warn, error};
 
 pub enum MidiEvent {
     NoteOn { note: u8, velocity: u8 },
+    NoteOff { note: u8 },
     ControlChange { controller: u8, value: u8 },
     Connected,
     Disconnected,
@@ -22,6 +23,10 @@ pub struct MidiConnectionPayload {
 pub enum MidiCommand {
     SetPadColor { note: u8, color: u8 },
     SetButtonColor { cc: u8, color: u8 },
+    /// Plain control change on channel 1, for fader feedback
+    ControlChange { cc: u8, value: u8 },
+    /// Status light on a pad (note) or button (CC); `pulse` uses the Launchpad's built-in pulsing channel
+    SetHeartbeat { button: u8, is_cc: bool, color: u8, pulse: bool },
     ClearAll,
     Connect(Box<MidiConnectionPayload>),
     Disconnect,
@@ -185,17 +190,20 @@ fn run_midi_loop(
                         let vel = message[2];
                         if vel > 0 {
                             let _ = tx.send(MidiEvent::NoteOn { note, velocity: vel });
+                        } else {
+                            // Note On with velocity 0 is a release
+                            let _ = tx.send(MidiEvent::NoteOff { note });
                         }
                     }
+                    0x80 => {
+                        let _ = tx.send(MidiEvent::NoteOff { note: message[1] });
+                    }
                     0xB0 => {
-                        let cc = message[1];
-                        let val = message[2];
-                        if val > 0 {
-                            let _ = tx.send(MidiEvent::ControlChange {
-                                controller: cc,
-                                value: val,
-                            });
-                        }
+                        // Value 0 is a button release; forwarded so held mappings can end
+                        let _ = tx.send(MidiEvent::ControlChange {
+                            controller: message[1],
+                            value: message[2],
+                        });
                     }
                     _ => {}
                 }
@@ -235,6 +243,13 @@ fn run_midi_loop(
                 MidiCommand::SetButtonColor { cc, color } => {
                      conn_out.send(&[0xB0, cc, color])?; 
                 },
+                MidiCommand::ControlChange { cc, value } => {
+                    conn_out.send(&[0xB0, cc, value.min(127)])?;
+                },
+                MidiCommand::SetHeartbeat { button, is_cc, color, pulse } => {
+                    let status = if is_cc { 0xB0 } else { 0x90 } + if pulse { 2 } else { 0 }; // Channel 3 pulses
+                    conn_out.send(&[status, button, color])?;
+                },
                 MidiCommand::ClearAll => {
                     for i in 0..127 {
                          conn_out.send(&[0x90, i, 0])?;
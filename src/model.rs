@@ -23,20 +23,43 @@ impl Default for GlobalEffect {
 pub struct Scene {
     pub id: u64,
     pub name: String,
-    pub kind: String,                  // "Masks" | "Global"
+    pub kind: String,                  // "Masks" | "Global" | "Blackout" (Masks/Global only pick defaults)
     #[serde(default = "default_category")]
     pub category: String,              // User-defined category for organization
     #[serde(default)]
-    pub masks: Vec<Mask>,              // used when kind=="Masks"
+    pub masks: Vec<Mask>,              // overlay, drawn on top of global_effects
     pub global: Option<GlobalEffect>,  // DEPRECATED: used only for migration
     #[serde(default)]
-    pub global_effects: Vec<GlobalEffectConfig>, // used when kind=="Global"
+    pub global_effects: Vec<GlobalEffectConfig>, // base layer, rendered before masks
     #[serde(default)]
     pub launchpad_btn: Option<u8>,
     #[serde(default)]
     pub launchpad_is_cc: bool,
     #[serde(default)]
     pub launchpad_color: Option<u8>,
+    #[serde(default = "default_master_intensity")]
+    pub master_intensity: f32,         // Scene submaster, 0..1 multiplier over the rendered output
+    #[serde(default = "default_compositing")]
+    pub compositing: String,           // "add" (masks added over base) | "replace" (masks replace base where lit) | "base_over" (base drawn over masks)
+    #[serde(default)]
+    pub page: u8,                      // Launchpad page (0-based); pad mappings only apply on this page
+    #[serde(default = "default_audio_reactive")]
+    pub audio_reactive: bool,          // Masks may read audio; off = audio-driven masks render static
+}
+
+/// Number of scene pages; each page is a full Launchpad grid
+pub const SCENE_PAGES: u8 = 8;
+
+fn default_master_intensity() -> f32 {
+    1.0
+}
+
+fn default_audio_reactive() -> bool {
+    true
+}
+
+fn default_compositing() -> String {
+    "add".to_string()
 }
 
 fn default_category() -> String {
@@ -53,13 +76,244 @@ pub struct PixelStrip {
     pub y: f32, // Normalized 0..1
     pub spacing: f32, // Relative spacing 0..1
     #[serde(default)]
-    pub flipped: bool, // true = 180 deg (Left), false = 0 deg (Right)
+    pub flipped: bool, // Data-in end: false = left end (strip.x), true = right end
     #[serde(default = "default_color_order")]
     pub color_order: String, // "RGB", "GRB", "BGR"
+    #[serde(default)]
+    pub lut: Option<ColorLut>, // Per-channel calibration curve, None = identity
+    #[serde(default = "default_dimmer_curve")]
+    pub dimmer_curve: String, // Driver response, one of DIMMER_CURVES; applied before the LUT
+    #[serde(default)]
+    pub channel_prefix: Vec<u8>, // Static values sent at start_channel ahead of the pixels (e.g. a master dimmer)
+    #[serde(default)]
+    pub pixel_offset: usize, // Where this strip starts within a longer logical run, for index-based effects
+    #[serde(default = "default_channels_per_pixel")]
+    pub channels_per_pixel: u8, // 3 = color pixels, 1 = one brightness channel per pixel (dimmers, white-only)
+    #[serde(default = "default_luma_weights")]
+    pub luma_weights: [f32; 3], // R, G, B share of a 1-channel pixel's brightness
     #[serde(skip)]
     pub data: Vec<[u8; 3]>, // RGB Data
 }
 
+/// Upper bound for `channel_prefix` length
+pub const MAX_CHANNEL_PREFIX: usize = 16;
+
+/// Upper bound for `pixel_count`: 12 full universes of RGB, enough for any real run and small
+/// enough that a typo can't allocate the app to death.
+pub const MAX_STRIP_PIXELS: usize = 2040;
+
+impl PixelStrip {
+    /// DMX channels the strip occupies from `start_channel`: prefix plus 3 (or 1) per pixel
+    pub fn channel_span(&self) -> usize {
+        self.channel_prefix.len() + self.pixel_count * self.channels_per_pixel as usize
+    }
+
+    /// Brightness of a 1-channel pixel: RGB weighted by `luma_weights`, scaled so white is full
+    pub fn luma(&self, rgb: [u8; 3]) -> u8 {
+        let total: f32 = self.luma_weights.iter().sum();
+        if total <= 0.0 {
+            return 0;
+        }
+        let sum: f32 = rgb.iter().zip(self.luma_weights).map(|(c, w)| *c as f32 * w).sum();
+        (sum / total).round().clamp(0.0, 255.0) as u8
+    }
+
+    /// World position of pixel `i`. The strip always spans x..x+length; pixel 0 is the
+    /// data-in end, which is the right end when `flipped`.
+    pub fn pixel_position(&self, i: usize) -> (f32, f32) {
+        let steps = if self.flipped { self.pixel_count.saturating_sub(1).saturating_sub(i) } else { i };
+        (self.x + steps as f32 * self.spacing, self.y)
+    }
+
+    /// Clamp `pixel_count` to 1..=MAX_STRIP_PIXELS, `start_channel` to a 1-based DMX
+    /// address and the channel prefix to MAX_CHANNEL_PREFIX, and size `data` to match.
+    pub fn sanitize(&mut self) {
+        self.pixel_count = self.pixel_count.clamp(1, MAX_STRIP_PIXELS);
+        self.start_channel = self.start_channel.clamp(1, 512);
+        self.channel_prefix.truncate(MAX_CHANNEL_PREFIX);
+        if !DIMMER_CURVES.iter().any(|(name, _)| *name == self.dimmer_curve) {
+            self.dimmer_curve = default_dimmer_curve();
+        }
+        if self.channels_per_pixel != 1 {
+            self.channels_per_pixel = default_channels_per_pixel();
+        }
+        if !self.luma_weights.iter().all(|w| w.is_finite() && *w >= 0.0) {
+            self.luma_weights = default_luma_weights();
+        }
+        self.data.resize(self.pixel_count, [0, 0, 0]);
+    }
+}
+
+/// Named dimmer response curves: (name, label). Gamma is a fixed 2.2.
+pub const DIMMER_CURVES: [(&str, &str); 4] = [
+    ("linear", "Linear"),
+    ("gamma", "Gamma 2.2"),
+    ("s_curve", "S-curve"),
+    ("log", "Logarithmic"),
+];
+
+fn default_dimmer_curve() -> String {
+    "linear".to_string()
+}
+
+fn default_channels_per_pixel() -> u8 {
+    3
+}
+
+/// Rec. 709 luma: how bright each primary looks
+pub fn default_luma_weights() -> [f32; 3] {
+    [0.2126, 0.7152, 0.0722]
+}
+
+/// Output level for `v` under a dimmer curve; unknown curves are linear
+fn dimmer_curve_value(curve: &str, v: u8) -> u8 {
+    let x = v as f32 / 255.0;
+    let y = match curve {
+        "gamma" => x.powf(2.2),
+        // Smoothstep: gentle at both ends, steep through the middle
+        "s_curve" => x * x * (3.0 - 2.0 * x),
+        // Fast rise at the bottom for drivers that crush low levels
+        "log" => (1.0 + 99.0 * x).ln() / 100f32.ln(),
+        _ => x,
+    };
+    (y * 255.0).round().clamp(0.0, 255.0) as u8
+}
+
+/// 256-entry table for a dimmer curve, built once per curve. None for linear (and unknown) curves.
+pub fn dimmer_curve_lut(curve: &str) -> Option<&'static [u8; 256]> {
+    static TABLES: std::sync::OnceLock<Vec<[u8; 256]>> = std::sync::OnceLock::new();
+    let idx = DIMMER_CURVES.iter().position(|(name, _)| *name == curve).filter(|i| *i > 0)?;
+    let tables = TABLES.get_or_init(|| {
+        DIMMER_CURVES.iter()
+            .map(|(name, _)| std::array::from_fn(|v| dimmer_curve_value(name, v as u8)))
+            .collect()
+    });
+    tables.get(idx)
+}
+
+/// Every wire order a 3-channel pixel can use
+pub const COLOR_ORDERS: [&str; 6] = ["RGB", "RBG", "GRB", "GBR", "BRG", "BGR"];
+
+/// Which color component (0 = R, 1 = G, 2 = B) each wire slot carries; unknown orders are RGB
+pub fn color_order_indices(order: &str) -> [usize; 3] {
+    let mut indices = [0, 1, 2];
+    if order.len() == 3 && COLOR_ORDERS.contains(&order) {
+        for (slot, c) in order.chars().enumerate() {
+            indices[slot] = "RGB".find(c).unwrap_or(slot);
+        }
+    }
+    indices
+}
+
+/// The order a strip really has, given its configured `order` and the color the user saw
+/// (0 = R, 1 = G, 2 = B) while pure red, green and blue were sent. None if the answers
+/// aren't a permutation (a dead channel or wiring fault, not a color order problem).
+pub fn corrected_color_order(order: &str, seen: [usize; 3]) -> Option<String> {
+    let mut sorted = seen;
+    sorted.sort_unstable();
+    if sorted != [0, 1, 2] {
+        return None;
+    }
+    // Wire slot k carries component indices[k]; the strip lights that slot as seen[indices[k]]
+    Some(color_order_indices(order).iter().map(|&c| ['R', 'G', 'B'][seen[c]]).collect())
+}
+
+/// A strip's DMX patch: (strip id, universe, start channel)
+pub type StripPatch = (u64, u16, u16);
+
+/// Sequential patch for every strip, in current (universe, start channel) order: each strip
+/// starts right after the previous one and moves to the next universe when it doesn't fit.
+/// Packing starts at channel 1 of the lowest universe in use. A strip wider than a universe
+/// gets one to itself (the overflow is dropped at send time either way).
+pub fn auto_pack_strips(strips: &[PixelStrip]) -> Vec<StripPatch> {
+    let mut order: Vec<&PixelStrip> = strips.iter().collect();
+    order.sort_by_key(|s| (s.universe, s.start_channel));
+    let Some(first) = order.first() else {
+        return Vec::new();
+    };
+    let mut universe = first.universe;
+    let mut next = 0usize; // Channels used in `universe`
+    let mut patch = Vec::with_capacity(order.len());
+    for s in order {
+        let span = s.channel_span().min(512);
+        if next > 0 && next + span > 512 {
+            universe = universe.saturating_add(1).min(63999);
+            next = 0;
+        }
+        patch.push((s.id, universe, next as u16 + 1));
+        next += span;
+    }
+    patch
+}
+
+/// Pairs of strips whose channel ranges collide within a universe
+pub fn strip_channel_overlaps(strips: &[PixelStrip]) -> Vec<(u64, u64)> {
+    let range = |s: &PixelStrip| {
+        let start = s.start_channel as usize;
+        (start, (start + s.channel_span()).min(513))
+    };
+    let mut overlaps = Vec::new();
+    for (i, a) in strips.iter().enumerate() {
+        for b in &strips[i + 1..] {
+            let ((a0, a1), (b0, b1)) = (range(a), range(b));
+            if a.universe == b.universe && a0 < b1 && b0 < a1 {
+                overlaps.push((a.id, b.id));
+            }
+        }
+    }
+    overlaps
+}
+
+/// Saved strip type: the layout and protocol fields a product fixes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StripPreset {
+    pub name: String,
+    pub pixel_count: usize,
+    pub spacing: f32,
+    pub color_order: String,
+}
+
+/// Built-in strip types: (name, pixel_count, spacing, color_order). Spacing assumes one canvas
+/// unit is 5 m of strip. WS2812-family chips expect GRB.
+pub const STRIP_PRESETS: [(&str, usize, f32, &str); 6] = [
+    ("WS2812B 30/m, 5 m", 150, 1.0 / 150.0, "GRB"),
+    ("WS2812B 60/m, 5 m", 300, 1.0 / 300.0, "GRB"),
+    ("WS2812B 144/m, 1 m", 144, 1.0 / 720.0, "GRB"),
+    ("SK6812 60/m, 5 m", 300, 1.0 / 300.0, "GRB"),
+    ("APA102 60/m, 5 m", 300, 1.0 / 300.0, "BGR"),
+    ("WS2811 string, 50 px @ 10 cm", 50, 0.02, "RGB"),
+];
+
+impl StripPreset {
+    pub fn builtins() -> Vec<StripPreset> {
+        STRIP_PRESETS.iter()
+            .map(|(name, pixel_count, spacing, color_order)| StripPreset {
+                name: name.to_string(),
+                pixel_count: *pixel_count,
+                spacing: *spacing,
+                color_order: color_order.to_string(),
+            })
+            .collect()
+    }
+
+    /// Capture a strip's current layout under `name`
+    pub fn from_strip(name: &str, strip: &PixelStrip) -> Self {
+        Self {
+            name: name.to_string(),
+            pixel_count: strip.pixel_count,
+            spacing: strip.spacing,
+            color_order: strip.color_order.clone(),
+        }
+    }
+
+    pub fn apply(&self, strip: &mut PixelStrip) {
+        strip.pixel_count = self.pixel_count;
+        strip.spacing = self.spacing;
+        strip.color_order = self.color_order.clone();
+        strip.sanitize();
+    }
+}
+
 fn default_color_order() -> String {
     "RGB".to_string()
 }
@@ -76,25 +330,458 @@ impl Default for PixelStrip {
             spacing: 0.05,
             flipped: false,
             color_order: "RGB".to_string(),
+            lut: None,
+            dimmer_curve: default_dimmer_curve(),
+            channel_prefix: Vec::new(),
+            pixel_offset: 0,
+            channels_per_pixel: default_channels_per_pixel(),
+            luma_weights: default_luma_weights(),
             data: vec![[0, 0, 0]; 50],
         }
     }
 }
 
+/// A conventional DMX fixture (e.g. a 4-channel PAR) driven like a single pixel at (x, y)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Fixture {
+    pub id: u64,
+    pub name: String,
+    pub universe: u16,
+    pub start_channel: u16,
+    pub channels: Vec<String>, // One role per DMX channel: "dimmer" | "red" | "green" | "blue" | "white" | "none"
+    pub x: f32, // Normalized 0..1
+    pub y: f32, // Normalized 0..1
+    #[serde(skip)]
+    pub color: [u8; 3], // Last rendered color
+}
+
+/// Built-in channel layouts offered in the fixture editor
+pub const FIXTURE_TEMPLATES: [(&str, &[&str]); 4] = [
+    ("RGB", &["red", "green", "blue"]),
+    ("Dimmer + RGB", &["dimmer", "red", "green", "blue"]),
+    ("RGBW", &["red", "green", "blue", "white"]),
+    ("Dimmer + RGBW", &["dimmer", "red", "green", "blue", "white"]),
+];
+
+impl Default for Fixture {
+    fn default() -> Self {
+        Self {
+            id: 0,
+            name: "PAR".to_string(),
+            universe: 1,
+            start_channel: 1,
+            channels: FIXTURE_TEMPLATES[1].1.iter().map(|c| c.to_string()).collect(),
+            x: 0.5,
+            y: 0.5,
+            color: [0, 0, 0],
+        }
+    }
+}
+
+impl Fixture {
+    /// One-pixel strip at the fixture's position, so scenes render into it like any strip
+    pub fn proxy_strip(&self) -> PixelStrip {
+        PixelStrip {
+            id: self.id,
+            universe: self.universe,
+            start_channel: self.start_channel,
+            pixel_count: 1,
+            x: self.x,
+            y: self.y,
+            spacing: 0.0,
+            flipped: false,
+            color_order: "RGB".to_string(),
+            lut: None,
+            dimmer_curve: default_dimmer_curve(),
+            channel_prefix: Vec::new(),
+            pixel_offset: 0,
+            channels_per_pixel: default_channels_per_pixel(),
+            luma_weights: default_luma_weights(),
+            data: vec![[0, 0, 0]],
+        }
+    }
+
+    /// DMX values for each channel in `channels`, from the current color
+    pub fn channel_values(&self) -> Vec<u8> {
+        let [r, g, b] = self.color;
+        let white = r.min(g).min(b);
+        let has_white = self.channels.iter().any(|c| c == "white");
+        // With a white channel, move the common part of RGB onto it
+        let (r, g, b) = if has_white { (r - white, g - white, b - white) } else { (r, g, b) };
+        self.channels.iter().map(|role| match role.as_str() {
+            "dimmer" => 255, // Intensity is carried by the color channels
+            "red" => r,
+            "green" => g,
+            "blue" => b,
+            "white" => white,
+            _ => 0,
+        }).collect()
+    }
+}
+
+/// Per-channel color correction: 256-entry output table for each of R, G and B
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ColorLut {
+    pub r: Vec<u8>,
+    pub g: Vec<u8>,
+    pub b: Vec<u8>,
+}
+
+impl Default for ColorLut {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl ColorLut {
+    pub fn identity() -> Self {
+        let table: Vec<u8> = (0..=255u8).collect();
+        Self { r: table.clone(), g: table.clone(), b: table }
+    }
+
+    /// Map an RGB pixel through the tables (malformed tables pass values through)
+    pub fn apply(&self, rgb: [u8; 3]) -> [u8; 3] {
+        let lookup = |table: &[u8], v: u8| table.get(v as usize).copied().unwrap_or(v);
+        [lookup(&self.r, rgb[0]), lookup(&self.g, rgb[1]), lookup(&self.b, rgb[2])]
+    }
+
+    /// Parse a LUT file: 256 lines of "R G B" output values (0-255), separated by
+    /// spaces, tabs or commas. Blank lines and lines starting with '#' are ignored.
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let mut lut = Self { r: Vec::with_capacity(256), g: Vec::with_capacity(256), b: Vec::with_capacity(256) };
+
+        for (line_no, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let values: Vec<&str> = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|v| !v.is_empty())
+                .collect();
+            if values.len() != 3 {
+                anyhow::bail!("Line {}: expected 3 values, found {}", line_no + 1, values.len());
+            }
+            let mut rgb = [0u8; 3];
+            for (c, v) in values.iter().enumerate() {
+                rgb[c] = v.parse::<u8>().map_err(|_| {
+                    anyhow::anyhow!("Line {}: '{}' is not a value between 0 and 255", line_no + 1, v)
+                })?;
+            }
+            lut.r.push(rgb[0]);
+            lut.g.push(rgb[1]);
+            lut.b.push(rgb[2]);
+        }
+
+        if lut.r.len() != 256 {
+            anyhow::bail!("Expected 256 entries, found {}", lut.r.len());
+        }
+        Ok(lut)
+    }
+}
+
+/// Numeric mask params and the ranges the editor allows; loaded values are clamped to these
+pub const MASK_NUMERIC_PARAMS: [(&str, f64, f64); 16] = [
+    ("speed", 0.1, 5.0),
+    ("rotation", 0.0, 360.0),
+    ("width", 0.01, 2.0),
+    ("height", 0.01, 2.0),
+    ("bar_width", 0.01, 4.0),
+    ("radius", 0.0, 5.0),
+    ("base_radius", 0.0, 2.0),
+    ("max_radius", 0.0, 5.0),
+    ("sensitivity", 0.0, 1.0),
+    ("decay", 0.001, 0.5),
+    ("edge_softness", 0.0, 0.2),
+    ("jitter", 0.0, 1.0),
+    ("jitter_seed", 0.0, u32::MAX as f64),
+    ("audio_gain", 0.5, 20.0),
+    ("lifetime", 0.1, 64.0),
+    ("spawn_time", f64::MIN, f64::MAX),
+];
+
+/// Editor range of a numeric mask param from `MASK_NUMERIC_PARAMS`
+pub fn mask_param_range(key: &str) -> Option<(f64, f64)> {
+    MASK_NUMERIC_PARAMS.iter()
+        .find(|(k, _, _)| *k == key)
+        .map(|(_, min, max)| (*min, *max))
+}
+const MASK_BOOL_PARAMS: [&str; 5] = ["hard_edge", "sync", "unidirectional", "constant_speed", "debug_fill"];
+const MASK_STRING_PARAMS: [&str; 14] = [
+    "color_mode", "rate", "color_rate", "motion", "start_pos", "gradient_space", "envelope", "lifetime_unit",
+    "audio_size_band", "audio_position_band", "audio_intensity_band", "audio_color_band",
+    "audio_hue_source", "audio_value_source",
+];
+/// Beat-synced rate choices, longest first: bars of 4 beats or fractions of a bar
+pub const SYNC_RATES: [&str; 6] = ["4 Bar", "2 Bar", "1 Bar", "1/2", "1/4", "1/8"];
+/// Mask properties an audio band can drive, as `audio_<target>_band` params
+pub const AUDIO_MOD_TARGETS: [&str; 4] = ["size", "position", "intensity", "color"];
+/// Free-running LFO rate limits in Hz; the slow end is a 120 s period
+pub const LFO_HZ_RANGE: (f64, f64) = (1.0 / 120.0, 10.0);
+/// Params that can carry an LFO (`<param>_lfo_<setting>` keys)
+const LFO_PARAMS: [&str; 4] = ["width", "height", "bar_width", "radius"];
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Mask {
     pub id: u64,
-    pub mask_type: String, // "scanner", "radial"
+    pub mask_type: String, // "scanner", "radial", "burst", "orbit", "image"
     pub x: f32,
     pub y: f32,
     pub params: HashMap<String, serde_json::Value>,
+    #[serde(default)]
+    pub anchor_strip: Option<u64>, // When set, x/y are offsets from this strip's origin
+    #[serde(default)]
+    pub target_strips: Vec<u64>, // Strip IDs this mask draws on; empty = all strips
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+impl Mask {
+    /// Origin that x/y are relative to: the anchor strip's position, or (0, 0) if unanchored
+    pub fn anchor_origin(&self, strips: &[PixelStrip]) -> (f32, f32) {
+        self.anchor_strip
+            .and_then(|id| strips.iter().find(|s| s.id == id))
+            .map(|s| (s.x, s.y))
+            .unwrap_or((0.0, 0.0))
+    }
+
+    /// Whether any strip this mask targets still exists. An all-strips mask always does.
+    pub fn targets_any(&self, strips: &[PixelStrip]) -> bool {
+        self.target_strips.is_empty() || self.target_strips.iter().any(|id| strips.iter().any(|s| s.id == *id))
+    }
+
+    /// Drop targets of deleted strips. When none of the targets are left the ids are kept,
+    /// since an empty list means all strips: the mask then draws on nothing.
+    pub fn prune_targets(&mut self, strips: &[PixelStrip]) {
+        if self.targets_any(strips) {
+            self.target_strips.retain(|id| strips.iter().any(|s| s.id == *id));
+        }
+    }
+
+    /// Absolute canvas position of the mask center
+    pub fn resolved_position(&self, strips: &[PixelStrip]) -> (f32, f32) {
+        let (ox, oy) = self.anchor_origin(strips);
+        (self.x + ox, self.y + oy)
+    }
+
+    /// Fraction of a temporary mask's life left (1 = just spawned, 0 = expired), or None for
+    /// permanent masks. `lifetime` counts in `lifetime_unit` ("seconds" of animation time or
+    /// "beats") from `spawn_time`, which is in the same unit.
+    pub fn life_remaining(&self, time: f64, beat: f64) -> Option<f32> {
+        let lifetime = self.params.get("lifetime").and_then(|v| v.as_f64())?;
+        let spawn = self.params.get("spawn_time").and_then(|v| v.as_f64())?;
+        let now = if self.lifetime_beats() { beat } else { time };
+        Some((1.0 - (now - spawn) / lifetime).clamp(0.0, 1.0) as f32)
+    }
+
+    /// Whether `lifetime` and `spawn_time` are in beats rather than seconds
+    pub fn lifetime_beats(&self) -> bool {
+        self.params.get("lifetime_unit").and_then(|v| v.as_str()) == Some("beats")
+    }
+
+    /// Make params loaded from disk safe to render: clamp known numbers to their editor ranges,
+    /// drop values of the wrong type (the engine then uses its default) and drop unknown keys.
+    pub fn sanitize(&mut self) {
+        if !self.x.is_finite() { self.x = 0.5; }
+        if !self.y.is_finite() { self.y = 0.5; }
+
+        self.params.retain(|key, value| {
+            let numeric_range = mask_param_range(key)
+                .or_else(|| {
+                    let (_, setting) = lfo_key_parts(key)?;
+                    match setting {
+                        "depth" => Some((0.0, 1.0)),
+                        "hz" => Some(LFO_HZ_RANGE),
+                        "phase" => Some((0.0, 1.0)),
+                        _ => None,
+                    }
+                });
+            if let Some((min, max)) = numeric_range {
+                return match value.as_f64().filter(|v| v.is_finite()) {
+                    Some(v) => {
+                        *value = v.clamp(min, max).into();
+                        true
+                    }
+                    None => false,
+                };
+            }
+
+            let lfo_setting = lfo_key_parts(key).map(|(_, setting)| setting);
+            if MASK_BOOL_PARAMS.contains(&key.as_str()) || matches!(lfo_setting, Some("enabled" | "sync")) {
+                return value.is_boolean();
+            }
+            if MASK_STRING_PARAMS.contains(&key.as_str()) || matches!(lfo_setting, Some("waveform" | "rate" | "mode" | "rate_unit")) {
+                return value.is_string();
+            }
+            match key.as_str() {
+                "color" | "color2" => serde_json::from_value::<[u8; 3]>(value.clone()).is_ok(),
+                "gradient_colors" => serde_json::from_value::<Vec<[u8; 3]>>(value.clone()).is_ok(),
+                "image" => serde_json::from_value::<MaskImage>(value.clone()).is_ok_and(|image| image.is_valid()),
+                _ => false, // Unknown key
+            }
+        });
+    }
+
+    /// Change the anchor strip without moving the mask on the canvas
+    pub fn set_anchor(&mut self, anchor: Option<u64>, strips: &[PixelStrip]) {
+        let (ax, ay) = self.resolved_position(strips);
+        self.anchor_strip = anchor.filter(|id| strips.iter().any(|s| s.id == *id));
+        let (ox, oy) = self.anchor_origin(strips);
+        self.x = ax - ox;
+        self.y = ay - oy;
+    }
+}
+
+/// Longest side of a mask image in pixels; larger files are scaled down on load
+pub const MASK_IMAGE_MAX_SIZE: u32 = 64;
+
+/// Picture an "image" mask samples, stored in its "image" param so it travels with the scene.
+/// Kept small (see MASK_IMAGE_MAX_SIZE) because it's saved as JSON with the mask.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MaskImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<[u8; 3]>, // Row-major from the top left
+}
+
+impl MaskImage {
+    /// Load an image file, scaled to fit MASK_IMAGE_MAX_SIZE. Transparency becomes black (no light).
+    pub fn load(path: &std::path::Path) -> anyhow::Result<Self> {
+        let mut image = image::open(path)?;
+        if image.width() > MASK_IMAGE_MAX_SIZE || image.height() > MASK_IMAGE_MAX_SIZE {
+            image = image.thumbnail(MASK_IMAGE_MAX_SIZE, MASK_IMAGE_MAX_SIZE);
+        }
+        let rgba = image.to_rgba8();
+        let (width, height) = rgba.dimensions();
+        let pixels = rgba.pixels()
+            .map(|p| [0, 1, 2].map(|c| (p[c] as u16 * p[3] as u16 / 255) as u8))
+            .collect();
+        Ok(Self { width, height, pixels })
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.width > 0 && self.height > 0 && self.pixels.len() == self.width as usize * self.height as usize
+    }
+}
+
+/// Split "<param>_lfo_<setting>" (or "_lfo2_" for the second LFO) for an LFO-capable param into (param, setting)
+fn lfo_key_parts(key: &str) -> Option<(&str, &str)> {
+    LFO_PARAMS.iter().find_map(|param| {
+        let rest = key.strip_prefix(param)?;
+        let setting = rest.strip_prefix("_lfo_").or_else(|| rest.strip_prefix("_lfo2_"))?;
+        Some((*param, setting))
+    })
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct NetworkConfig {
     pub use_multicast: bool,
     pub unicast_ip: String,
     pub universe: u16,
+    #[serde(default = "default_multicast_ttl")]
+    pub multicast_ttl: u32, // 1 = stay on the local subnet; raise to cross routers
+    #[serde(default)]
+    pub multicast_interface: Option<String>, // Local IPv4 to send from, None = OS default route
+    #[serde(default = "default_max_universes")]
+    pub max_universes: u16, // Soft cap on registered universes; output beyond it is dropped
+    #[serde(default)]
+    pub universe_routes: Vec<UniverseRoute>, // Per-universe overrides of use_multicast/unicast_ip
+    #[serde(default)]
+    pub reverse_universe_order: bool, // Mirror strip universes across their range, for controllers that number ports backwards
+    #[serde(default)]
+    pub redundant_output: bool, // Also send every universe from a second sender, for a backup data network
+    #[serde(default)]
+    pub redundant_interface: Option<String>, // Local IPv4 of the backup network adapter
+    #[serde(default)]
+    pub muted_universes: Vec<u16>, // Output universes not sent (after one black frame), for troubleshooting
+    #[serde(default = "default_source_name")]
+    pub source_name: String, // sACN source name receivers show for this controller
+    #[serde(default)]
+    pub source_cid: String, // sACN CID (UUID text), generated once so the source keeps its identity
+    #[serde(default = "default_universe_mode")]
+    pub universe_mode: String, // "relative" (strip universes count from `universe`) or "absolute" (sent as-is)
+}
+
+/// Destination override for one output universe
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct UniverseRoute {
+    pub universe: u16, // Output universe (after the global universe offset)
+    pub use_multicast: bool,
+    pub unicast_ip: String,
+}
+
+impl NetworkConfig {
+    /// Source name as sent: trimmed, the default if blank, and cut to fit the packet field
+    pub fn sacn_source_name(&self) -> String {
+        let name = self.source_name.trim();
+        if name.is_empty() {
+            return default_source_name();
+        }
+        let mut end = name.len().min(SACN_SOURCE_NAME_MAX);
+        while !name.is_char_boundary(end) {
+            end -= 1;
+        }
+        name[..end].to_string()
+    }
+
+    /// Fill in a random CID if none is set yet. Returns true if one was generated.
+    pub fn ensure_source_cid(&mut self) -> bool {
+        if uuid::Uuid::parse_str(&self.source_cid).is_ok() {
+            return false;
+        }
+        self.source_cid = uuid::Uuid::new_v4().hyphenated().to_string();
+        true
+    }
+
+    /// Multicast flag and unicast IP for an output universe: its override, else the global setting
+    pub fn route_for(&self, universe: u16) -> (bool, &str) {
+        match self.universe_routes.iter().find(|r| r.universe == universe) {
+            Some(route) => (route.use_multicast, &route.unicast_ip),
+            None => (self.use_multicast, &self.unicast_ip),
+        }
+    }
+
+    /// Universe a strip is sent on, before the global offset. With reverse_universe_order the
+    /// strip universes are mirrored across `range` (lowest <-> highest) instead of used as-is.
+    pub fn output_universe(&self, universe: u16, range: (u16, u16)) -> u16 {
+        if !self.reverse_universe_order {
+            return universe;
+        }
+        let (lo, hi) = range;
+        (lo as u32 + hi as u32).saturating_sub(universe as u32).clamp(1, 63999) as u16
+    }
+
+    /// Added to strip and fixture universes: `universe - 1` in relative mode (so universe 1
+    /// lands on the global universe), 0 in absolute mode
+    pub fn universe_offset(&self) -> u16 {
+        if self.universe_mode == "absolute" {
+            0
+        } else {
+            self.universe.saturating_sub(1)
+        }
+    }
+
+    /// Universe a strip is actually sent on: reversal, then the global offset, clamped to 1-63999
+    pub fn effective_universe(&self, universe: u16, range: (u16, u16)) -> u16 {
+        self.output_universe(universe, range).saturating_add(self.universe_offset()).clamp(1, 63999)
+    }
+}
+
+fn default_universe_mode() -> String {
+    "relative".to_string()
+}
+
+fn default_multicast_ttl() -> u32 {
+    1
+}
+
+fn default_source_name() -> String {
+    "Lightspeed".to_string()
+}
+
+/// Longest sACN source name in bytes (the packet field is 64 bytes, null terminated)
+pub const SACN_SOURCE_NAME_MAX: usize = 63;
+
+fn default_max_universes() -> u16 {
+    64
 }
 
 impl Default for NetworkConfig {
@@ -103,6 +790,17 @@ impl Default for NetworkConfig {
             use_multicast: true,
             unicast_ip: "192.168.1.50".to_string(), // Default placeholder
             universe: 1,
+            multicast_ttl: default_multicast_ttl(),
+            multicast_interface: None,
+            max_universes: default_max_universes(),
+            universe_routes: Vec::new(),
+            reverse_universe_order: false,
+            redundant_output: false,
+            redundant_interface: None,
+            muted_universes: Vec::new(),
+            source_name: default_source_name(),
+            source_cid: String::new(),
+            universe_mode: default_universe_mode(),
         }
     }
 }
@@ -113,6 +811,33 @@ pub struct AudioConfig {
     pub use_flywheel: bool,
     pub hybrid_sync: bool,
     pub sensitivity: f32,
+    #[serde(default)]
+    pub beat_offset: f32, // Visual phase shift in beats, independent of latency_ms (+ = visuals earlier)
+    // Advanced sync tuning (see the engine's flywheel logic)
+    #[serde(default = "default_flywheel_jump_beats")]
+    pub flywheel_jump_beats: f32, // Flywheel vs Link difference (beats) treated as a jump rather than drift
+    #[serde(default = "default_flywheel_recovery_secs")]
+    pub flywheel_recovery_secs: f32, // How long a jump must persist before snapping to Link
+    #[serde(default = "default_flywheel_nudge")]
+    pub flywheel_nudge: f32, // Fraction of the remaining drift toward Link corrected each frame
+    #[serde(default = "default_phase_correction")]
+    pub phase_correction: f32, // Fraction of the audio phase error corrected per detected beat
+    #[serde(default = "default_phase_decay")]
+    pub phase_decay: f32, // Fraction of the audio phase correction released each frame
+    #[serde(default = "default_source_priority")]
+    pub source_priority: Vec<String>, // Tempo sources, most preferred first (see BEAT_SOURCES)
+    #[serde(default = "default_manual_bpm")]
+    pub manual_bpm: f32, // Manual tempo before the Master Speed multiplier; can be latched from audio
+    #[serde(default)]
+    pub auto_latch_bpm: bool, // Latch a steady detected audio BPM into manual_bpm automatically
+    #[serde(default = "default_beats_per_bar")]
+    pub beats_per_bar: u8, // Meter: beats per bar, also the Link quantum for bar phase
+    #[serde(default = "default_trigger_action")]
+    pub trigger_action: String, // On a strong onset: "off" | "next_scene" | "fire" | "scene:<id>"
+    #[serde(default = "default_trigger_threshold")]
+    pub trigger_threshold: f32, // Onset strength (0..1) that fires the trigger
+    #[serde(default = "default_trigger_cooldown_secs")]
+    pub trigger_cooldown_secs: f32, // Minimum time between triggers
 }
 
 impl Default for AudioConfig {
@@ -122,16 +847,108 @@ impl Default for AudioConfig {
             use_flywheel: true,
             hybrid_sync: false,
             sensitivity: 0.5,
+            beat_offset: 0.0,
+            flywheel_jump_beats: default_flywheel_jump_beats(),
+            flywheel_recovery_secs: default_flywheel_recovery_secs(),
+            flywheel_nudge: default_flywheel_nudge(),
+            phase_correction: default_phase_correction(),
+            phase_decay: default_phase_decay(),
+            source_priority: default_source_priority(),
+            manual_bpm: default_manual_bpm(),
+            auto_latch_bpm: false,
+            beats_per_bar: default_beats_per_bar(),
+            trigger_action: default_trigger_action(),
+            trigger_threshold: default_trigger_threshold(),
+            trigger_cooldown_secs: default_trigger_cooldown_secs(),
+        }
+    }
+}
+
+impl AudioConfig {
+    /// `source_priority` with unknown and repeated entries dropped and any missing
+    /// source appended in the default order, so the engine always has all three
+    pub fn beat_source_order(&self) -> Vec<String> {
+        let mut order: Vec<String> = Vec::new();
+        for source in self.source_priority.iter().map(String::as_str).chain(BEAT_SOURCES) {
+            if BEAT_SOURCES.contains(&source) && !order.iter().any(|s| s == source) {
+                order.push(source.to_string());
+            }
         }
+        order
     }
 }
 
-#[derive(Serialize, Deserialize, Default, Clone, Debug)]
+/// Tempo sources the engine can follow, in the default priority order
+pub const BEAT_SOURCES: [&str; 3] = ["link", "audio", "manual"];
+
+/// Valid ranges for the advanced sync settings, shared by the editor and the engine
+pub const FLYWHEEL_JUMP_BEATS_RANGE: std::ops::RangeInclusive<f32> = 0.05..=2.0;
+pub const FLYWHEEL_RECOVERY_SECS_RANGE: std::ops::RangeInclusive<f32> = 0.1..=5.0;
+pub const FLYWHEEL_NUDGE_RANGE: std::ops::RangeInclusive<f32> = 0.01..=1.0;
+pub const PHASE_CORRECTION_RANGE: std::ops::RangeInclusive<f32> = 0.0..=1.0;
+pub const PHASE_DECAY_RANGE: std::ops::RangeInclusive<f32> = 0.0..=0.2;
+/// Valid range for `manual_bpm`
+pub const MANUAL_BPM_RANGE: std::ops::RangeInclusive<f32> = 40.0..=240.0;
+
+fn default_manual_bpm() -> f32 {
+    120.0
+}
+
+/// Valid range for `beats_per_bar`
+pub const BEATS_PER_BAR_RANGE: std::ops::RangeInclusive<u8> = 1..=16;
+
+fn default_beats_per_bar() -> u8 {
+    4
+}
+
+/// Valid ranges for the audio trigger settings
+pub const TRIGGER_THRESHOLD_RANGE: std::ops::RangeInclusive<f32> = 0.1..=1.0;
+pub const TRIGGER_COOLDOWN_SECS_RANGE: std::ops::RangeInclusive<f32> = 1.0..=120.0;
+
+fn default_trigger_action() -> String {
+    "off".to_string()
+}
+
+fn default_trigger_threshold() -> f32 {
+    0.6
+}
+
+fn default_trigger_cooldown_secs() -> f32 {
+    8.0
+}
+
+fn default_flywheel_jump_beats() -> f32 {
+    0.5
+}
+
+fn default_flywheel_recovery_secs() -> f32 {
+    1.0
+}
+
+fn default_flywheel_nudge() -> f32 {
+    0.1
+}
+
+fn default_phase_correction() -> f32 {
+    0.4
+}
+
+fn default_phase_decay() -> f32 {
+    0.02
+}
+
+fn default_source_priority() -> Vec<String> {
+    BEAT_SOURCES.iter().map(|s| s.to_string()).collect()
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AppState {
     pub strips: Vec<PixelStrip>,
-    pub masks: Vec<Mask>,
+    pub masks: Vec<Mask>, // LEGACY: moved into a scene by migrate_global_masks on load
     #[serde(default)]
     pub scenes: Vec<Scene>,
+    #[serde(default)]
+    pub fixtures: Vec<Fixture>,
     pub selected_scene_id: Option<u64>,
     #[serde(default)]
     pub network: NetworkConfig,
@@ -144,8 +961,386 @@ pub struct AppState {
     pub layout_locked: bool,
     #[serde(default = "default_midi_enabled")]
     pub midi_enabled: bool,
+    #[serde(default = "default_no_selection_mode")]
+    pub no_selection_mode: String, // What renders with no scene selected: "black" | "last" | "scene"
+    #[serde(default)]
+    pub fallback_scene_id: Option<u64>, // Scene used when no_selection_mode == "scene"
+    #[serde(default)]
+    pub output_smoothing: f32, // Temporal smoothing of the composited output, 0 = off, <1 = trail length
+    #[serde(default)]
+    pub fire_midi_btn: Option<u8>, // Launchpad note/CC that fires one-shot effects
+    #[serde(default)]
+    pub fire_midi_is_cc: bool,
+    #[serde(default = "default_sync_loss_behavior")]
+    pub sync_loss_behavior: String, // When tempo sync is lost: "continue" | "hold" | "fade"
+    #[serde(default)]
+    pub panic_midi_btn: Option<u8>, // Launchpad note/CC that forces full white while held
+    #[serde(default)]
+    pub panic_midi_is_cc: bool,
+    #[serde(default = "default_max_flash_hz")]
+    pub max_flash_hz: f32, // Photosensitivity guard: flashing effects are slowed to at most this rate
+    #[serde(default = "default_max_flash_contrast")]
+    pub max_flash_contrast: f32, // Largest brightness swing (0..1) a flashing effect may use
+    #[serde(default)]
+    pub zero_based_channels: bool, // Display DMX channels as 0..=511; stored values stay 1-based
+    #[serde(default = "default_canvas_theme")]
+    pub canvas_theme: String, // Layout canvas colors: "dark" | "light" | "high_contrast"
+    #[serde(default)]
+    pub light_ui: bool, // Light egui visuals for the panels and windows
+    #[serde(default = "default_engine_tick_hz")]
+    pub engine_tick_hz: f32, // Fixed animation timestep rate, independent of UI frame pacing
+    #[serde(default = "default_mask_compositing")]
+    pub mask_compositing: String, // How overlapping masks combine: "add" | "max" (HTP) | "ltp"
+    #[serde(default)]
+    pub launchpad_page: u8, // Scene page currently shown on the Launchpad grid
+    #[serde(default)]
+    pub heartbeat_midi_btn: Option<u8>, // Launchpad note/CC lit as an output health indicator
+    #[serde(default)]
+    pub heartbeat_midi_is_cc: bool,
+    #[serde(default = "default_fit_padding")]
+    pub fit_padding: f32, // Canvas units added around the content when fitting the view
+    #[serde(default = "default_fit_margin")]
+    pub fit_margin: f32, // Fraction of the canvas the fitted content fills
+    #[serde(default)]
+    pub performance_mode: bool, // Lock edits to the live scene; switching and submasters still work
+    #[serde(default = "default_master_intensity")]
+    pub master_brightness: f32, // Grand master over everything the scenes render
+    #[serde(default = "default_master_speed")]
+    pub master_speed: f32, // The Global Settings Master Speed: scales effect animation and the manual tempo
+    #[serde(default)]
+    pub cc_mappings: Vec<CcMapping>, // Fixed fader CCs for the performance controls
+    #[serde(default)]
+    pub canvas_unit_meters: f32, // Physical length of one canvas unit, for readouts; 0 = no physical scale
+    #[serde(default = "default_mask_overlays")]
+    pub mask_overlays: String, // Canvas editing overlays: "auto" (hidden in performance mode) | "show" | "hide"
+    #[serde(default)]
+    pub freeze_midi_btn: Option<u8>, // Launchpad note/CC that toggles freeze (hold the current output)
+    #[serde(default)]
+    pub freeze_midi_is_cc: bool,
+    #[serde(default)]
+    pub staged_editing: bool, // Scene edits preview live but only save once applied
+    #[serde(default)]
+    pub feedback_midi: bool, // Send mapped fader CCs their current values (see feedback.rs)
+    #[serde(default)]
+    pub feedback_osc: bool, // Send scene, masters and tempo over OSC to feedback_osc_target
+    #[serde(default = "default_feedback_osc_target")]
+    pub feedback_osc_target: String, // "host:port" of the OSC control surface
+}
+
+fn default_feedback_osc_target() -> String {
+    "127.0.0.1:9000".to_string()
+}
+
+/// A MIDI CC that drives one performance control
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CcMapping {
+    pub target: String, // One of CC_TARGETS, or "scene:<id>" for that scene's submaster
+    pub cc: u8,
+}
+
+/// Performance controls a fader CC can drive: (target, label). Per-scene submasters are
+/// mapped separately as "scene:<id>".
+pub const CC_TARGETS: [(&str, &str); 3] = [
+    ("brightness", "Master brightness"),
+    ("speed", "Master speed"),
+    ("submaster", "Live scene submaster"),
+];
+
+/// Valid range for `master_speed`
+pub const MASTER_SPEED_RANGE: std::ops::RangeInclusive<f32> = 0.1..=5.0;
+
+fn default_master_speed() -> f32 {
+    1.0
+}
+
+/// Master speed for a CC value: the centre (64) is 1x, each 32 steps doubles or halves it
+pub fn cc_to_speed(value: u8) -> f32 {
+    2f32.powf((value as f32 - 64.0) / 32.0).clamp(*MASTER_SPEED_RANGE.start(), *MASTER_SPEED_RANGE.end())
+}
+
+/// CC value for a master speed, the inverse of `cc_to_speed`
+pub fn speed_to_cc(speed: f32) -> u8 {
+    (64.0 + 32.0 * speed.max(f32::MIN_POSITIVE).log2()).round().clamp(0.0, 127.0) as u8
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self {
+            strips: Vec::new(),
+            masks: Vec::new(),
+            scenes: Vec::new(),
+            fixtures: Vec::new(),
+            selected_scene_id: None,
+            network: NetworkConfig::default(),
+            bind_address: None,
+            mode: String::new(),
+            effect: String::new(),
+            audio: AudioConfig::default(),
+            layout_locked: false,
+            midi_enabled: default_midi_enabled(),
+            no_selection_mode: default_no_selection_mode(),
+            fallback_scene_id: None,
+            output_smoothing: 0.0,
+            fire_midi_btn: None,
+            fire_midi_is_cc: false,
+            sync_loss_behavior: default_sync_loss_behavior(),
+            panic_midi_btn: None,
+            panic_midi_is_cc: false,
+            max_flash_hz: default_max_flash_hz(),
+            max_flash_contrast: default_max_flash_contrast(),
+            zero_based_channels: false,
+            canvas_theme: default_canvas_theme(),
+            light_ui: false,
+            engine_tick_hz: default_engine_tick_hz(),
+            mask_compositing: default_mask_compositing(),
+            launchpad_page: 0,
+            heartbeat_midi_btn: None,
+            heartbeat_midi_is_cc: false,
+            fit_padding: default_fit_padding(),
+            fit_margin: default_fit_margin(),
+            performance_mode: false,
+            master_brightness: 1.0,
+            master_speed: default_master_speed(),
+            cc_mappings: Vec::new(),
+            canvas_unit_meters: 0.0,
+            mask_overlays: default_mask_overlays(),
+            freeze_midi_btn: None,
+            freeze_midi_is_cc: false,
+            staged_editing: false,
+            feedback_midi: false,
+            feedback_osc: false,
+            feedback_osc_target: default_feedback_osc_target(),
+        }
+    }
+}
+
+impl AppState {
+    /// Whether the canvas draws mask overlays and other editing chrome, or only the output pixels
+    pub fn mask_overlays_visible(&self) -> bool {
+        match self.mask_overlays.as_str() {
+            "show" => true,
+            "hide" => false,
+            _ => !self.performance_mode,
+        }
+    }
+
+    /// First channel number as shown in the UI: 0 or 1. Stored channels are always 1-based.
+    pub fn channel_base(&self) -> u16 {
+        if self.zero_based_channels { 0 } else { 1 }
+    }
+
+    /// Masks of the selected scene; empty for Blackout scenes or when nothing is selected
+    pub fn active_masks(&self) -> &[Mask] {
+        self.selected_scene_id
+            .and_then(|sel| self.scenes.iter().find(|s| s.id == sel && s.kind != "Blackout"))
+            .map_or(&[], |s| s.masks.as_slice())
+    }
+
+    /// Mutable access to the selected scene's masks, None for Blackout scenes or no selection
+    pub fn active_masks_mut(&mut self) -> Option<&mut Vec<Mask>> {
+        let sel = self.selected_scene_id?;
+        self.scenes.iter_mut()
+            .find(|s| s.id == sel && s.kind != "Blackout")
+            .map(|s| &mut s.masks)
+    }
+
+    /// Move legacy top-level masks into a new "Migrated Masks" scene. Returns true if anything moved.
+    pub fn migrate_global_masks(&mut self) -> bool {
+        if self.masks.is_empty() {
+            return false;
+        }
+        let id: u64 = rand::random();
+        self.scenes.push(Scene {
+            id,
+            name: "Migrated Masks".into(),
+            kind: "Masks".into(),
+            category: default_category(),
+            masks: std::mem::take(&mut self.masks),
+            global: None,
+            global_effects: Vec::new(),
+            launchpad_btn: None,
+            launchpad_is_cc: false,
+            launchpad_color: None,
+            master_intensity: default_master_intensity(),
+            compositing: default_compositing(),
+            page: self.launchpad_page,
+            audio_reactive: true,
+        });
+        if self.selected_scene_id.is_none() {
+            self.selected_scene_id = Some(id);
+        }
+        true
+    }
+
+    /// CC mapped to a performance control target, see `CcMapping`
+    pub fn cc_for(&self, target: &str) -> Option<u8> {
+        self.cc_mappings.iter().find(|m| m.target == target).map(|m| m.cc)
+    }
+
+    /// Map a target to a CC, or unmap it with None
+    pub fn set_cc(&mut self, target: &str, cc: Option<u8>) {
+        self.cc_mappings.retain(|m| m.target != target);
+        if let Some(cc) = cc {
+            self.cc_mappings.push(CcMapping { target: target.to_string(), cc: cc.min(127) });
+        }
+    }
+
+    /// Move every control mapped to `cc` to the fader position `value`. Returns true if the
+    /// CC is mapped, so it isn't also treated as a button press.
+    pub fn apply_cc(&mut self, cc: u8, value: u8) -> bool {
+        let targets: Vec<String> = self.cc_mappings.iter().filter(|m| m.cc == cc).map(|m| m.target.clone()).collect();
+        let level = value.min(127) as f32 / 127.0;
+        for target in &targets {
+            match target.as_str() {
+                "brightness" => self.master_brightness = level,
+                "speed" => self.master_speed = cc_to_speed(value),
+                "submaster" => {
+                    let live = self.selected_scene_id;
+                    if let Some(scene) = self.scenes.iter_mut().find(|s| Some(s.id) == live) {
+                        scene.master_intensity = level;
+                    }
+                }
+                other => {
+                    let id = other.strip_prefix("scene:").and_then(|id| id.parse::<u64>().ok());
+                    if let Some(scene) = self.scenes.iter_mut().find(|s| Some(s.id) == id) {
+                        scene.master_intensity = level;
+                    }
+                }
+            }
+        }
+        !targets.is_empty()
+    }
+
+    /// Each mapped CC with its control's current value, as `apply_cc` would read it back.
+    /// A CC driving several controls reports only its first mapping with a value, so a
+    /// shared fader gets one steady value instead of alternating between them.
+    pub fn cc_feedback(&self) -> Vec<(u8, u8)> {
+        let level = |v: f32| (v.clamp(0.0, 1.0) * 127.0).round() as u8;
+        let scene_level = |id: Option<u64>| self.scenes.iter().find(|s| Some(s.id) == id).map(|s| level(s.master_intensity));
+        let mut seen = std::collections::HashSet::new();
+        self.cc_mappings.iter().filter_map(|m| {
+            let value = match m.target.as_str() {
+                "brightness" => Some(level(self.master_brightness)),
+                "speed" => Some(speed_to_cc(self.master_speed)),
+                "submaster" => scene_level(self.selected_scene_id),
+                other => scene_level(other.strip_prefix("scene:").and_then(|id| id.parse().ok())),
+            }?;
+            seen.insert(m.cc).then_some((m.cc, value))
+        }).collect()
+    }
+
+    /// The scene after the selected one in list order, wrapping around; the first scene when
+    /// nothing is selected
+    pub fn next_scene_id(&self) -> Option<u64> {
+        let current = self.selected_scene_id.and_then(|id| self.scenes.iter().position(|s| s.id == id));
+        let next = current.map_or(0, |i| (i + 1) % self.scenes.len());
+        self.scenes.get(next).map(|s| s.id)
+    }
+
+    /// Scene mapped to this note/CC on the current Launchpad page
+    pub fn launchpad_scene(&self, btn: u8, is_cc: bool) -> Option<&Scene> {
+        self.scenes.iter().find(|s| {
+            s.page == self.launchpad_page && s.launchpad_is_cc == is_cc && s.launchpad_btn == Some(btn)
+        })
+    }
+
+    /// Scenes whose Launchpad note/CC is already mapped by an earlier scene on the same page.
+    /// The pad only ever triggers the first, so these are unreachable (imports and merges can cause this).
+    pub fn duplicate_launchpad_scenes(&self) -> Vec<u64> {
+        let mut seen = std::collections::HashSet::new();
+        let mut duplicates = Vec::new();
+        for s in &self.scenes {
+            if let Some(btn) = s.launchpad_btn.filter(|b| *b != 0) {
+                if !seen.insert((s.page, s.launchpad_is_cc, btn)) {
+                    duplicates.push(s.id);
+                }
+            }
+        }
+        duplicates
+    }
+
+    /// Unmap every scene reported by `duplicate_launchpad_scenes`. Returns how many were cleared.
+    pub fn clear_duplicate_launchpad_mappings(&mut self) -> usize {
+        let duplicates = self.duplicate_launchpad_scenes();
+        for s in self.scenes.iter_mut().filter(|s| duplicates.contains(&s.id)) {
+            s.launchpad_btn = None;
+        }
+        duplicates.len()
+    }
+
+    /// Lowest and highest universe used by any strip, (1, 1) if there are none
+    pub fn strip_universe_range(&self) -> (u16, u16) {
+        let lo = self.strips.iter().map(|s| s.universe).min().unwrap_or(1);
+        let hi = self.strips.iter().map(|s| s.universe).max().unwrap_or(1);
+        (lo, hi)
+    }
+
+    /// Remove a strip, converting masks anchored to it back to absolute coordinates
+    /// and dropping it from mask targets
+    pub fn remove_strip(&mut self, idx: usize) -> PixelStrip {
+        let strip = self.strips.remove(idx);
+        let masks = self.masks.iter_mut()
+            .chain(self.scenes.iter_mut().flat_map(|s| s.masks.iter_mut()));
+        for m in masks {
+            if m.anchor_strip == Some(strip.id) {
+                m.x += strip.x;
+                m.y += strip.y;
+                m.anchor_strip = None;
+            }
+            m.prune_targets(&self.strips);
+        }
+        strip
+    }
+}
+
+fn default_mask_overlays() -> String {
+    "auto".to_string()
+}
+
+fn default_canvas_theme() -> String {
+    "dark".to_string()
 }
 
 fn default_midi_enabled() -> bool {
     true
 }
+
+fn default_no_selection_mode() -> String {
+    "black".to_string()
+}
+
+fn default_sync_loss_behavior() -> String {
+    "continue".to_string()
+}
+
+/// 3 flashes per second is the common photosensitive-epilepsy guideline
+fn default_max_flash_hz() -> f32 {
+    3.0
+}
+
+fn default_max_flash_contrast() -> f32 {
+    0.8
+}
+
+/// Valid range for `engine_tick_hz`
+pub const ENGINE_TICK_HZ_RANGE: std::ops::RangeInclusive<f32> = 30.0..=480.0;
+
+fn default_engine_tick_hz() -> f32 {
+    120.0
+}
+
+/// Valid ranges for `fit_padding` and `fit_margin`
+pub const FIT_PADDING_RANGE: std::ops::RangeInclusive<f32> = 0.0..=1.0;
+pub const FIT_MARGIN_RANGE: std::ops::RangeInclusive<f32> = 0.5..=1.0;
+
+fn default_fit_padding() -> f32 {
+    0.1
+}
+
+fn default_fit_margin() -> f32 {
+    0.9
+}
+
+fn default_mask_compositing() -> String {
+    "add".to_string()
+}
@@ -0,0 +1,220 @@
+//! Show recordings: the engine's per-frame universe output, timestamped, for playback
+//! without rendering.
+//!
+//! File format (all integers little-endian):
+//!
+//! - Header: the 8 magic bytes `LSREC001`
+//! - Then one record per frame, until end of file:
+//!   - `u64` microseconds since recording started
+//!   - `u16` number of universes in the frame
+//!   - per universe: `u16` universe number, `u16` channel count (at most 512), then the
+//!     DMX channels (no start code)
+//!
+//! A frame holds every universe that was output that frame; universes missing from a frame
+//! aren't sent. A truncated final frame (recording cut off mid-write) is ignored.
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::Instant;
+
+pub const MAGIC: &[u8; 8] = b"LSREC001";
+
+/// Universe number to DMX channels, as packed by the engine
+pub type UniverseFrame = HashMap<u16, Vec<u8>>;
+
+pub struct Frame {
+    pub time_us: u64,
+    pub universes: UniverseFrame,
+}
+
+/// Append one frame. Universes are written in ascending order so files are reproducible.
+pub fn write_frame<W: Write>(w: &mut W, time_us: u64, universes: &UniverseFrame) -> Result<()> {
+    let mut keys: Vec<u16> = universes.keys().copied().collect();
+    keys.sort_unstable();
+    w.write_all(&time_us.to_le_bytes())?;
+    w.write_all(&(keys.len() as u16).to_le_bytes())?;
+    for u in keys {
+        let data = &universes[&u][..universes[&u].len().min(512)];
+        w.write_all(&u.to_le_bytes())?;
+        w.write_all(&(data.len() as u16).to_le_bytes())?;
+        w.write_all(data)?;
+    }
+    Ok(())
+}
+
+/// Read the next frame; None at end of file (including a truncated last frame)
+pub fn read_frame<R: Read>(r: &mut R) -> Result<Option<Frame>> {
+    fn read_u16<R: Read>(r: &mut R) -> std::io::Result<u16> {
+        let mut buf = [0; 2];
+        r.read_exact(&mut buf)?;
+        Ok(u16::from_le_bytes(buf))
+    }
+    let mut read = || -> std::io::Result<Frame> {
+        let mut time = [0; 8];
+        r.read_exact(&mut time)?;
+        let count = read_u16(r)?;
+        let mut universes = HashMap::with_capacity(count as usize);
+        for _ in 0..count {
+            let u = read_u16(r)?;
+            let len = read_u16(r)?;
+            if len > 512 {
+                return Err(std::io::Error::new(ErrorKind::InvalidData, format!("universe {} has {} channels", u, len)));
+            }
+            let mut data = vec![0; len as usize];
+            r.read_exact(&mut data)?;
+            universes.insert(u, data);
+        }
+        Ok(Frame { time_us: u64::from_le_bytes(time), universes })
+    };
+    match read() {
+        Ok(frame) => Ok(Some(frame)),
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => Ok(None),
+        Err(e) => Err(e).context("Corrupt recording"),
+    }
+}
+
+/// Writes frames as they are output, timed from when recording started
+pub struct Recorder {
+    writer: BufWriter<File>,
+    start: Instant,
+    frames: u64,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> Result<Self> {
+        let mut writer = BufWriter::new(File::create(path).with_context(|| format!("Failed to create {}", path.display()))?);
+        writer.write_all(MAGIC)?;
+        Ok(Self { writer, start: Instant::now(), frames: 0 })
+    }
+
+    pub fn record(&mut self, universes: &UniverseFrame) -> Result<()> {
+        write_frame(&mut self.writer, self.start.elapsed().as_micros() as u64, universes)?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Frames written and seconds since recording started
+    pub fn progress(&self) -> (u64, f32) {
+        (self.frames, self.start.elapsed().as_secs_f32())
+    }
+
+    pub fn finish(mut self) -> Result<u64> {
+        self.writer.flush()?;
+        Ok(self.frames)
+    }
+}
+
+/// Streams a recording back on its original schedule, optionally looping
+pub struct Player<R = BufReader<File>> {
+    reader: R,
+    current: UniverseFrame,
+    next: Option<Frame>,
+    loop_start_us: u64, // Playback time the current pass started at
+    last_time_us: u64,  // Recording time of `current`
+    pub looping: bool,
+}
+
+impl Player {
+    pub fn open(path: &Path, looping: bool) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+        Self::new(BufReader::new(file), looping)
+    }
+}
+
+impl<R: Read + Seek> Player<R> {
+    pub fn new(mut reader: R, looping: bool) -> Result<Self> {
+        let mut magic = [0; 8];
+        if reader.read_exact(&mut magic).is_err() || &magic != MAGIC {
+            bail!("Not a Lightspeed recording");
+        }
+        let next = read_frame(&mut reader)?;
+        if next.is_none() {
+            bail!("Recording has no frames");
+        }
+        Ok(Self { reader, current: UniverseFrame::new(), next, loop_start_us: 0, last_time_us: 0, looping })
+    }
+
+    /// Output due `elapsed_us` after playback started: the latest frame whose time has come.
+    /// A pass ends once the last frame's time has passed; then a looping recording starts
+    /// over and a non-looping one returns None.
+    pub fn frame_at(&mut self, elapsed_us: u64) -> Result<Option<&UniverseFrame>> {
+        let mut rewound = false;
+        loop {
+            match self.next.take() {
+                Some(frame) if self.loop_start_us + frame.time_us <= elapsed_us => {
+                    self.last_time_us = frame.time_us;
+                    self.current = frame.universes;
+                    self.next = read_frame(&mut self.reader)?;
+                }
+                Some(frame) => {
+                    self.next = Some(frame);
+                    break;
+                }
+                None if elapsed_us <= self.loop_start_us + self.last_time_us => break,
+                // Rewind at most once per call, so a one-frame recording can't spin
+                None if self.looping && !rewound => {
+                    self.reader.seek(SeekFrom::Start(MAGIC.len() as u64))?;
+                    self.next = read_frame(&mut self.reader)?;
+                    self.loop_start_us = elapsed_us;
+                    rewound = true;
+                }
+                None if self.looping => break,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(&self.current))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn recording(frames: &[(u64, u8)]) -> Vec<u8> {
+        let mut buf = MAGIC.to_vec();
+        for &(time_us, level) in frames {
+            let universes = HashMap::from([(2, vec![level; 3]), (1, vec![level])]);
+            write_frame(&mut buf, time_us, &universes).unwrap();
+        }
+        buf
+    }
+
+    #[test]
+    fn test_frames_round_trip_and_play_on_schedule() {
+        let bytes = recording(&[(0, 10), (1000, 20), (2000, 30)]);
+        // Universes are sorted on disk: universe 1 comes first after the frame header
+        assert_eq!(&bytes[8 + 10..8 + 12], &1u16.to_le_bytes());
+
+        let mut player = Player::new(Cursor::new(bytes.clone()), false).unwrap();
+        assert_eq!(player.frame_at(0).unwrap().unwrap()[&2], vec![10; 3]);
+        assert_eq!(player.frame_at(1500).unwrap().unwrap()[&1], vec![20]);
+        // Skips straight to the latest due frame
+        let mut late = Player::new(Cursor::new(bytes.clone()), false).unwrap();
+        assert_eq!(late.frame_at(2000).unwrap().unwrap()[&1], vec![30]);
+        // Ends once the last frame's time has passed
+        assert!(late.frame_at(2001).unwrap().is_none());
+
+        // A truncated final frame is dropped
+        let mut cut = Player::new(Cursor::new(bytes[..bytes.len() - 2].to_vec()), false).unwrap();
+        assert_eq!(cut.frame_at(1000).unwrap().unwrap()[&1], vec![20]);
+        assert!(cut.frame_at(5000).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_playback_loops_from_the_end() {
+        let bytes = recording(&[(0, 10), (1000, 20)]);
+        let mut player = Player::new(Cursor::new(bytes), true).unwrap();
+        assert_eq!(player.frame_at(1000).unwrap().unwrap()[&1], vec![20]);
+        // Past the end: rewinds and starts the next pass now
+        assert_eq!(player.frame_at(1200).unwrap().unwrap()[&1], vec![10]);
+        assert_eq!(player.frame_at(2100).unwrap().unwrap()[&1], vec![10]);
+        assert_eq!(player.frame_at(2200).unwrap().unwrap()[&1], vec![20]);
+
+        assert!(Player::new(Cursor::new(b"nope".to_vec()), true).is_err());
+        assert!(Player::new(Cursor::new(MAGIC.to_vec()), true).is_err());
+    }
+}
@@ -106,16 +106,8 @@ pub fn apply_scanner_mask(
         for pixel_index in 0..pixel_limit {
             // === 1. Calculate pixel position in world space ===
 
-            // Apply direction
-            let pixel_world_x = if strip.flipped {
-                 // Flipped: Start at x + length, go left... 
-                 // OR: x + ((count-1-i) * spacing)
-                 // This effectively maps Index 0 -> Far Right, Index N -> Left (Strip X)
-                 strip.x + ((strip.pixel_count - 1).saturating_sub(pixel_index) as f32 * strip.spacing)
-            } else {
-                strip.x + (pixel_index as f32 * strip.spacing)
-            };
-            let pixel_world_y = strip.y;
+            // Flipped strips take data in at the right end: index 0 -> far right
+            let (pixel_world_x, pixel_world_y) = strip.pixel_position(pixel_index);
 
             // === 2. Transform to mask's local coordinate system ===
 
@@ -198,6 +190,12 @@ mod tests {
             spacing: 0.01, // 1cm spacing in normalized coords
             flipped,
             color_order: "RGB".to_string(),
+            lut: None,
+            dimmer_curve: "linear".to_string(),
+            channel_prefix: Vec::new(),
+            pixel_offset: 0,
+            channels_per_pixel: 3,
+            luma_weights: crate::model::default_luma_weights(),
             data: vec![[0, 0, 0]; pixel_count],
         }
     }
@@ -228,6 +226,18 @@ mod tests {
         assert_eq!(strips[0].data[0], [0, 0, 0], "Far left pixel should be dark");
         assert_eq!(strips[0].data[99], [0, 0, 0], "Far right pixel should be dark");
     }
+
+    #[test]
+    fn test_bar_edge_reaches_mask_edge() {
+        // Mask spans x=0.35..0.65; the sweep is scaled so the bar's edge lands on it
+        let mut strips = vec![create_test_strip(0.0, 0.5, false, 100)];
+        apply_scanner_mask(0.5, 0.5, 0.3, 0.2, 0.0, -1.0, 0.05, true, [0, 255, 255], &mut strips);
+
+        assert_eq!(strips[0].data[34], [0, 0, 0], "Outside the mask should be dark");
+        assert_eq!(strips[0].data[35], [0, 255, 255], "Bar should reach the left mask edge");
+        assert_eq!(strips[0].data[44], [0, 255, 255], "Full bar width should be inside the mask");
+        assert_eq!(strips[0].data[47], [0, 0, 0], "Beyond the bar should be dark");
+    }
 /*
     #[test]
     fn test_rotated_90_degrees() {